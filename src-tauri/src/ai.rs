@@ -0,0 +1,251 @@
+use crate::{secrets, store};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const AI_CONFIG_FILE: &str = "ai.json";
+const API_KEY_ACCOUNT: &str = "ai-api-key";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AiProvider {
+    /// Any server speaking the OpenAI chat completions API
+    OpenAi,
+    /// llama.cpp's built-in server (`/completion` endpoint)
+    LlamaCpp,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AiConfig {
+    /// AI features stay off until the user explicitly enables them
+    enabled: bool,
+    provider: AiProvider,
+    base_url: String,
+    model: String,
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        AiConfig {
+            enabled: false,
+            provider: AiProvider::OpenAi,
+            base_url: "https://api.openai.com/v1".to_string(),
+            model: "gpt-4o-mini".to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AiState {
+    next_id: AtomicU64,
+    cancelled: Mutex<HashSet<u64>>,
+}
+
+#[derive(Serialize, Clone)]
+struct AiChunk {
+    request_id: u64,
+    text: String,
+}
+
+#[derive(Serialize, Clone)]
+struct AiError {
+    request_id: u64,
+    message: String,
+}
+
+#[derive(Serialize, Clone)]
+struct AiDone {
+    request_id: u64,
+    cancelled: bool,
+}
+
+fn request_body(config: &AiConfig, system: &str, prompt: &str) -> (String, Value) {
+    let base = config.base_url.trim_end_matches('/');
+    match config.provider {
+        AiProvider::OpenAi => (
+            format!("{}/chat/completions", base),
+            json!({
+                "model": config.model,
+                "stream": true,
+                "messages": [
+                    { "role": "system", "content": system },
+                    { "role": "user", "content": prompt },
+                ],
+            }),
+        ),
+        AiProvider::LlamaCpp => (
+            format!("{}/completion", base),
+            json!({
+                "stream": true,
+                "prompt": format!("{}\n\n{}\n", system, prompt),
+            }),
+        ),
+    }
+}
+
+/// Extracts the text delta from one server-sent event payload.
+fn chunk_text(provider: &AiProvider, data: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(data).ok()?;
+    let text = match provider {
+        AiProvider::OpenAi => value["choices"][0]["delta"]["content"].as_str(),
+        AiProvider::LlamaCpp => value["content"].as_str(),
+    };
+    text.filter(|t| !t.is_empty()).map(|t| t.to_string())
+}
+
+fn stream_completion(
+    app: &AppHandle,
+    config: &AiConfig,
+    api_key: Option<String>,
+    request_id: u64,
+    system: &str,
+    prompt: &str,
+) -> Result<bool, String> {
+    let (url, body) = request_body(config, system, prompt);
+    let mut request = ureq::post(&url).set("Accept", "text/event-stream");
+    if let Some(key) = api_key {
+        request = request.set("Authorization", &format!("Bearer {}", key));
+    }
+
+    let response = request.send_json(body).map_err(|e| match e {
+        ureq::Error::Status(code, resp) => {
+            let detail = resp.into_string().unwrap_or_default();
+            crate::i18n::tf(
+                "error.ai_provider",
+                &[("status", &code.to_string()), ("detail", &detail)],
+            )
+        }
+        other => other.to_string(),
+    })?;
+
+    let state = app.state::<AiState>();
+    for line in BufReader::new(response.into_reader()).lines() {
+        if state.cancelled.lock().unwrap().remove(&request_id) {
+            return Ok(true);
+        }
+        let line = line.map_err(|e| e.to_string())?;
+        let Some(data) = line.strip_prefix("data:") else {
+            continue;
+        };
+        let data = data.trim();
+        if data == "[DONE]" {
+            break;
+        }
+        if let Some(text) = chunk_text(&config.provider, data) {
+            let _ = app.emit("ai-chunk", AiChunk { request_id, text });
+        }
+    }
+
+    // Drop a cancel request that arrived after the stream finished
+    state.cancelled.lock().unwrap().remove(&request_id);
+    Ok(false)
+}
+
+fn start_request(
+    app: AppHandle,
+    state: &AiState,
+    system: &'static str,
+    prompt: String,
+) -> Result<u64, String> {
+    let config: AiConfig = store::load_json(&app, AI_CONFIG_FILE);
+    if !config.enabled {
+        return Err(crate::i18n::t("error.ai_disabled"));
+    }
+    let api_key = secrets::get_secret(API_KEY_ACCOUNT)?;
+    if config.provider == AiProvider::OpenAi && api_key.is_none() {
+        return Err(crate::i18n::t("error.no_api_key"));
+    }
+
+    let request_id = state.next_id.fetch_add(1, Ordering::SeqCst);
+    std::thread::spawn(move || {
+        match stream_completion(&app, &config, api_key, request_id, system, &prompt) {
+            Ok(cancelled) => {
+                let _ = app.emit(
+                    "ai-done",
+                    AiDone {
+                        request_id,
+                        cancelled,
+                    },
+                );
+            }
+            Err(message) => {
+                let _ = app.emit(
+                    "ai-error",
+                    AiError {
+                        request_id,
+                        message,
+                    },
+                );
+            }
+        }
+    });
+
+    Ok(request_id)
+}
+
+#[tauri::command]
+pub fn get_ai_config(app: AppHandle) -> AiConfig {
+    store::load_json(&app, AI_CONFIG_FILE)
+}
+
+#[tauri::command]
+pub fn save_ai_config(app: AppHandle, config: AiConfig) -> Result<(), String> {
+    store::save_json(&app, AI_CONFIG_FILE, &config)
+}
+
+#[tauri::command]
+pub fn has_ai_api_key() -> Result<bool, String> {
+    Ok(secrets::get_secret(API_KEY_ACCOUNT)?.is_some())
+}
+
+#[tauri::command]
+pub fn set_ai_api_key(key: Option<String>) -> Result<(), String> {
+    match key.filter(|k| !k.trim().is_empty()) {
+        Some(key) => secrets::set_secret(API_KEY_ACCOUNT, key.trim()),
+        None => secrets::delete_secret(API_KEY_ACCOUNT),
+    }
+}
+
+/// Streams a summary of the note via `ai-chunk` events; returns the request id.
+#[tauri::command]
+pub fn ai_summarize(
+    app: AppHandle,
+    state: State<'_, AiState>,
+    path: String,
+) -> Result<u64, String> {
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    start_request(
+        app,
+        &state,
+        "Summarize the following markdown note in a few concise bullet points. \
+         Reply in markdown.",
+        content,
+    )
+}
+
+#[tauri::command]
+pub fn ai_rewrite(
+    app: AppHandle,
+    state: State<'_, AiState>,
+    selection: String,
+    instruction: String,
+) -> Result<u64, String> {
+    start_request(
+        app,
+        &state,
+        "Rewrite the text given by the user according to their instruction. \
+         Reply with the rewritten text only, keeping markdown formatting.",
+        format!("Instruction: {}\n\nText:\n{}", instruction, selection),
+    )
+}
+
+#[tauri::command]
+pub fn ai_cancel(state: State<'_, AiState>, request_id: u64) {
+    state.cancelled.lock().unwrap().insert(request_id);
+}
@@ -0,0 +1,222 @@
+use crate::blocks::{self, AnchoredBlock};
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Highlight {
+    id: String,
+    /// Stable block anchor from `get_block_anchors`
+    anchor: String,
+    /// Character offsets within the block's text
+    start: usize,
+    end: usize,
+    text: String,
+    color: Option<String>,
+    comment: Option<String>,
+    created_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Bookmark {
+    id: String,
+    anchor: String,
+    /// Line at the time of bookmarking, used as a fallback when the anchor is gone
+    line: usize,
+    label: Option<String>,
+    created_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct NoteAnnotations {
+    path: String,
+    highlights: Vec<Highlight>,
+    bookmarks: Vec<Bookmark>,
+}
+
+#[derive(Deserialize)]
+pub struct NewHighlight {
+    anchor: String,
+    start: usize,
+    end: usize,
+    text: String,
+    color: Option<String>,
+    comment: Option<String>,
+}
+
+fn annotations_file(app: &AppHandle, path: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("annotations");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(format!("{:016x}.json", blocks::fnv1a(path.as_bytes()))))
+}
+
+fn load(app: &AppHandle, path: &str) -> Result<NoteAnnotations, String> {
+    let file = annotations_file(app, path)?;
+    let annotations = fs::read_to_string(file)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(|| NoteAnnotations {
+            path: path.to_string(),
+            ..NoteAnnotations::default()
+        });
+    Ok(annotations)
+}
+
+fn save(app: &AppHandle, annotations: &NoteAnnotations) -> Result<(), String> {
+    let file = annotations_file(app, &annotations.path)?;
+    if annotations.highlights.is_empty() && annotations.bookmarks.is_empty() {
+        let _ = fs::remove_file(file);
+        return Ok(());
+    }
+    let content = serde_json::to_string_pretty(annotations).map_err(|e| e.to_string())?;
+    store::write_atomic(&file, content)
+}
+
+fn new_id() -> String {
+    format!(
+        "{:x}",
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
+    )
+}
+
+#[tauri::command]
+pub fn get_block_anchors(content: String) -> Vec<AnchoredBlock> {
+    blocks::block_anchors(&content)
+}
+
+#[tauri::command]
+pub fn get_annotations(app: AppHandle, path: String) -> Result<NoteAnnotations, String> {
+    load(&app, &path)
+}
+
+#[tauri::command]
+pub fn add_highlight(
+    app: AppHandle,
+    path: String,
+    highlight: NewHighlight,
+) -> Result<Highlight, String> {
+    let mut annotations = load(&app, &path)?;
+    let highlight = Highlight {
+        id: new_id(),
+        anchor: highlight.anchor,
+        start: highlight.start,
+        end: highlight.end,
+        text: highlight.text,
+        color: highlight.color,
+        comment: highlight.comment,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    annotations.highlights.push(highlight.clone());
+    save(&app, &annotations)?;
+    Ok(highlight)
+}
+
+#[tauri::command]
+pub fn update_highlight(
+    app: AppHandle,
+    path: String,
+    id: String,
+    color: Option<String>,
+    comment: Option<String>,
+) -> Result<(), String> {
+    let mut annotations = load(&app, &path)?;
+    let highlight = annotations
+        .highlights
+        .iter_mut()
+        .find(|h| h.id == id)
+        .ok_or_else(|| crate::i18n::t("error.highlight_not_found"))?;
+    highlight.color = color;
+    highlight.comment = comment;
+    save(&app, &annotations)
+}
+
+#[tauri::command]
+pub fn remove_highlight(app: AppHandle, path: String, id: String) -> Result<(), String> {
+    let mut annotations = load(&app, &path)?;
+    annotations.highlights.retain(|h| h.id != id);
+    save(&app, &annotations)
+}
+
+#[tauri::command]
+pub fn add_bookmark(
+    app: AppHandle,
+    path: String,
+    anchor: String,
+    line: usize,
+    label: Option<String>,
+) -> Result<Bookmark, String> {
+    let mut annotations = load(&app, &path)?;
+    let bookmark = Bookmark {
+        id: new_id(),
+        anchor,
+        line,
+        label,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    annotations.bookmarks.push(bookmark.clone());
+    save(&app, &annotations)?;
+    Ok(bookmark)
+}
+
+#[tauri::command]
+pub fn remove_bookmark(app: AppHandle, path: String, id: String) -> Result<(), String> {
+    let mut annotations = load(&app, &path)?;
+    annotations.bookmarks.retain(|b| b.id != id);
+    save(&app, &annotations)
+}
+
+/// Writes the note's highlights, in document order, to a new markdown note
+/// (default: "<note> highlights.md" next to the original) and returns its path.
+#[tauri::command]
+pub fn export_highlights(
+    app: AppHandle,
+    path: String,
+    out_path: Option<String>,
+) -> Result<String, String> {
+    let annotations = load(&app, &path)?;
+    if annotations.highlights.is_empty() {
+        return Err(crate::i18n::t("error.no_highlights"));
+    }
+
+    let note = Path::new(&path);
+    let stem = note.file_stem().unwrap_or_default().to_string_lossy();
+    let content = fs::read_to_string(note).unwrap_or_default();
+    let order: Vec<String> = blocks::block_anchors(&content)
+        .into_iter()
+        .map(|b| b.anchor)
+        .collect();
+
+    let mut highlights = annotations.highlights.clone();
+    highlights.sort_by_key(|h| {
+        (
+            order
+                .iter()
+                .position(|a| *a == h.anchor)
+                .unwrap_or(usize::MAX),
+            h.start,
+        )
+    });
+
+    let mut summary = format!("# Highlights from [[{}]]\n", stem);
+    for highlight in &highlights {
+        summary.push('\n');
+        for line in highlight.text.lines() {
+            summary.push_str(&format!("> {}\n", line));
+        }
+        if let Some(comment) = highlight.comment.as_deref().filter(|c| !c.is_empty()) {
+            summary.push_str(&format!("\n{}\n", comment));
+        }
+    }
+
+    let out = out_path
+        .map(PathBuf::from)
+        .unwrap_or_else(|| note.with_file_name(format!("{} highlights.md", stem)));
+    store::write_atomic(&out, summary)?;
+    Ok(out.to_string_lossy().to_string())
+}
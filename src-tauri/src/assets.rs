@@ -0,0 +1,69 @@
+use crate::index::VaultIndex;
+use regex::{Captures, Regex};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// The URL the webview loads a local file through, matching the frontend's
+/// `convertFileSrc`.
+pub fn asset_url(path: &Path) -> String {
+    let encoded = urlencoding::encode(&path.to_string_lossy()).into_owned();
+    if cfg!(windows) {
+        format!("http://asset.localhost/{}", encoded)
+    } else {
+        format!("asset://localhost/{}", encoded)
+    }
+}
+
+fn is_external(src: &str) -> bool {
+    static SCHEME: OnceLock<Regex> = OnceLock::new();
+    let scheme_re = SCHEME.get_or_init(|| Regex::new(r"^[A-Za-z][A-Za-z0-9+.-]+:").unwrap());
+    src.is_empty() || src.starts_with('#') || src.starts_with("//") || scheme_re.is_match(src)
+}
+
+fn resolve(src: &str, base: &Path, links: Option<&VaultIndex>, from: &Path) -> Option<PathBuf> {
+    let decoded = urlencoding::decode(&src.replace("&amp;", "&"))
+        .map(|s| s.into_owned())
+        .unwrap_or_else(|_| src.to_string());
+    let path = Path::new(&decoded);
+    if path.is_absolute() {
+        return Some(path.to_path_buf());
+    }
+    let relative = base.join(path);
+    if relative.exists() {
+        return Some(relative);
+    }
+    // Obsidian embeds name attachments anywhere in the vault
+    links
+        .and_then(|index| index.resolve(&decoded, from))
+        .or(Some(relative))
+}
+
+/// Rewrites relative `src` attributes (and PDF embed paths) against the
+/// note's folder so assets load no matter where the note lives.
+pub fn resolve_asset_paths(
+    html: &str,
+    base: &Path,
+    links: Option<&VaultIndex>,
+    from: &Path,
+) -> String {
+    static SRC: OnceLock<Regex> = OnceLock::new();
+    let re = SRC.get_or_init(|| Regex::new(r#"\b(src|data-pdf)="([^"]*)""#).unwrap());
+
+    re.replace_all(html, |caps: &Captures| {
+        let (attr, src) = (&caps[1], &caps[2]);
+        if is_external(src) {
+            return caps[0].to_string();
+        }
+        match resolve(src, base, links, from) {
+            // render_pdf_page takes a filesystem path, not a URL
+            Some(path) if attr == "data-pdf" => format!(
+                "{}=\"{}\"",
+                attr,
+                crate::escape_attr(&path.to_string_lossy())
+            ),
+            Some(path) => format!("{}=\"{}\"", attr, asset_url(&path)),
+            None => caps[0].to_string(),
+        }
+    })
+    .into_owned()
+}
@@ -0,0 +1,168 @@
+use crate::store;
+use crate::vault_config;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const ATTACHMENTS_FILE: &str = "attachments.json";
+
+/// Where new attachments for a note are put, as in Obsidian's
+/// "Default location for new attachments".
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum AttachmentLocation {
+    VaultRoot,
+    /// A fixed folder, relative to the vault root
+    VaultFolder {
+        folder: String,
+    },
+    /// The note's own folder
+    SameFolder,
+    /// A subfolder of the note's folder, e.g. `attachments`
+    Subfolder {
+        folder: String,
+    },
+    /// A folder next to the note, named after it (`Note.md` -> `Note/`)
+    NoteNamed,
+}
+
+impl Default for AttachmentLocation {
+    fn default() -> Self {
+        AttachmentLocation::Subfolder {
+            folder: "attachments".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Attachment {
+    pub path: String,
+    /// `![[...]]`, ready to insert at the cursor
+    pub embed: String,
+}
+
+/// Attachment locations keyed by vault root.
+type AttachmentStore = HashMap<String, AttachmentLocation>;
+
+/// The vault `note` belongs to: the stored root that contains it, or its folder.
+fn vault_for(store: &AttachmentStore, note: &Path) -> Option<(PathBuf, AttachmentLocation)> {
+    store
+        .iter()
+        .filter(|(root, _)| note.starts_with(root))
+        .max_by_key(|(root, _)| root.len())
+        .map(|(root, location)| (PathBuf::from(root), location.clone()))
+}
+
+/// The folder new attachments of `note_path` go into, following the setting
+/// of its vault; a `.marko/config.json` location takes precedence.
+/// `vault_root` overrides the vault lookup.
+pub fn attachment_dir(app: &AppHandle, note_path: &Path, vault_root: Option<&Path>) -> PathBuf {
+    let store: AttachmentStore = store::load_json(app, ATTACHMENTS_FILE);
+    let note_dir = note_path.parent().unwrap_or(Path::new("")).to_path_buf();
+    let (root, location) = match vault_root {
+        Some(root) => (
+            root.to_path_buf(),
+            store
+                .get(&*root.to_string_lossy())
+                .cloned()
+                .unwrap_or_default(),
+        ),
+        None => vault_config::for_path(note_path)
+            .and_then(|(root, config)| Some((root, config.attachment_location?)))
+            .or_else(|| vault_for(&store, note_path))
+            .unwrap_or_else(|| (note_dir.clone(), AttachmentLocation::default())),
+    };
+
+    match location {
+        AttachmentLocation::VaultRoot => root,
+        AttachmentLocation::VaultFolder { folder } => root.join(folder),
+        AttachmentLocation::SameFolder => note_dir,
+        AttachmentLocation::Subfolder { folder } => note_dir.join(folder),
+        AttachmentLocation::NoteNamed => {
+            let stem = note_path.file_stem().unwrap_or_default();
+            note_dir.join(stem)
+        }
+    }
+}
+
+/// `![[...]]` embed for an attachment: the path relative to the note when it
+/// is below the note's folder, otherwise the file name alone, which wikilink
+/// resolution finds anywhere in the vault.
+pub fn embed_for(note_path: &Path, attachment: &Path) -> String {
+    let note_dir = note_path.parent().unwrap_or(Path::new(""));
+    let target = match attachment.strip_prefix(note_dir) {
+        Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+        Err(_) => attachment
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+    };
+    format!("![[{}]]", target)
+}
+
+#[tauri::command]
+pub fn get_attachment_location(app: AppHandle, vault_root: String) -> AttachmentLocation {
+    let store: AttachmentStore = store::load_json(&app, ATTACHMENTS_FILE);
+    store.get(&vault_root).cloned().unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn set_attachment_location(
+    app: AppHandle,
+    vault_root: String,
+    location: AttachmentLocation,
+) -> Result<(), String> {
+    let mut store: AttachmentStore = store::load_json(&app, ATTACHMENTS_FILE);
+    store.insert(vault_root, location);
+    store::save_json(&app, ATTACHMENTS_FILE, &store)
+}
+
+/// Where an attachment pasted or imported into `note_path` would be saved.
+#[tauri::command]
+pub fn get_attachment_folder(
+    app: AppHandle,
+    note_path: String,
+    vault_root: Option<String>,
+) -> String {
+    attachment_dir(
+        &app,
+        Path::new(&note_path),
+        vault_root.as_deref().map(Path::new),
+    )
+    .to_string_lossy()
+    .to_string()
+}
+
+/// Copies `source` into the attachment folder of `note_path`, renaming it if
+/// the name is taken, e.g. for files dropped onto the editor.
+#[tauri::command]
+pub fn import_attachment(
+    app: AppHandle,
+    note_path: String,
+    source: String,
+) -> Result<Attachment, String> {
+    let source = Path::new(&source);
+    let dir = attachment_dir(&app, Path::new(&note_path), None);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = source
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut target = dir.join(format!("{}{}", stem, extension));
+    let mut n = 1;
+    while target.exists() {
+        target = dir.join(format!("{} {}{}", stem, n, extension));
+        n += 1;
+    }
+    fs::copy(source, &target).map_err(|e| e.to_string())?;
+
+    Ok(Attachment {
+        path: target.to_string_lossy().to_string(),
+        embed: embed_for(Path::new(&note_path), &target),
+    })
+}
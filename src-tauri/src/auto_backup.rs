@@ -0,0 +1,162 @@
+use crate::{backup, store};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const AUTO_BACKUP_FILE: &str = "auto_backup.json";
+/// How often the loop checks whether a backup is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// How long to wait before trying again after a failed backup
+const RETRY_SECS: u64 = 60 * 60;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupFrequency {
+    Daily,
+    Weekly,
+}
+
+impl BackupFrequency {
+    fn seconds(self) -> u64 {
+        match self {
+            BackupFrequency::Daily => 24 * 60 * 60,
+            BackupFrequency::Weekly => 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AutoBackupConfig {
+    enabled: bool,
+    /// Vault to back up
+    root: Option<String>,
+    frequency: BackupFrequency,
+    /// Number of backups of the vault to keep
+    keep: usize,
+}
+
+impl Default for AutoBackupConfig {
+    fn default() -> Self {
+        AutoBackupConfig {
+            enabled: false,
+            root: None,
+            frequency: BackupFrequency::Daily,
+            keep: 7,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct BackupEvent {
+    root: String,
+    backup: Option<String>,
+    error: Option<String>,
+}
+
+/// Bumped whenever the schedule changes; a running loop exits once it sees
+/// a newer generation.
+#[derive(Default)]
+pub struct AutoBackupState {
+    generation: AtomicU64,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn run_backup(app: &AppHandle, root: &str, keep: usize) -> bool {
+    let result = backup::default_dir(app)
+        .and_then(|dir| backup::create_backup(app, Path::new(root), &dir).map(|path| (dir, path)));
+    let event = match result {
+        Ok((dir, path)) => {
+            backup::prune(&dir, root, keep.max(1));
+            BackupEvent {
+                root: root.to_string(),
+                backup: Some(path.to_string_lossy().to_string()),
+                error: None,
+            }
+        }
+        Err(error) => BackupEvent {
+            root: root.to_string(),
+            backup: None,
+            error: Some(error),
+        },
+    };
+    let ok = event.error.is_none();
+    let name = if ok {
+        "auto-backup-finished"
+    } else {
+        "auto-backup-error"
+    };
+    let _ = app.emit(name, event);
+    ok
+}
+
+fn start(app: &AppHandle, state: &AutoBackupState, config: &AutoBackupConfig) {
+    let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let Some(root) = config.root.clone().filter(|_| config.enabled) else {
+        return;
+    };
+    let period = config.frequency.seconds();
+    let keep = config.keep;
+    let app = app.clone();
+
+    thread::spawn(move || {
+        let current = || {
+            app.state::<AutoBackupState>()
+                .generation
+                .load(Ordering::SeqCst)
+        };
+        // The schedule survives restarts: the newest backup on disk says
+        // when the last one ran
+        let due = || {
+            let last = backup::default_dir(&app)
+                .ok()
+                .and_then(|dir| backup::last_backup_at(&dir, &root))
+                .unwrap_or(0);
+            now_secs().saturating_sub(last) >= period
+        };
+        let mut retry_at = 0;
+        while current() == generation {
+            if now_secs() >= retry_at && due() && !run_backup(&app, &root, keep) {
+                retry_at = now_secs() + RETRY_SECS;
+            }
+            let mut waited = Duration::ZERO;
+            while waited < CHECK_INTERVAL && current() == generation {
+                thread::sleep(Duration::from_secs(1));
+                waited += Duration::from_secs(1);
+            }
+        }
+    });
+}
+
+/// Resumes the saved schedule at startup.
+pub fn resume(app: &AppHandle) {
+    let config: AutoBackupConfig = store::load_json(app, AUTO_BACKUP_FILE);
+    start(app, &app.state::<AutoBackupState>(), &config);
+}
+
+#[tauri::command]
+pub fn get_auto_backup(app: AppHandle) -> AutoBackupConfig {
+    store::load_json(&app, AUTO_BACKUP_FILE)
+}
+
+/// Saves the schedule and restarts it. Backups go to the app's backup folder;
+/// each run emits "auto-backup-finished" or "auto-backup-error".
+#[tauri::command]
+pub fn set_auto_backup(
+    app: AppHandle,
+    state: State<'_, AutoBackupState>,
+    config: AutoBackupConfig,
+) -> Result<(), String> {
+    store::save_json(&app, AUTO_BACKUP_FILE, &config)?;
+    start(&app, &state, &config);
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use crate::{git, store};
+use serde::{Deserialize, Serialize};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const AUTO_SYNC_FILE: &str = "auto_sync.json";
+/// How often to check whether the remote is reachable again after a failure
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AutoSyncConfig {
+    enabled: bool,
+    /// Repository (or any folder inside it) to sync
+    path: Option<String>,
+    interval_minutes: u64,
+}
+
+impl Default for AutoSyncConfig {
+    fn default() -> Self {
+        AutoSyncConfig {
+            enabled: false,
+            path: None,
+            interval_minutes: 15,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct SyncEvent {
+    path: String,
+    message: Option<String>,
+    error: Option<String>,
+}
+
+/// Bumped whenever the schedule changes; a running loop exits once it sees
+/// a newer generation.
+#[derive(Default)]
+pub struct AutoSyncState {
+    generation: AtomicU64,
+}
+
+/// Whether a TCP connection to the remote's host can be opened. Local and
+/// unrecognised remotes count as reachable.
+fn remote_reachable(url: &str) -> bool {
+    let (rest, default_port) = if let Some(rest) = url.strip_prefix("https://") {
+        (rest, 443)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        (rest, 80)
+    } else if let Some(rest) = url.strip_prefix("ssh://") {
+        (rest, 22)
+    } else if url.contains(':') && !url.contains("://") {
+        // scp-style `git@host:owner/repo`
+        (url.split(':').next().unwrap_or_default(), 22)
+    } else {
+        return true;
+    };
+    let host_port = rest.split('/').next().unwrap_or_default();
+    let host_port = host_port.rsplit('@').next().unwrap_or_default();
+    let address = if host_port.contains(':') {
+        host_port.to_string()
+    } else {
+        format!("{}:{}", host_port, default_port)
+    };
+    address
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .is_some_and(|addr| TcpStream::connect_timeout(&addr, Duration::from_secs(5)).is_ok())
+}
+
+fn run_sync(app: &AppHandle, path: &str) -> bool {
+    let event = |message, error| SyncEvent {
+        path: path.to_string(),
+        message,
+        error,
+    };
+    let _ = app.emit("sync-started", event(None, None));
+    match git::sync(app, path) {
+        Ok(message) => {
+            let _ = app.emit("sync-finished", event(Some(message), None));
+            true
+        }
+        Err(error) => {
+            let _ = app.emit("sync-error", event(None, Some(error)));
+            false
+        }
+    }
+}
+
+fn start(app: &AppHandle, state: &AutoSyncState, config: &AutoSyncConfig) {
+    let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let Some(path) = config.path.clone().filter(|_| config.enabled) else {
+        return;
+    };
+    let interval = Duration::from_secs(config.interval_minutes.max(1) * 60);
+    let app = app.clone();
+
+    thread::spawn(move || {
+        let current = || {
+            app.state::<AutoSyncState>()
+                .generation
+                .load(Ordering::SeqCst)
+        };
+        let reachable = || match git::sync_remote_url(&path) {
+            Some(url) => remote_reachable(&url),
+            None => true,
+        };
+        let mut next = Instant::now() + interval;
+        let mut offline = false;
+        while current() == generation {
+            thread::sleep(Duration::from_secs(1));
+            if Instant::now() < next {
+                continue;
+            }
+            // While offline, poll the remote and sync as soon as it answers
+            if offline && !reachable() {
+                next = Instant::now() + RETRY_INTERVAL;
+                continue;
+            }
+            offline = !run_sync(&app, &path) && !reachable();
+            next = Instant::now() + if offline { RETRY_INTERVAL } else { interval };
+        }
+    });
+}
+
+/// Resumes the saved schedule at startup.
+pub fn resume(app: &AppHandle) {
+    let config: AutoSyncConfig = store::load_json(app, AUTO_SYNC_FILE);
+    start(app, &app.state::<AutoSyncState>(), &config);
+}
+
+#[tauri::command]
+pub fn get_auto_sync(app: AppHandle) -> AutoSyncConfig {
+    store::load_json(&app, AUTO_SYNC_FILE)
+}
+
+/// Saves the schedule and restarts it. Emits "sync-started", then
+/// "sync-finished" or "sync-error" around each background sync.
+#[tauri::command]
+pub fn set_auto_sync(
+    app: AppHandle,
+    state: State<'_, AutoSyncState>,
+    config: AutoSyncConfig,
+) -> Result<(), String> {
+    store::save_json(&app, AUTO_SYNC_FILE, &config)?;
+    start(&app, &state, &config);
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use crate::{encoding, history, store};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const AUTOSAVE_FILE: &str = "autosave.json";
+const TICK: Duration = Duration::from_millis(100);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AutosaveSettings {
+    /// Milliseconds without edits before a buffer is written
+    idle_ms: u64,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        AutosaveSettings { idle_ms: 1000 }
+    }
+}
+
+struct Pending {
+    content: String,
+    due: Instant,
+}
+
+#[derive(Serialize, Clone)]
+struct AutosaveEvent {
+    path: String,
+    error: Option<String>,
+}
+
+/// Unsaved buffers streamed from the editor, written once they have been
+/// idle long enough, so edits survive a webview crash.
+#[derive(Default)]
+pub struct AutosaveState {
+    pending: Mutex<HashMap<String, Pending>>,
+    settings: Mutex<Option<AutosaveSettings>>,
+    worker_started: AtomicBool,
+}
+
+impl AutosaveState {
+    fn idle(&self, app: &AppHandle) -> Duration {
+        let mut settings = self.settings.lock().unwrap();
+        let settings = settings.get_or_insert_with(|| store::load_json(app, AUTOSAVE_FILE));
+        Duration::from_millis(settings.idle_ms)
+    }
+
+    fn take(&self, due_only: bool) -> Vec<(String, String)> {
+        let now = Instant::now();
+        let mut pending = self.pending.lock().unwrap();
+        let ready: Vec<String> = pending
+            .iter()
+            .filter(|(_, p)| !due_only || p.due <= now)
+            .map(|(path, _)| path.clone())
+            .collect();
+        ready
+            .into_iter()
+            .filter_map(|path| pending.remove(&path).map(|p| (path, p.content)))
+            .collect()
+    }
+
+    /// Drops pending buffers at or under a deleted path, so writing them
+    /// doesn't bring it back.
+    pub fn forget(&self, path: &Path) {
+        self.pending
+            .lock()
+            .unwrap()
+            .retain(|p, _| !Path::new(p).starts_with(path));
+    }
+
+    /// Re-keys pending buffers at or under a renamed file or folder, so they
+    /// are written to the new location.
+    pub fn moved(&self, old: &Path, new: &Path) {
+        let mut pending = self.pending.lock().unwrap();
+        let moved: Vec<String> = pending
+            .keys()
+            .filter(|p| Path::new(p).starts_with(old))
+            .cloned()
+            .collect();
+        for path in moved {
+            let Some(entry) = pending.remove(&path) else {
+                continue;
+            };
+            let to = match Path::new(&path).strip_prefix(old) {
+                Ok(relative) if !relative.as_os_str().is_empty() => new.join(relative),
+                _ => new.to_path_buf(),
+            };
+            pending.insert(to.to_string_lossy().to_string(), entry);
+        }
+    }
+
+    fn ensure_worker(&self, app: &AppHandle) {
+        if self.worker_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let app = app.clone();
+        thread::spawn(move || loop {
+            thread::sleep(TICK);
+            for (path, content) in app.state::<AutosaveState>().take(true) {
+                write(&app, &path, &content);
+            }
+        });
+    }
+}
+
+fn write(app: &AppHandle, path: &str, content: &str) {
+    // Moved or deleted since the edit; writing would recreate it
+    if !Path::new(path).exists() {
+        return;
+    }
+    let error = match encoding::encode_for(app, path, content) {
+        // Unchanged buffers don't need a write (or a watcher event)
+        Ok(bytes) if fs::read(path).is_ok_and(|current| current == bytes) => return,
+        Ok(bytes) => store::write_atomic(Path::new(path), &bytes)
+            .map(|()| {
+                let _ = history::snapshot(app, path, &bytes);
+            })
+            .err(),
+        Err(e) => Some(e),
+    };
+    let event = if error.is_some() {
+        "autosave-error"
+    } else {
+        "autosave-saved"
+    };
+    let _ = app.emit(
+        event,
+        AutosaveEvent {
+            path: path.to_string(),
+            error,
+        },
+    );
+}
+
+/// Records the latest content of an open buffer. It is written after the
+/// configured idle time, emitting "autosave-saved" or "autosave-error".
+#[tauri::command]
+pub fn autosave_update(
+    app: AppHandle,
+    state: State<'_, AutosaveState>,
+    path: String,
+    content: String,
+) {
+    let due = Instant::now() + state.idle(&app);
+    state
+        .pending
+        .lock()
+        .unwrap()
+        .insert(path, Pending { content, due });
+    state.ensure_worker(&app);
+}
+
+/// Writes pending buffers now, e.g. before closing a tab or the window.
+/// Without `path`, every pending buffer is written.
+#[tauri::command]
+pub fn autosave_flush(app: AppHandle, state: State<'_, AutosaveState>, path: Option<String>) {
+    let pending = match path {
+        Some(path) => state
+            .pending
+            .lock()
+            .unwrap()
+            .remove(&path)
+            .map(|p| vec![(path, p.content)])
+            .unwrap_or_default(),
+        None => state.take(false),
+    };
+    for (path, content) in pending {
+        write(&app, &path, &content);
+    }
+}
+
+/// Drops a pending buffer without writing it, e.g. when changes are discarded.
+#[tauri::command]
+pub fn autosave_cancel(state: State<'_, AutosaveState>, path: String) {
+    state.pending.lock().unwrap().remove(&path);
+}
+
+#[tauri::command]
+pub fn get_autosave_settings(app: AppHandle, state: State<'_, AutosaveState>) -> AutosaveSettings {
+    state
+        .settings
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| store::load_json(&app, AUTOSAVE_FILE))
+        .clone()
+}
+
+#[tauri::command]
+pub fn set_autosave_settings(
+    app: AppHandle,
+    state: State<'_, AutosaveState>,
+    settings: AutosaveSettings,
+) -> Result<(), String> {
+    store::save_json(&app, AUTOSAVE_FILE, &settings)?;
+    *state.settings.lock().unwrap() = Some(settings);
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use crate::vault;
+use chrono::{Datelike, Timelike};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime, ZipArchive, ZipWriter};
+
+#[derive(Serialize)]
+pub struct BackupInfo {
+    path: String,
+    name: String,
+    size: u64,
+    /// Seconds since the epoch
+    created_at: u64,
+    /// The vault the backup was taken of, from the archive comment
+    root: Option<String>,
+    file_count: usize,
+}
+
+#[derive(Serialize, Clone)]
+struct BackupProgress {
+    root: String,
+    done: usize,
+    total: usize,
+}
+
+/// Where backups go unless a destination is given.
+pub fn default_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("backups"))
+}
+
+fn zip_time(path: &Path) -> Option<DateTime> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let local: chrono::DateTime<chrono::Local> = modified.into();
+    DateTime::from_date_and_time(
+        local.year().try_into().ok()?,
+        local.month() as u8,
+        local.day() as u8,
+        local.hour() as u8,
+        local.minute() as u8,
+        local.second() as u8,
+    )
+    .ok()
+}
+
+fn info(path: &Path) -> Option<BackupInfo> {
+    let meta = fs::metadata(path).ok()?;
+    let archive = ZipArchive::new(File::open(path).ok()?).ok()?;
+    let comment = String::from_utf8_lossy(archive.comment()).to_string();
+    Some(BackupInfo {
+        path: path.to_string_lossy().to_string(),
+        name: path.file_name()?.to_string_lossy().to_string(),
+        size: meta.len(),
+        created_at: meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        root: (!comment.is_empty()).then_some(comment),
+        file_count: archive.len(),
+    })
+}
+
+/// Zips every file of the vault that isn't hidden or ignored into
+/// `dest_dir/<vault> <timestamp>.zip`, emitting "backup-progress".
+pub fn create_backup(app: &AppHandle, root: &Path, dest_dir: &Path) -> Result<PathBuf, String> {
+    if !root.is_dir() {
+        return Err(crate::i18n::t("error.not_a_directory"));
+    }
+    fs::create_dir_all(dest_dir).map_err(|e| e.to_string())?;
+    let vault_name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "vault".to_string());
+    let stamp = chrono::Local::now().format("%Y-%m-%d %H%M%S");
+    let target = dest_dir.join(format!("{} {}.zip", vault_name, stamp));
+    // Written under a temporary name so a half-written archive is never listed
+    let partial = target.with_extension("zip.part");
+
+    // Earlier backups kept inside the vault aren't backed up again
+    let files: Vec<PathBuf> = vault::walk_files(root)
+        .into_iter()
+        .filter(|f| !f.starts_with(dest_dir))
+        .collect();
+    let total = files.len();
+    let result = (|| -> Result<(), String> {
+        let mut zip = ZipWriter::new(File::create(&partial).map_err(|e| e.to_string())?);
+        zip.set_comment(root.to_string_lossy());
+        let mut last_emit = Instant::now();
+        for (i, file) in files.iter().enumerate() {
+            let Ok(relative) = file.strip_prefix(root) else {
+                continue;
+            };
+            let name = relative.to_string_lossy().replace('\\', "/");
+            let mut options = SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Deflated)
+                .large_file(fs::metadata(file).is_ok_and(|m| m.len() >= u32::MAX as u64));
+            if let Some(time) = zip_time(file) {
+                options = options.last_modified_time(time);
+            }
+            // Files can vanish while walking a live vault; skip them
+            let Ok(mut source) = File::open(file) else {
+                continue;
+            };
+            zip.start_file(name, options).map_err(|e| e.to_string())?;
+            io::copy(&mut source, &mut zip).map_err(|e| e.to_string())?;
+
+            let done = i + 1;
+            if done == total || last_emit.elapsed() >= Duration::from_millis(100) {
+                last_emit = Instant::now();
+                let _ = app.emit(
+                    "backup-progress",
+                    BackupProgress {
+                        root: root.to_string_lossy().to_string(),
+                        done,
+                        total,
+                    },
+                );
+            }
+        }
+        zip.finish().map_err(|e| e.to_string())?;
+        fs::rename(&partial, &target).map_err(|e| e.to_string())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result.map(|()| target)
+}
+
+/// Backups in `dir`, newest first.
+pub fn backups_in(dir: &Path) -> Vec<BackupInfo> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<BackupInfo> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "zip"))
+        .filter_map(|p| info(&p))
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.name.cmp(&a.name)));
+    backups
+}
+
+/// When the newest backup of `root` in `dir` was taken.
+pub fn last_backup_at(dir: &Path, root: &str) -> Option<u64> {
+    backups_in(dir)
+        .into_iter()
+        .find(|b| b.root.as_deref() == Some(root))
+        .map(|b| b.created_at)
+}
+
+/// Deletes all but the `keep` newest backups of `root` in `dir`.
+pub fn prune(dir: &Path, root: &str, keep: usize) {
+    for old in backups_in(dir)
+        .into_iter()
+        .filter(|b| b.root.as_deref() == Some(root))
+        .skip(keep)
+    {
+        let _ = fs::remove_file(old.path);
+    }
+}
+
+/// Zips the vault into `dest` (a folder; defaults to the app's backup
+/// folder), skipping hidden and ignored files.
+#[tauri::command]
+pub async fn backup_vault(
+    app: AppHandle,
+    root: String,
+    dest: Option<String>,
+) -> Result<BackupInfo, String> {
+    let dest = match dest {
+        Some(dest) => PathBuf::from(dest),
+        None => default_dir(&app)?,
+    };
+    let path = create_backup(&app, Path::new(&root), &dest)?;
+    info(&path).ok_or_else(|| crate::i18n::t("error.backup_unreadable"))
+}
+
+/// Backups in `dest` (defaults to the app's backup folder), newest first,
+/// optionally only those taken of `root`.
+#[tauri::command]
+pub fn list_backups(
+    app: AppHandle,
+    dest: Option<String>,
+    root: Option<String>,
+) -> Result<Vec<BackupInfo>, String> {
+    let dir = match dest {
+        Some(dest) => PathBuf::from(dest),
+        None => default_dir(&app)?,
+    };
+    Ok(backups_in(&dir)
+        .into_iter()
+        .filter(|b| root.is_none() || b.root == root)
+        .collect())
+}
+
+/// Extracts a backup into `target`. A non-empty target is only written to
+/// with `overwrite`, in which case files from the backup replace existing
+/// ones and everything else is left alone.
+#[tauri::command]
+pub async fn restore_backup(
+    backup: String,
+    target: String,
+    overwrite: Option<bool>,
+) -> Result<usize, String> {
+    let target = Path::new(&target);
+    let occupied = fs::read_dir(target).is_ok_and(|mut entries| entries.next().is_some());
+    if occupied && !overwrite.unwrap_or(false) {
+        return Err("target_not_empty".to_string());
+    }
+
+    let file = File::open(&backup).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut restored = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        // Entries that would escape the target folder are skipped
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let path = target.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut out = File::create(&path).map_err(|e| e.to_string())?;
+        io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+        restored += 1;
+    }
+    Ok(restored)
+}
@@ -0,0 +1,271 @@
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case", tag = "type", content = "level")]
+pub enum BlockKind {
+    Heading(u8),
+    Paragraph,
+    Code,
+    List,
+    Quote,
+    Table,
+    Rule,
+    Html,
+}
+
+/// A top-level markdown block. Lines are 1-based and inclusive, matching the
+/// `data-sourcepos` attributes comrak emits on the rendered element.
+#[derive(Serialize, Clone, Debug)]
+pub struct Block {
+    pub kind: BlockKind,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+fn fence_marker(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("```") {
+        Some("```")
+    } else if trimmed.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+pub fn heading_level(line: &str) -> Option<u8> {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&hashes) && matches!(line[hashes..].chars().next(), None | Some(' ')) {
+        Some(hashes as u8)
+    } else {
+        None
+    }
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && matches!(compact[0], '-' | '*' | '_')
+        && compact.iter().all(|c| *c == compact[0])
+}
+
+fn is_list_item(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("- ") || trimmed.starts_with("* ") || trimmed.starts_with("+ ") {
+        return true;
+    }
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && (trimmed[digits..].starts_with(". ") || trimmed[digits..].starts_with(") "))
+}
+
+fn classify(line: &str) -> BlockKind {
+    let trimmed = line.trim_start();
+    if let Some(level) = heading_level(trimmed) {
+        BlockKind::Heading(level)
+    } else if trimmed.starts_with('>') {
+        BlockKind::Quote
+    } else if is_list_item(line) {
+        BlockKind::List
+    } else if trimmed.starts_with('|') {
+        BlockKind::Table
+    } else if trimmed.starts_with('<') {
+        BlockKind::Html
+    } else if line.starts_with("    ") || line.starts_with('\t') {
+        BlockKind::Code
+    } else {
+        BlockKind::Paragraph
+    }
+}
+
+/// Line index just past a leading YAML frontmatter block (0 if there is none).
+pub fn frontmatter_len(lines: &[&str]) -> usize {
+    if lines.first().map(|l| l.trim_end()) != Some("---") {
+        return 0;
+    }
+    lines
+        .iter()
+        .skip(1)
+        .position(|l| matches!(l.trim_end(), "---" | "..."))
+        .map(|i| i + 2)
+        .unwrap_or(0)
+}
+
+/// Splits markdown into its top-level blocks, keeping fenced code intact and
+/// treating loose lists (blank lines between items) as a single block.
+pub fn split_blocks(content: &str) -> Vec<Block> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut current: Option<Block> = None;
+    let mut fence: Option<&str> = None;
+
+    let mut i = frontmatter_len(&lines);
+    while i < lines.len() {
+        let line = lines[i];
+        let line_no = i + 1;
+        i += 1;
+
+        if let Some(marker) = fence {
+            if let Some(block) = current.as_mut() {
+                block.end_line = line_no;
+            }
+            if line.trim_start().starts_with(marker) {
+                fence = None;
+                blocks.extend(current.take());
+            }
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            // A loose list continues if the next content is another item or indented
+            if let Some(block) = current.as_ref() {
+                let next = lines[i..].iter().find(|l| !l.trim().is_empty());
+                let continues = block.kind == BlockKind::List
+                    && next.is_some_and(|l| is_list_item(l) || l.starts_with([' ', '\t']));
+                if !continues {
+                    blocks.extend(current.take());
+                }
+            }
+            continue;
+        }
+
+        if let Some(marker) = fence_marker(line) {
+            blocks.extend(current.take());
+            fence = Some(marker);
+            current = Some(Block {
+                kind: BlockKind::Code,
+                start_line: line_no,
+                end_line: line_no,
+            });
+            continue;
+        }
+
+        // Setext headings turn the preceding paragraph into a heading
+        if let Some(block) = current.as_mut() {
+            if block.kind == BlockKind::Paragraph {
+                let trimmed = line.trim();
+                let level = if !trimmed.is_empty() && trimmed.chars().all(|c| c == '=') {
+                    Some(1)
+                } else if !trimmed.is_empty() && trimmed.chars().all(|c| c == '-') {
+                    Some(2)
+                } else {
+                    None
+                };
+                if let Some(level) = level {
+                    block.kind = BlockKind::Heading(level);
+                    block.end_line = line_no;
+                    blocks.extend(current.take());
+                    continue;
+                }
+            }
+        }
+
+        let kind = if is_rule(line) {
+            BlockKind::Rule
+        } else {
+            classify(line)
+        };
+
+        match (current.as_mut(), kind) {
+            // Headings and rules always stand alone
+            (_, BlockKind::Heading(_) | BlockKind::Rule) => {
+                blocks.extend(current.take());
+                blocks.push(Block {
+                    kind,
+                    start_line: line_no,
+                    end_line: line_no,
+                });
+            }
+            (Some(block), _) => block.end_line = line_no,
+            (None, _) => {
+                current = Some(Block {
+                    kind,
+                    start_line: line_no,
+                    end_line: line_no,
+                })
+            }
+        }
+    }
+    blocks.extend(current);
+
+    blocks
+}
+
+/// 64-bit FNV-1a; stable across Rust versions unlike `DefaultHasher`.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[derive(Serialize, Clone)]
+pub struct AnchoredBlock {
+    pub anchor: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Content-derived block ids that survive edits elsewhere in the document.
+/// Repeated identical blocks get a `-2`, `-3`... suffix.
+pub fn block_anchors(content: &str) -> Vec<AnchoredBlock> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut seen: HashMap<u64, usize> = HashMap::new();
+
+    split_blocks(content)
+        .into_iter()
+        .map(|block| {
+            let text = lines[block.start_line - 1..block.end_line]
+                .iter()
+                .map(|l| l.trim())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let hash = fnv1a(text.as_bytes());
+            let count = seen.entry(hash).or_insert(0);
+            *count += 1;
+            let anchor = if *count == 1 {
+                format!("b{:08x}", hash as u32)
+            } else {
+                format!("b{:08x}-{}", hash as u32, count)
+            };
+            AnchoredBlock {
+                anchor,
+                start_line: block.start_line,
+                end_line: block.end_line,
+            }
+        })
+        .collect()
+}
+
+/// Applies a regex replacement everywhere except fenced code blocks and
+/// inline code spans.
+pub fn replace_outside_code(
+    content: &str,
+    re: &Regex,
+    mut replace: impl FnMut(&Captures) -> String,
+) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut in_fence = false;
+
+    for line in content.split_inclusive('\n') {
+        if fence_marker(line).is_some() {
+            in_fence = !in_fence;
+        }
+        if in_fence || !re.is_match(line) {
+            out.push_str(line);
+            continue;
+        }
+        // Odd segments between backticks are inline code
+        for (i, segment) in line.split('`').enumerate() {
+            if i > 0 {
+                out.push('`');
+            }
+            if i % 2 == 1 {
+                out.push_str(segment);
+            } else {
+                out.push_str(&re.replace_all(segment, &mut replace));
+            }
+        }
+    }
+    out
+}
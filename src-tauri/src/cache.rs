@@ -0,0 +1,206 @@
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager, State};
+
+const CACHE_CONFIG_FILE: &str = "cache.json";
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    max_bytes: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            max_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// Serializes writes and evictions so concurrent renders don't race on the
+/// size accounting.
+#[derive(Default)]
+pub struct CacheState {
+    lock: Mutex<()>,
+}
+
+#[derive(Serialize)]
+pub struct CacheKindStats {
+    kind: String,
+    entries: usize,
+    bytes: u64,
+}
+
+#[derive(Serialize)]
+pub struct CacheStats {
+    total_bytes: u64,
+    max_bytes: u64,
+    entries: usize,
+    kinds: Vec<CacheKindStats>,
+}
+
+struct CacheEntry {
+    path: PathBuf,
+    size: u64,
+    last_used: SystemTime,
+}
+
+fn cache_root(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("artifacts"))
+}
+
+// Keys come from content hashes but may carry an extension; keep them file-safe
+fn sanitize(part: &str) -> String {
+    part.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn entries(root: &Path) -> Vec<(String, CacheEntry)> {
+    let mut result = Vec::new();
+    let Ok(kinds) = fs::read_dir(root) else {
+        return result;
+    };
+    for kind in kinds.flatten() {
+        let kind_name = kind.file_name().to_string_lossy().to_string();
+        let Ok(files) = fs::read_dir(kind.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let Ok(meta) = file.metadata() else {
+                continue;
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            result.push((
+                kind_name.clone(),
+                CacheEntry {
+                    path: file.path(),
+                    size: meta.len(),
+                    last_used: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                },
+            ));
+        }
+    }
+    result
+}
+
+/// Removes least recently used artifacts until the cache fits in `max_bytes`.
+fn evict(root: &Path, max_bytes: u64) {
+    let mut all: Vec<CacheEntry> = entries(root).into_iter().map(|(_, e)| e).collect();
+    let mut total: u64 = all.iter().map(|e| e.size).sum();
+    if total <= max_bytes {
+        return;
+    }
+    all.sort_by_key(|e| e.last_used);
+    for entry in all {
+        if total <= max_bytes {
+            break;
+        }
+        if fs::remove_file(&entry.path).is_ok() {
+            total = total.saturating_sub(entry.size);
+        }
+    }
+}
+
+/// Returns the cached artifact for `kind`/`key`, marking it as recently used.
+pub fn get(app: &AppHandle, kind: &str, key: &str) -> Option<PathBuf> {
+    let path = cache_root(app)
+        .ok()?
+        .join(sanitize(kind))
+        .join(sanitize(key));
+    let file = fs::File::options().write(true).open(&path).ok()?;
+    let _ = file.set_modified(SystemTime::now());
+    Some(path)
+}
+
+/// Stores an artifact and evicts old entries if the cache grew past its limit.
+pub fn put(app: &AppHandle, kind: &str, key: &str, bytes: &[u8]) -> Result<PathBuf, String> {
+    let state = app.state::<CacheState>();
+    let _guard = state.lock.lock().unwrap();
+
+    let root = cache_root(app)?;
+    let dir = root.join(sanitize(kind));
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(sanitize(key));
+    store::write_atomic(&path, bytes)?;
+
+    let config: CacheConfig = store::load_json(app, CACHE_CONFIG_FILE);
+    evict(&root, config.max_bytes);
+    Ok(path)
+}
+
+#[tauri::command]
+pub fn get_cache_stats(app: AppHandle) -> Result<CacheStats, String> {
+    let config: CacheConfig = store::load_json(&app, CACHE_CONFIG_FILE);
+    let mut kinds: Vec<CacheKindStats> = Vec::new();
+    for (kind, entry) in entries(&cache_root(&app)?) {
+        match kinds.iter_mut().find(|k| k.kind == kind) {
+            Some(stats) => {
+                stats.entries += 1;
+                stats.bytes += entry.size;
+            }
+            None => kinds.push(CacheKindStats {
+                kind,
+                entries: 1,
+                bytes: entry.size,
+            }),
+        }
+    }
+    kinds.sort_by(|a, b| a.kind.cmp(&b.kind));
+
+    Ok(CacheStats {
+        total_bytes: kinds.iter().map(|k| k.bytes).sum(),
+        max_bytes: config.max_bytes,
+        entries: kinds.iter().map(|k| k.entries).sum(),
+        kinds,
+    })
+}
+
+/// Clears one artifact kind (e.g. "mermaid"), or everything when `kind` is omitted.
+#[tauri::command]
+pub fn clear_cache(
+    app: AppHandle,
+    state: State<'_, CacheState>,
+    kind: Option<String>,
+) -> Result<(), String> {
+    let _guard = state.lock.lock().unwrap();
+    let root = cache_root(&app)?;
+    let target = match kind {
+        Some(kind) => root.join(sanitize(&kind)),
+        None => root,
+    };
+    match fs::remove_dir_all(&target) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tauri::command]
+pub fn set_cache_limit(
+    app: AppHandle,
+    state: State<'_, CacheState>,
+    max_bytes: u64,
+) -> Result<(), String> {
+    store::save_json(&app, CACHE_CONFIG_FILE, &CacheConfig { max_bytes })?;
+    let _guard = state.lock.lock().unwrap();
+    evict(&cache_root(&app)?, max_bytes);
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use crate::{store, vault};
+use chrono::{DateTime, Local, NaiveDate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+use tauri::AppHandle;
+
+const DAILY_NOTES_FILE: &str = "daily_notes.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DailyNotesConfig {
+    /// Folder holding daily notes, relative to the vault root ("" = root)
+    folder: String,
+    /// chrono format string used for daily note file names
+    format: String,
+}
+
+impl Default for DailyNotesConfig {
+    fn default() -> Self {
+        DailyNotesConfig {
+            folder: String::new(),
+            format: "%Y-%m-%d".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CalendarRange {
+    start: NaiveDate,
+    end: NaiveDate,
+}
+
+#[derive(Serialize)]
+pub struct CalendarTask {
+    path: String,
+    line: usize,
+    text: String,
+    done: bool,
+}
+
+#[derive(Serialize, Default)]
+pub struct CalendarDay {
+    date: String,
+    daily_note: Option<String>,
+    created: Vec<String>,
+    modified: Vec<String>,
+    tasks_due: Vec<CalendarTask>,
+    /// Total number of events on this day, for heatmap intensity
+    activity: usize,
+}
+
+fn local_date(time: SystemTime) -> NaiveDate {
+    DateTime::<Local>::from(time).date_naive()
+}
+
+fn day_entry(days: &mut BTreeMap<NaiveDate, CalendarDay>, date: NaiveDate) -> &mut CalendarDay {
+    days.entry(date).or_insert_with(|| CalendarDay {
+        date: date.format("%Y-%m-%d").to_string(),
+        ..CalendarDay::default()
+    })
+}
+
+#[tauri::command]
+pub fn get_daily_notes_config(app: AppHandle) -> DailyNotesConfig {
+    store::load_json(&app, DAILY_NOTES_FILE)
+}
+
+#[tauri::command]
+pub fn save_daily_notes_config(app: AppHandle, config: DailyNotesConfig) -> Result<(), String> {
+    store::save_json(&app, DAILY_NOTES_FILE, &config)
+}
+
+#[tauri::command]
+pub async fn get_calendar_data(
+    app: AppHandle,
+    root: String,
+    range: CalendarRange,
+) -> Result<Vec<CalendarDay>, String> {
+    let root_path = Path::new(&root);
+    if !root_path.is_dir() {
+        return Err(crate::i18n::t("error.not_a_directory"));
+    }
+
+    let config: DailyNotesConfig = store::load_json(&app, DAILY_NOTES_FILE);
+    let daily_dir = root_path.join(&config.folder);
+    // Tasks plugin style (📅 2024-01-31), todo.txt style (due:2024-01-31) and @due(2024-01-31)
+    let task_re = Regex::new(r"^\s*[-*+] \[([ xX])\] (.*)$").unwrap();
+    let due_re = Regex::new(r"(?:📅|due:|@due\()\s*(\d{4}-\d{2}-\d{2})").unwrap();
+
+    let in_range = |date: NaiveDate| date >= range.start && date <= range.end;
+    let mut days: BTreeMap<NaiveDate, CalendarDay> = BTreeMap::new();
+
+    for file in vault::markdown_files(root_path) {
+        let path_str = file.to_string_lossy().to_string();
+
+        if file.parent() == Some(daily_dir.as_path()) {
+            let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+            if let Ok(date) = NaiveDate::parse_from_str(&stem, &config.format) {
+                if in_range(date) {
+                    day_entry(&mut days, date).daily_note = Some(path_str.clone());
+                }
+            }
+        }
+
+        if let Ok(meta) = fs::metadata(&file) {
+            let created = meta.created().ok().map(local_date);
+            let modified = meta.modified().ok().map(local_date);
+            if let Some(date) = created.filter(|d| in_range(*d)) {
+                day_entry(&mut days, date).created.push(path_str.clone());
+            }
+            if let Some(date) = modified.filter(|d| in_range(*d) && Some(*d) != created) {
+                day_entry(&mut days, date).modified.push(path_str.clone());
+            }
+        }
+
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for (i, line) in content.lines().enumerate() {
+            let Some(task) = task_re.captures(line) else {
+                continue;
+            };
+            let Some(due) = due_re.captures(line) else {
+                continue;
+            };
+            if let Ok(date) = NaiveDate::parse_from_str(&due[1], "%Y-%m-%d") {
+                if in_range(date) {
+                    day_entry(&mut days, date).tasks_due.push(CalendarTask {
+                        path: path_str.clone(),
+                        line: i + 1,
+                        text: task[2].to_string(),
+                        done: &task[1] != " ",
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(days
+        .into_values()
+        .map(|mut d| {
+            d.activity = d.created.len()
+                + d.modified.len()
+                + d.tasks_due.len()
+                + usize::from(d.daily_note.is_some());
+            d
+        })
+        .collect())
+}
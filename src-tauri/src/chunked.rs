@@ -0,0 +1,166 @@
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+
+#[derive(Serialize)]
+pub struct FileChunk {
+    content: String,
+    /// Byte offset the content starts at
+    offset: u64,
+    /// Where the next chunk starts; `None` at the end of the file
+    next_offset: Option<u64>,
+    total_bytes: u64,
+}
+
+#[derive(Serialize)]
+pub struct LineChunk {
+    content: String,
+    /// Zero-based index of the first line returned
+    start_line: usize,
+    line_count: usize,
+    eof: bool,
+}
+
+fn open(path: &str) -> Result<(BufReader<File>, u64), String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    Ok((BufReader::new(file), len))
+}
+
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+/// Reads about `length` bytes from `offset`, widened or trimmed so the chunk
+/// never splits a UTF-8 character.
+#[tauri::command]
+pub fn read_file_range(path: String, offset: u64, length: u64) -> Result<FileChunk, String> {
+    let (mut reader, total_bytes) = open(&path)?;
+    let offset = offset.min(total_bytes);
+    reader
+        .seek(SeekFrom::Start(offset))
+        .map_err(|e| e.to_string())?;
+    // A few extra bytes let a character straddling the end be completed
+    let mut bytes = Vec::new();
+    reader
+        .take(length + 3)
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+
+    let start = bytes.iter().take_while(|b| is_continuation(**b)).count();
+    let mut end = (length as usize).min(bytes.len()).max(start);
+    while end < bytes.len() && is_continuation(bytes[end]) {
+        end += 1;
+    }
+    let next = offset + end as u64;
+    Ok(FileChunk {
+        content: String::from_utf8_lossy(&bytes[start..end]).into_owned(),
+        offset: offset + start as u64,
+        next_offset: (next < total_bytes).then_some(next),
+        total_bytes,
+    })
+}
+
+/// Returns up to `max_lines` lines starting at line `start_line`.
+#[tauri::command]
+pub fn read_file_lines(
+    path: String,
+    start_line: usize,
+    max_lines: usize,
+) -> Result<LineChunk, String> {
+    let (mut reader, _) = open(&path)?;
+    let mut line = Vec::new();
+    for _ in 0..start_line {
+        line.clear();
+        if reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| e.to_string())?
+            == 0
+        {
+            break;
+        }
+    }
+    let mut content = Vec::new();
+    let mut line_count = 0;
+    while line_count < max_lines {
+        if reader
+            .read_until(b'\n', &mut content)
+            .map_err(|e| e.to_string())?
+            == 0
+        {
+            break;
+        }
+        line_count += 1;
+    }
+    let eof = reader.fill_buf().map_err(|e| e.to_string())?.is_empty();
+    Ok(LineChunk {
+        content: String::from_utf8_lossy(&content).into_owned(),
+        start_line,
+        line_count,
+        eof,
+    })
+}
+
+fn fence_marker(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    ["```", "~~~"]
+        .into_iter()
+        .find(|marker| trimmed.starts_with(marker))
+}
+
+/// Reads markdown from `offset` until `max_blocks` blank-line separated
+/// blocks have been collected, never cutting inside a fenced code block.
+/// Returns the source and the offset to continue from.
+pub fn read_blocks(
+    path: &str,
+    offset: u64,
+    max_blocks: usize,
+) -> Result<(String, Option<u64>, u64), String> {
+    let (mut reader, total_bytes) = open(path)?;
+    reader
+        .seek(SeekFrom::Start(offset))
+        .map_err(|e| e.to_string())?;
+
+    let mut source = Vec::new();
+    let mut position = offset;
+    let mut blocks = 0;
+    let mut in_block = false;
+    let mut fence: Option<String> = None;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Ok((
+                String::from_utf8_lossy(&source).into_owned(),
+                None,
+                total_bytes,
+            ));
+        }
+        let text = String::from_utf8_lossy(&line);
+        let blank = text.trim().is_empty();
+        if blank && fence.is_none() && in_block {
+            in_block = false;
+            blocks += 1;
+            if blocks >= max_blocks {
+                return Ok((
+                    String::from_utf8_lossy(&source).into_owned(),
+                    Some(position),
+                    total_bytes,
+                ));
+            }
+        }
+        if !blank {
+            in_block = true;
+        }
+        match (&fence, fence_marker(&text)) {
+            (None, Some(marker)) => fence = Some(marker.to_string()),
+            (Some(open), Some(marker)) if open == marker => fence = None,
+            _ => {}
+        }
+        source.extend_from_slice(&line);
+        position += read as u64;
+    }
+}
@@ -0,0 +1,218 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Mutex, OnceLock};
+use tauri::State;
+
+#[derive(Serialize, Clone)]
+pub struct BibEntry {
+    key: String,
+    entry_type: String,
+    title: Option<String>,
+    author: Option<String>,
+    year: Option<String>,
+}
+
+#[derive(Default)]
+pub struct BibliographyState {
+    entries: Mutex<HashMap<String, BibEntry>>,
+}
+
+#[derive(Serialize)]
+pub struct CitationIssue {
+    line: usize,
+    /// 0-based character column of the `@`
+    column: usize,
+    key: String,
+    /// Closest known keys, best first, for quick fixes
+    suggestions: Vec<String>,
+}
+
+/// Minimal BibTeX reader: entry type, key and the handful of fields the UI shows.
+fn parse_bibtex(content: &str) -> Vec<BibEntry> {
+    static FIELD: OnceLock<Regex> = OnceLock::new();
+    let field_re = FIELD.get_or_init(|| {
+        Regex::new(
+            r#"(?is)\b(title|author|year)\s*=\s*(?:\{((?:[^{}]|\{[^{}]*\})*)\}|"([^"]*)"|(\d+))"#,
+        )
+        .unwrap()
+    });
+
+    let mut entries = Vec::new();
+    for chunk in content.split('@').skip(1) {
+        let Some(open) = chunk.find(['{', '(']) else {
+            continue;
+        };
+        let entry_type = chunk[..open].trim().to_lowercase();
+        if matches!(entry_type.as_str(), "comment" | "preamble" | "string") {
+            continue;
+        }
+        let rest = &chunk[open + 1..];
+        let Some(comma) = rest.find(',') else {
+            continue;
+        };
+        let key = rest[..comma].trim().to_string();
+        if key.is_empty() {
+            continue;
+        }
+
+        let mut entry = BibEntry {
+            key,
+            entry_type,
+            title: None,
+            author: None,
+            year: None,
+        };
+        for caps in field_re.captures_iter(&rest[comma..]) {
+            let value = caps
+                .get(2)
+                .or(caps.get(3))
+                .or(caps.get(4))
+                .map(|m| m.as_str().replace(['{', '}'], "").replace('\n', " "))
+                .map(|v| v.split_whitespace().collect::<Vec<_>>().join(" "));
+            match caps[1].to_lowercase().as_str() {
+                "title" => entry.title = value,
+                "author" => entry.author = value,
+                _ => entry.year = value,
+            }
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+fn suggestions(key: &str, entries: &HashMap<String, BibEntry>) -> Vec<String> {
+    let lower = key.to_lowercase();
+    let max_distance = (key.chars().count() / 3).max(2);
+    let mut scored: Vec<(usize, &String)> = entries
+        .keys()
+        .map(|k| (levenshtein(&lower, &k.to_lowercase()), k))
+        .filter(|(d, _)| *d <= max_distance)
+        .collect();
+    scored.sort();
+    scored.into_iter().take(3).map(|(_, k)| k.clone()).collect()
+}
+
+/// Byte ranges of inline code spans on a line.
+fn code_spans(line: &str) -> Vec<(usize, usize)> {
+    let ticks: Vec<usize> = line.match_indices('`').map(|(i, _)| i).collect();
+    ticks
+        .chunks(2)
+        .filter(|p| p.len() == 2)
+        .map(|p| (p[0], p[1]))
+        .collect()
+}
+
+#[tauri::command]
+pub fn load_bibliography(
+    state: State<'_, BibliographyState>,
+    paths: Vec<String>,
+) -> Result<usize, String> {
+    let mut entries = HashMap::new();
+    for path in &paths {
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        for entry in parse_bibtex(&content) {
+            entries.insert(entry.key.clone(), entry);
+        }
+    }
+    let count = entries.len();
+    *state.entries.lock().unwrap() = entries;
+    Ok(count)
+}
+
+/// Completion candidates for a partially typed `@key`: key prefix matches
+/// first, then keys, titles or authors containing the query.
+#[tauri::command]
+pub fn complete_citation(
+    state: State<'_, BibliographyState>,
+    prefix: String,
+    limit: Option<usize>,
+) -> Vec<BibEntry> {
+    let query = prefix.trim_start_matches('@').to_lowercase();
+    let entries = state.entries.lock().unwrap();
+
+    let mut matches: Vec<(u8, &BibEntry)> = entries
+        .values()
+        .filter_map(|e| {
+            let key = e.key.to_lowercase();
+            let rank = if key.starts_with(&query) {
+                0
+            } else if key.contains(&query) {
+                1
+            } else if [&e.title, &e.author].iter().any(|f| {
+                f.as_ref()
+                    .is_some_and(|v| v.to_lowercase().contains(&query))
+            }) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, e))
+        })
+        .collect();
+    matches.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.key.cmp(&b.1.key)));
+    matches
+        .into_iter()
+        .take(limit.unwrap_or(20))
+        .map(|(_, e)| e.clone())
+        .collect()
+}
+
+/// Flags `@key` / `[@key]` citations that aren't in the loaded bibliographies.
+#[tauri::command]
+pub fn lint_citations(state: State<'_, BibliographyState>, content: String) -> Vec<CitationIssue> {
+    static CITE: OnceLock<Regex> = OnceLock::new();
+    let cite_re = CITE
+        .get_or_init(|| Regex::new(r"(?:^|[\s\[;(-])-?@([A-Za-z0-9_][\w:.#$%&+?/-]*)").unwrap());
+    let entries = state.entries.lock().unwrap();
+    if entries.is_empty() {
+        return Vec::new();
+    }
+
+    let mut issues = Vec::new();
+    let mut in_fence = false;
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let spans = code_spans(line);
+        for caps in cite_re.captures_iter(line) {
+            let m = caps.get(1).unwrap();
+            if spans.iter().any(|(s, e)| m.start() > *s && m.start() < *e) {
+                continue;
+            }
+            let key = m.as_str().trim_end_matches(['.', ':', ',', ';', '?']);
+            if key.is_empty() || entries.contains_key(key) {
+                continue;
+            }
+            issues.push(CitationIssue {
+                line: i + 1,
+                column: line[..m.start() - 1].chars().count(),
+                key: key.to_string(),
+                suggestions: suggestions(key, &entries),
+            });
+        }
+    }
+    issues
+}
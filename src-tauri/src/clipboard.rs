@@ -0,0 +1,82 @@
+use crate::attachments::{self, Attachment};
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+fn format_for(name: &str) -> Result<(ImageFormat, &'static str), String> {
+    match name.to_lowercase().as_str() {
+        "png" => Ok((ImageFormat::Png, "png")),
+        "jpeg" | "jpg" => Ok((ImageFormat::Jpeg, "jpg")),
+        "webp" => Ok((ImageFormat::WebP, "webp")),
+        other => Err(crate::i18n::tf(
+            "error.unsupported_image_format",
+            &[("format", other)],
+        )),
+    }
+}
+
+/// "Pasted image 20240101120000.png", with a counter if that name is taken.
+fn unique_name(folder: &Path, extension: &str) -> PathBuf {
+    let stem = format!(
+        "Pasted image {}",
+        chrono::Local::now().format("%Y%m%d%H%M%S")
+    );
+    let mut path = folder.join(format!("{}.{}", stem, extension));
+    let mut n = 1;
+    while path.exists() {
+        path = folder.join(format!("{} {}.{}", stem, n, extension));
+        n += 1;
+    }
+    path
+}
+
+/// Writes the image on the system clipboard as PNG (the default), JPEG or
+/// WebP and returns the embed to insert into the note. It goes into
+/// `dest_folder` if given, otherwise the note's attachment folder.
+#[tauri::command]
+pub async fn save_clipboard_image(
+    app: AppHandle,
+    note_path: Option<String>,
+    dest_folder: Option<String>,
+    format: Option<String>,
+) -> Result<Attachment, String> {
+    let folder = match (&dest_folder, &note_path) {
+        (Some(folder), _) => PathBuf::from(folder),
+        (None, Some(note)) => attachments::attachment_dir(&app, Path::new(note), None),
+        (None, None) => return Err(crate::i18n::t("error.note_or_folder_required")),
+    };
+    let (format, extension) = format_for(format.as_deref().unwrap_or("png"))?;
+    let data = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_image())
+        .map_err(|e| e.to_string())?;
+    let rgba = RgbaImage::from_raw(
+        data.width as u32,
+        data.height as u32,
+        data.bytes.into_owned(),
+    )
+    .ok_or_else(|| crate::i18n::t("error.clipboard_image_size"))?;
+    let image = match format {
+        // JPEG has no alpha channel
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(rgba).to_rgb8()),
+        _ => DynamicImage::ImageRgba8(rgba),
+    };
+
+    fs::create_dir_all(&folder).map_err(|e| e.to_string())?;
+    let path = unique_name(&folder, extension);
+    image
+        .save_with_format(&path, format)
+        .map_err(|e| e.to_string())?;
+
+    let embed = match &note_path {
+        Some(note) => attachments::embed_for(Path::new(note), &path),
+        None => format!(
+            "![[{}]]",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        ),
+    };
+    Ok(Attachment {
+        path: path.to_string_lossy().to_string(),
+        embed,
+    })
+}
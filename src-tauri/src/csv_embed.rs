@@ -0,0 +1,97 @@
+use crate::blocks;
+use crate::index::VaultIndex;
+use regex::{Captures, Regex};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const DEFAULT_ROW_LIMIT: usize = 500;
+
+fn resolve(target: &str, base: &Path, links: Option<&VaultIndex>, from: &Path) -> PathBuf {
+    let relative = base.join(target);
+    if relative.exists() {
+        return relative;
+    }
+    links
+        .and_then(|index| index.resolve(target, from))
+        .unwrap_or(relative)
+}
+
+/// Reads up to `limit` records, streaming so large files aren't loaded whole.
+fn csv_table(path: &Path, limit: usize) -> Result<String, String> {
+    crate::render_cache::depends_on(path);
+    let is_tsv = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("tsv"));
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(if is_tsv { b'\t' } else { b',' })
+        .flexible(true)
+        .from_path(path)
+        .map_err(|e| e.to_string())?;
+
+    let mut html = format!(
+        "<div class=\"csv-embed\" data-path=\"{}\"><table><thead><tr>",
+        crate::escape_attr(&path.to_string_lossy())
+    );
+    for header in reader.headers().map_err(|e| e.to_string())?.iter() {
+        html.push_str(&format!("<th>{}</th>", crate::escape_attr(header)));
+    }
+    html.push_str("</tr></thead><tbody>");
+
+    let mut truncated = false;
+    for (i, record) in reader.records().enumerate() {
+        if i == limit {
+            truncated = true;
+            break;
+        }
+        let record = record.map_err(|e| e.to_string())?;
+        html.push_str("<tr>");
+        for field in record.iter() {
+            html.push_str(&format!("<td>{}</td>", crate::escape_attr(field)));
+        }
+        html.push_str("</tr>");
+    }
+    html.push_str("</tbody></table>");
+    if truncated {
+        html.push_str(&format!(
+            "<p class=\"csv-embed-truncated\">{}</p>",
+            crate::escape_attr(&crate::i18n::tf(
+                "embed.csv_truncated",
+                &[("count", &limit.to_string())]
+            ))
+        ));
+    }
+    html.push_str("</div>");
+    Ok(html)
+}
+
+/// Renders `![[data.csv]]` (or `.tsv`) embeds as HTML tables. An optional
+/// `|N` sets the row limit. The table is emitted on a single line so comrak
+/// passes it through as one HTML block.
+pub fn process_csv_embeds(
+    content: &str,
+    base: &Path,
+    links: Option<&VaultIndex>,
+    from: &Path,
+) -> String {
+    static CSV_EMBED: OnceLock<Regex> = OnceLock::new();
+    let re = CSV_EMBED.get_or_init(|| {
+        Regex::new(r"(?i)!\[\[([^\[\]\|\n]+?\.(?:csv|tsv))(?:\|(\d+))?\]\]").unwrap()
+    });
+
+    blocks::replace_outside_code(content, re, |caps: &Captures| {
+        let limit = caps
+            .get(2)
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(DEFAULT_ROW_LIMIT);
+        let path = resolve(caps[1].trim(), base, links, from);
+        csv_table(&path, limit).unwrap_or_else(|e| {
+            format!(
+                "<p class=\"csv-embed-error\">{}</p>",
+                crate::escape_attr(&crate::i18n::tf(
+                    "embed.csv_error",
+                    &[("file", &caps[1]), ("error", &e)]
+                ))
+            )
+        })
+    })
+}
@@ -0,0 +1,182 @@
+use crate::{blocks, index, vault};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+/// MinHash signature length, split into bands for candidate lookup.
+const BANDS: usize = 16;
+const ROWS: usize = 4;
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct DuplicateOptions {
+    /// Also look for notes that are mostly, not exactly, the same
+    near: bool,
+    /// Minimum shingle overlap (0..1) for a near-duplicate
+    threshold: f64,
+    /// Words per shingle
+    shingle_size: usize,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        DuplicateOptions {
+            near: true,
+            threshold: 0.8,
+            shingle_size: 5,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DuplicateGroup {
+    /// Identical notes, sorted by path
+    paths: Vec<String>,
+    size: u64,
+}
+
+#[derive(Serialize)]
+pub struct NearDuplicate {
+    a: String,
+    b: String,
+    /// Jaccard similarity of the notes' word shingles, 0..1
+    similarity: f64,
+}
+
+#[derive(Serialize)]
+pub struct DuplicateReport {
+    exact: Vec<DuplicateGroup>,
+    near: Vec<NearDuplicate>,
+}
+
+/// Line endings and trailing whitespace differ between sync clients, so
+/// they don't count as changes.
+fn normalize(content: &str) -> String {
+    content
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+fn shingles(content: &str, size: usize) -> HashSet<u64> {
+    let words: Vec<String> = index::plain_text(content)
+        .split_whitespace()
+        .map(|w| w.to_lowercase())
+        .collect();
+    if words.is_empty() {
+        return HashSet::new();
+    }
+    words
+        .windows(size.min(words.len()))
+        .map(|window| blocks::fnv1a(window.join(" ").as_bytes()))
+        .collect()
+}
+
+fn minhash(shingles: &HashSet<u64>) -> Vec<u64> {
+    (0..(BANDS * ROWS) as u64)
+        .map(|seed| {
+            let salt = blocks::fnv1a(&seed.to_le_bytes());
+            shingles
+                .iter()
+                .map(|s| (s ^ salt).wrapping_mul(0x9e3779b97f4a7c15).rotate_left(31))
+                .min()
+                .unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+/// Notes with identical content, and with `near` on, pairs whose text is
+/// largely the same (MinHash over word shingles, verified exactly), to help
+/// merge copies left behind by old syncs.
+#[tauri::command]
+pub async fn find_duplicate_notes(
+    root: String,
+    options: Option<DuplicateOptions>,
+) -> Result<DuplicateReport, String> {
+    let options = options.unwrap_or_default();
+    let root = PathBuf::from(root);
+    if !root.is_dir() {
+        return Err(crate::i18n::t("error.not_a_directory"));
+    }
+
+    let notes: Vec<(PathBuf, String)> = vault::markdown_files(&root)
+        .into_iter()
+        .filter_map(|path| {
+            let content = normalize(&fs::read_to_string(&path).ok()?);
+            (!content.is_empty()).then_some((path, content))
+        })
+        .collect();
+
+    let mut by_hash: BTreeMap<u64, Vec<usize>> = BTreeMap::new();
+    for (i, (_, content)) in notes.iter().enumerate() {
+        by_hash
+            .entry(blocks::fnv1a(content.as_bytes()))
+            .or_default()
+            .push(i);
+    }
+    let mut exact: Vec<DuplicateGroup> = by_hash
+        .values()
+        .filter(|group| group.len() > 1)
+        .map(|group| DuplicateGroup {
+            paths: group
+                .iter()
+                .map(|&i| notes[i].0.to_string_lossy().to_string())
+                .collect(),
+            size: notes[group[0]].1.len() as u64,
+        })
+        .collect();
+    exact.sort_by(|a, b| b.size.cmp(&a.size).then(a.paths.cmp(&b.paths)));
+
+    let mut near = Vec::new();
+    if options.near {
+        // One representative per exact group; the others are reported above
+        let unique: Vec<usize> = by_hash.values().map(|group| group[0]).collect();
+        let sets: HashMap<usize, HashSet<u64>> = unique
+            .iter()
+            .map(|&i| (i, shingles(&notes[i].1, options.shingle_size.max(1))))
+            .filter(|(_, set)| !set.is_empty())
+            .collect();
+
+        let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+        for (&i, set) in &sets {
+            for (band, rows) in minhash(set).chunks(ROWS).enumerate() {
+                let key = rows.iter().fold(0u64, |h, r| h.rotate_left(7) ^ r);
+                buckets.entry((band, key)).or_default().push(i);
+            }
+        }
+        let mut candidates: HashSet<(usize, usize)> = HashSet::new();
+        for bucket in buckets.values() {
+            for (n, &a) in bucket.iter().enumerate() {
+                for &b in &bucket[n + 1..] {
+                    candidates.insert((a.min(b), a.max(b)));
+                }
+            }
+        }
+        for (a, b) in candidates {
+            let similarity = jaccard(&sets[&a], &sets[&b]);
+            if similarity >= options.threshold {
+                near.push(NearDuplicate {
+                    a: notes[a].0.to_string_lossy().to_string(),
+                    b: notes[b].0.to_string_lossy().to_string(),
+                    similarity,
+                });
+            }
+        }
+        near.sort_by(|x, y| {
+            y.similarity
+                .total_cmp(&x.similarity)
+                .then_with(|| x.a.cmp(&y.a))
+        });
+    }
+
+    Ok(DuplicateReport { exact, near })
+}
@@ -0,0 +1,158 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+/// How a file was stored on disk, so saving can write it back the same way.
+#[derive(Clone, Copy, PartialEq)]
+pub struct FileEncoding {
+    encoding: &'static Encoding,
+    bom: bool,
+}
+
+impl Default for FileEncoding {
+    fn default() -> Self {
+        FileEncoding {
+            encoding: UTF_8,
+            bom: false,
+        }
+    }
+}
+
+/// Encodings of opened files that aren't plain UTF-8, keyed by path.
+#[derive(Default)]
+pub struct EncodingState {
+    files: Mutex<HashMap<String, FileEncoding>>,
+}
+
+#[derive(Serialize)]
+pub struct EncodingInfo {
+    encoding: String,
+    bom: bool,
+}
+
+/// Decodes file bytes, honouring a BOM and otherwise guessing the encoding
+/// when the bytes aren't valid UTF-8.
+pub fn decode(bytes: &[u8]) -> (String, FileEncoding) {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return (
+            text.into_owned(),
+            FileEncoding {
+                encoding,
+                bom: true,
+            },
+        );
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return (text.to_string(), FileEncoding::default());
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, true);
+    let (text, _) = encoding.decode_without_bom_handling(bytes);
+    (
+        text.into_owned(),
+        FileEncoding {
+            encoding,
+            bom: false,
+        },
+    )
+}
+
+/// Encodes `content` back into `target`. Fails with "unmappable_characters"
+/// when a legacy encoding can't represent the text.
+pub fn encode(content: &str, target: FileEncoding) -> Result<Vec<u8>, String> {
+    // encoding_rs only encodes UTF-16 as UTF-8, so those are handled here
+    let utf16 = |to_bytes: fn(u16) -> [u8; 2]| -> Vec<u8> {
+        let bom = target.bom.then_some(0xFEFF);
+        bom.into_iter()
+            .chain(content.encode_utf16())
+            .flat_map(to_bytes)
+            .collect()
+    };
+    if target.encoding == UTF_16LE {
+        return Ok(utf16(u16::to_le_bytes));
+    }
+    if target.encoding == UTF_16BE {
+        return Ok(utf16(u16::to_be_bytes));
+    }
+    if target.encoding == UTF_8 {
+        let bom: &[u8] = if target.bom { b"\xEF\xBB\xBF" } else { b"" };
+        return Ok([bom, content.as_bytes()].concat());
+    }
+    let (bytes, _, had_errors) = target.encoding.encode(content);
+    if had_errors {
+        return Err("unmappable_characters".to_string());
+    }
+    Ok(bytes.into_owned())
+}
+
+/// Reads a file as UTF-8 text and remembers its original encoding.
+pub fn read(app: &AppHandle, path: &str) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let (text, encoding) = decode(&bytes);
+    let state = app.state::<EncodingState>();
+    let mut files = state.files.lock().unwrap();
+    if encoding == FileEncoding::default() {
+        files.remove(path);
+    } else {
+        files.insert(path.to_string(), encoding);
+    }
+    Ok(text)
+}
+
+/// Encodes `content` the way `path` was stored when it was read.
+pub fn encode_for(app: &AppHandle, path: &str, content: &str) -> Result<Vec<u8>, String> {
+    let state = app.state::<EncodingState>();
+    let encoding = state
+        .files
+        .lock()
+        .unwrap()
+        .get(path)
+        .copied()
+        .unwrap_or_default();
+    encode(content, encoding)
+}
+
+#[tauri::command]
+pub fn get_file_encoding(state: State<'_, EncodingState>, path: String) -> EncodingInfo {
+    let encoding = state
+        .files
+        .lock()
+        .unwrap()
+        .get(&path)
+        .copied()
+        .unwrap_or_default();
+    EncodingInfo {
+        encoding: encoding.encoding.name().to_string(),
+        bom: encoding.bom,
+    }
+}
+
+/// Changes the encoding used when `path` is next saved, e.g. to convert a
+/// legacy file to UTF-8. Takes WHATWG labels such as "windows-1252".
+#[tauri::command]
+pub fn set_file_encoding(
+    state: State<'_, EncodingState>,
+    path: String,
+    encoding: String,
+    bom: Option<bool>,
+) -> Result<(), String> {
+    let encoding = Encoding::for_label(encoding.as_bytes())
+        .ok_or_else(|| crate::i18n::tf("error.unknown_encoding", &[("encoding", &encoding)]))?;
+    let target = FileEncoding {
+        encoding,
+        bom: bom.unwrap_or(encoding == UTF_16LE || encoding == UTF_16BE),
+    };
+    let mut files = state.files.lock().unwrap();
+    if target == FileEncoding::default() {
+        files.remove(&path);
+    } else {
+        files.insert(path, target);
+    }
+    Ok(())
+}
@@ -0,0 +1,350 @@
+use crate::{export, frontmatter, headings, store, vault, RenderOptions};
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::AppHandle;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const BOOK_CSS: &str = "body { font-family: serif; line-height: 1.5; margin: 0 5%; }
+h1, h2, h3, h4, h5, h6 { font-family: sans-serif; line-height: 1.2; }
+img { max-width: 100%; }
+pre { white-space: pre-wrap; font-size: 0.85em; }
+code { font-family: monospace; }
+blockquote { margin-left: 1em; padding-left: 1em; border-left: 3px solid #ccc; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 0.2em 0.5em; }";
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct EpubExportOptions {
+    /// Output file; defaults to `<note or folder>.epub` alongside the source
+    output: Option<String>,
+    /// Override the title, author and language read from frontmatter
+    title: Option<String>,
+    author: Option<String>,
+    language: Option<String>,
+    #[serde(flatten)]
+    render: RenderOptions,
+}
+
+struct Chapter {
+    title: String,
+    file: String,
+    body: String,
+    /// (level, id, text) of the h1/h2 headings, for the table of contents
+    headings: Vec<(u8, String, String)>,
+}
+
+struct BookMeta {
+    title: String,
+    author: Option<String>,
+    language: String,
+    date: Option<String>,
+    description: Option<String>,
+    tags: Vec<String>,
+}
+
+struct Image {
+    href: String,
+    path: PathBuf,
+}
+
+fn xml_escape(text: &str) -> String {
+    crate::escape_attr(text).replace('\'', "&apos;")
+}
+
+/// Chapter notes: the note itself, or every markdown file directly in the
+/// folder, ordered by file name so "01 Intro.md"-style prefixes work.
+fn chapter_files(source: &Path) -> Result<Vec<PathBuf>, String> {
+    if !source.is_dir() {
+        return Ok(vec![source.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(source)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && vault::is_markdown(path))
+        .collect();
+    files.sort_by_key(|path| path.file_name().map(|n| n.to_string_lossy().to_lowercase()));
+    if files.is_empty() {
+        return Err(crate::i18n::t("error.no_markdown_notes"));
+    }
+    Ok(files)
+}
+
+/// Comrak emits HTML; EPUB content documents must be well-formed XHTML.
+fn to_xhtml(html: &str) -> String {
+    static VOID: OnceLock<Regex> = OnceLock::new();
+    let re = VOID
+        .get_or_init(|| Regex::new(r"<(img|br|hr|input|source|col|wbr)\b([^>]*?)\s*/?>").unwrap());
+    re.replace_all(html, "<$1$2 />").replace("&nbsp;", "&#160;")
+}
+
+fn toc_headings(html: &str) -> Vec<(u8, String, String)> {
+    static HEADING: OnceLock<Regex> = OnceLock::new();
+    let re = HEADING.get_or_init(|| {
+        Regex::new(r#"(?s)<h([12])\b[^>]*\bid="([^"]*)"[^>]*>(.*?)</h[12]>"#).unwrap()
+    });
+    re.captures_iter(html)
+        .map(|caps: Captures| {
+            (
+                caps[1].parse().unwrap_or(1),
+                caps[2].to_string(),
+                headings::text_content(&caps[3]),
+            )
+        })
+        .collect()
+}
+
+fn chapter_xhtml(title: &str, language: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" xml:lang=\"{}\">\n<head>\n<title>{}</title>\n<link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\" />\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        xml_escape(language),
+        xml_escape(title),
+        body
+    )
+}
+
+fn nav_xhtml(title: &str, language: &str, chapters: &[Chapter]) -> String {
+    let mut items = String::new();
+    for chapter in chapters {
+        items.push_str(&format!(
+            "<li><a href=\"{}\">{}</a>",
+            chapter.file,
+            xml_escape(&chapter.title)
+        ));
+        // A chapter's own title heading is already the chapter entry
+        let sections: Vec<_> = chapter
+            .headings
+            .iter()
+            .filter(|(_, _, text)| *text != chapter.title)
+            .collect();
+        if !sections.is_empty() {
+            items.push_str("<ol>");
+            for (_, id, text) in sections {
+                items.push_str(&format!(
+                    "<li><a href=\"{}#{}\">{}</a></li>",
+                    chapter.file,
+                    xml_escape(id),
+                    xml_escape(text)
+                ));
+            }
+            items.push_str("</ol>");
+        }
+        items.push_str("</li>\n");
+    }
+    chapter_xhtml(
+        title,
+        language,
+        &format!(
+            "<nav epub:type=\"toc\" id=\"toc\">\n<h1>{}</h1>\n<ol>\n{}</ol>\n</nav>",
+            crate::escape_attr(&crate::i18n::t("export.contents")),
+            items
+        ),
+    )
+}
+
+fn content_opf(meta: &BookMeta, modified: &str, chapters: &[Chapter], images: &[Image]) -> String {
+    let mut manifest = String::from(
+        "<item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\" />\n<item id=\"css\" href=\"style.css\" media-type=\"text/css\" />\n",
+    );
+    let mut spine = String::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        manifest.push_str(&format!(
+            "<item id=\"chapter{}\" href=\"{}\" media-type=\"application/xhtml+xml\" />\n",
+            i, chapter.file
+        ));
+        spine.push_str(&format!("<itemref idref=\"chapter{}\" />\n", i));
+    }
+    for (i, image) in images.iter().enumerate() {
+        manifest.push_str(&format!(
+            "<item id=\"image{}\" href=\"{}\" media-type=\"{}\" />\n",
+            i,
+            image.href,
+            export::mime_type(&image.path)
+        ));
+    }
+
+    let mut metadata = format!(
+        "<dc:identifier id=\"book-id\">urn:marko:{:016x}</dc:identifier>\n<dc:title>{}</dc:title>\n<dc:language>{}</dc:language>\n<meta property=\"dcterms:modified\">{}</meta>\n",
+        crate::blocks::fnv1a(meta.title.as_bytes()),
+        xml_escape(&meta.title),
+        xml_escape(&meta.language),
+        modified
+    );
+    if let Some(author) = &meta.author {
+        metadata.push_str(&format!(
+            "<dc:creator>{}</dc:creator>\n",
+            xml_escape(author)
+        ));
+    }
+    if let Some(date) = &meta.date {
+        metadata.push_str(&format!("<dc:date>{}</dc:date>\n", xml_escape(date)));
+    }
+    if let Some(description) = &meta.description {
+        metadata.push_str(&format!(
+            "<dc:description>{}</dc:description>\n",
+            xml_escape(description)
+        ));
+    }
+    for tag in &meta.tags {
+        metadata.push_str(&format!("<dc:subject>{}</dc:subject>\n", xml_escape(tag)));
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\">\n<metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n{}</metadata>\n<manifest>\n{}</manifest>\n<spine>\n{}</spine>\n</package>\n",
+        metadata, manifest, spine
+    )
+}
+
+fn field(fm: Option<&frontmatter::Frontmatter>, keys: &[&str]) -> Option<String> {
+    let fields = &fm?.fields;
+    keys.iter()
+        .find_map(|key| fields.get(*key))
+        .and_then(|value| match value {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Array(items) => Some(
+                items
+                    .iter()
+                    .filter_map(|i| i.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            _ => None,
+        })
+}
+
+/// Book metadata from the first chapter's frontmatter; for folders the
+/// title falls back to the folder name.
+fn book_meta(source: &Path, first: &str, options: &EpubExportOptions) -> BookMeta {
+    let fm = frontmatter::parse(first).ok().flatten();
+    let title = options.title.clone().unwrap_or_else(|| {
+        if source.is_dir() {
+            source
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        } else {
+            export::note_title(first, source)
+        }
+    });
+    BookMeta {
+        title,
+        author: options
+            .author
+            .clone()
+            .or_else(|| field(fm.as_ref(), &["author", "authors"])),
+        language: options
+            .language
+            .clone()
+            .or_else(|| field(fm.as_ref(), &["lang", "language"]))
+            .unwrap_or_else(|| "en".to_string()),
+        date: fm.as_ref().and_then(|fm| fm.date.clone()),
+        description: field(fm.as_ref(), &["description", "summary"]),
+        tags: fm.map(|fm| fm.tags).unwrap_or_default(),
+    }
+}
+
+/// Exports a note, or a folder of chapter notes, as an EPUB 3 book with
+/// frontmatter metadata, a table of contents and embedded images.
+#[tauri::command]
+pub async fn export_epub(
+    app: AppHandle,
+    path: String,
+    options: Option<EpubExportOptions>,
+) -> Result<String, String> {
+    let mut options = options.unwrap_or_default();
+    let source = Path::new(&path);
+    let files = chapter_files(source)?;
+    let first = fs::read_to_string(&files[0]).map_err(|e| e.to_string())?;
+    let meta = book_meta(source, &first, &options);
+
+    let mut images: Vec<Image> = Vec::new();
+    let mut image_hrefs: HashMap<PathBuf, String> = HashMap::new();
+    let mut chapters: Vec<Chapter> = Vec::new();
+    for (i, file) in files.iter().enumerate() {
+        let content = fs::read_to_string(file).map_err(|e| e.to_string())?;
+        let mut render = options.render.clone();
+        render.path = Some(file.to_string_lossy().to_string());
+        let html = crate::render_in_vault(&app, &content, &mut render);
+
+        let html = export::rewrite_local_assets(&html, |asset| {
+            if let Some(href) = image_hrefs.get(asset) {
+                return Some(href.clone());
+            }
+            if !asset.is_file() || !export::mime_type(asset).starts_with("image/") {
+                return None;
+            }
+            let ext = asset.extension()?.to_string_lossy().to_lowercase();
+            let href = format!("images/image{}.{}", images.len(), ext);
+            images.push(Image {
+                href: href.clone(),
+                path: asset.to_path_buf(),
+            });
+            image_hrefs.insert(asset.to_path_buf(), href.clone());
+            Some(href)
+        });
+
+        chapters.push(Chapter {
+            title: export::note_title(&content, file),
+            file: format!("chapter{}.xhtml", i + 1),
+            headings: toc_headings(&html),
+            body: to_xhtml(&html),
+        });
+    }
+
+    let output = options.output.take().map(PathBuf::from).unwrap_or_else(|| {
+        let name = source.file_stem().unwrap_or_default().to_string_lossy();
+        source.with_file_name(format!("{}.epub", name))
+    });
+    let modified = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut add = |name: &str, bytes: &[u8], options: SimpleFileOptions| -> Result<(), String> {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(bytes).map_err(|e| e.to_string())
+    };
+
+    // The mimetype entry must come first and be uncompressed
+    add("mimetype", b"application/epub+zip", stored)?;
+    add(
+        "META-INF/container.xml",
+        b"<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">\n<rootfiles>\n<rootfile full-path=\"OEBPS/content.opf\" media-type=\"application/oebps-package+xml\" />\n</rootfiles>\n</container>\n",
+        deflated,
+    )?;
+    add(
+        "OEBPS/content.opf",
+        content_opf(&meta, &modified, &chapters, &images).as_bytes(),
+        deflated,
+    )?;
+    add(
+        "OEBPS/nav.xhtml",
+        nav_xhtml(&meta.title, &meta.language, &chapters).as_bytes(),
+        deflated,
+    )?;
+    add("OEBPS/style.css", BOOK_CSS.as_bytes(), deflated)?;
+    for chapter in &chapters {
+        let page = chapter_xhtml(&chapter.title, &meta.language, &chapter.body);
+        add(
+            &format!("OEBPS/{}", chapter.file),
+            page.as_bytes(),
+            deflated,
+        )?;
+    }
+    for image in &images {
+        let bytes = fs::read(&image.path).map_err(|e| e.to_string())?;
+        add(&format!("OEBPS/{}", image.href), &bytes, deflated)?;
+    }
+
+    let bytes = zip.finish().map_err(|e| e.to_string())?.into_inner();
+    store::write_atomic(&output, bytes)?;
+    Ok(output.to_string_lossy().to_string())
+}
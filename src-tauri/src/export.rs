@@ -0,0 +1,728 @@
+use crate::settings::SettingsState;
+use crate::{blocks, cache, frontmatter, highlight, presentation, store, RenderOptions};
+use base64::Engine;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const APP_CSS: &str = include_str!("../../src/styles.css");
+
+// The app stylesheet assumes a fixed-size window; exported pages scroll
+const EXPORT_CSS: &str = "html, body { height: auto; overflow: auto; border-radius: 0; }
+.markdown-body { box-sizing: border-box; max-width: 860px; margin: 0 auto; padding: 32px; }
+@media print { .markdown-body { max-width: none; padding: 0; } }";
+
+// Same versions as the app's own preview
+const KATEX_URL: &str = "https://cdn.jsdelivr.net/npm/katex@0.16.27/dist";
+const MERMAID_URL: &str = "https://cdn.jsdelivr.net/npm/mermaid@11.12.2/dist/mermaid.min.js";
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct HtmlExportOptions {
+    /// Output file; defaults to the note's path with an `.html` extension
+    output: Option<String>,
+    /// "light", "dark" or "system"; defaults to the app's saved theme
+    theme: Option<String>,
+    #[serde(flatten)]
+    render: RenderOptions,
+}
+
+pub fn mime_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "avif" => "image/avif",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Rewrites `src` attributes that point at local files (see
+/// `assets::asset_url`) with `replace`. Returning `None` keeps the URL.
+pub fn rewrite_local_assets(
+    html: &str,
+    mut replace: impl FnMut(&Path) -> Option<String>,
+) -> String {
+    static ASSET: OnceLock<Regex> = OnceLock::new();
+    let re = ASSET.get_or_init(|| {
+        Regex::new(r#"\bsrc="(?:asset://localhost/|http://asset\.localhost/)([^"]*)""#).unwrap()
+    });
+    re.replace_all(html, |caps: &Captures| {
+        let decoded = urlencoding::decode(&caps[1])
+            .map(|s| s.into_owned())
+            .unwrap_or_else(|_| caps[1].to_string());
+        match replace(Path::new(&decoded)) {
+            Some(src) => format!("src=\"{}\"", crate::escape_attr(&src)),
+            None => caps[0].to_string(),
+        }
+    })
+    .into_owned()
+}
+
+/// Replaces local asset URLs with base64 data URIs so the page has no
+/// outside dependencies. Files that can't be read keep their URL.
+pub fn inline_assets(html: &str) -> String {
+    rewrite_local_assets(html, |path| {
+        let bytes = fs::read(path).ok()?;
+        Some(format!(
+            "data:{};base64,{}",
+            mime_type(path),
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        ))
+    })
+}
+
+pub fn saved_theme(app: &AppHandle) -> String {
+    app.state::<SettingsState>()
+        .get(app)
+        .theme
+        .as_str()
+        .to_string()
+}
+
+/// The app and code highlighting styles, adjusted for a scrolling page.
+pub fn page_css() -> String {
+    format!(
+        "{}\n{}\n{}",
+        APP_CSS,
+        highlight::get_highlight_css().unwrap_or_default(),
+        EXPORT_CSS
+    )
+}
+
+// "system" leaves the stylesheet's prefers-color-scheme rules in charge
+pub fn theme_attr(theme: &str) -> String {
+    match theme {
+        "light" | "dark" => format!(" data-theme=\"{}\"", theme),
+        _ => String::new(),
+    }
+}
+
+/// The note's frontmatter title, else its file name.
+pub fn note_title(content: &str, path: &Path) -> String {
+    frontmatter::parse(content)
+        .ok()
+        .flatten()
+        .and_then(|fm| fm.title)
+        .unwrap_or_else(|| {
+            path.file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        })
+}
+
+/// A library an exported page needs, downloaded once into the artifact cache
+/// so later exports work offline. `None` when it can't be fetched.
+fn vendor_asset(app: &AppHandle, url: &str) -> Option<String> {
+    let key = format!("{:016x}", blocks::fnv1a(url.as_bytes()));
+    if let Some(text) = cache::get(app, "vendor", &key).and_then(|p| fs::read_to_string(p).ok()) {
+        return Some(text);
+    }
+    let text = ureq::get(url)
+        .timeout(Duration::from_secs(15))
+        .call()
+        .ok()?
+        .into_string()
+        .ok()?;
+    let _ = cache::put(app, "vendor", &key, text.as_bytes());
+    Some(text)
+}
+
+fn script(app: &AppHandle, url: &str) -> String {
+    match vendor_asset(app, url) {
+        Some(js) => format!(
+            "<script>\n{}\n</script>\n",
+            js.replace("</script", "<\\/script")
+        ),
+        None => format!("<script src=\"{}\"></script>\n", url),
+    }
+}
+
+/// KaTeX and mermaid for the math and diagrams the browser still has to
+/// render, only when the page has any. Inlined when available, otherwise
+/// loaded from the CDN.
+pub fn page_scripts(app: &AppHandle, body: &str) -> String {
+    let mut scripts = String::new();
+    if body.contains("class=\"math ") {
+        // The fonts stay on the CDN; offline, math falls back to system fonts
+        scripts.push_str(
+            &match vendor_asset(app, &format!("{}/katex.min.css", KATEX_URL)) {
+                Some(css) => format!(
+                    "<style>\n{}\n</style>\n",
+                    css.replace("url(fonts/", &format!("url({}/fonts/", KATEX_URL))
+                ),
+                None => format!(
+                    "<link rel=\"stylesheet\" href=\"{}/katex.min.css\">\n",
+                    KATEX_URL
+                ),
+            },
+        );
+        for file in ["katex.min.js", "contrib/auto-render.min.js"] {
+            scripts.push_str(&script(app, &format!("{}/{}", KATEX_URL, file)));
+        }
+        scripts.push_str(
+            "<script>\ndocument.querySelectorAll('.math').forEach((el) => renderMathInElement(el));\n</script>\n",
+        );
+    }
+    if body.contains("class=\"mermaid\"") {
+        scripts.push_str(&script(app, MERMAID_URL));
+        scripts.push_str("<script>\nmermaid.initialize({ startOnLoad: true });\n</script>\n");
+    }
+    scripts
+}
+
+/// Wraps rendered note HTML in a complete page with the app's styles inlined.
+/// `extra_css` is appended last so callers can override layout; `scripts`
+/// go at the end of the body.
+pub fn standalone_page(
+    title: &str,
+    body: &str,
+    theme: &str,
+    extra_css: &str,
+    scripts: &str,
+) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\"{}>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>\n{}\n{}\n</style>\n</head>\n<body>\n<article class=\"markdown-body\">\n{}\n</article>\n{}</body>\n</html>\n",
+        theme_attr(theme),
+        crate::escape_attr(title),
+        page_css(),
+        extra_css,
+        body,
+        scripts
+    )
+}
+
+/// Renders a note into a self-contained HTML page.
+pub fn render_standalone(
+    app: &AppHandle,
+    path: &Path,
+    render: &mut RenderOptions,
+    theme: Option<&str>,
+    extra_css: &str,
+) -> Result<String, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    render
+        .path
+        .get_or_insert_with(|| path.to_string_lossy().to_string());
+    let body = inline_assets(&crate::render_in_vault(app, &content, render));
+    let theme = theme
+        .map(|t| t.to_string())
+        .unwrap_or_else(|| saved_theme(app));
+    Ok(standalone_page(
+        &note_title(&content, path),
+        &body,
+        &theme,
+        extra_css,
+        &page_scripts(app, &body),
+    ))
+}
+
+/// Exports a note as a single .html file with styles and images embedded,
+/// returning the path written.
+#[tauri::command]
+pub async fn export_html(
+    app: AppHandle,
+    path: String,
+    options: Option<HtmlExportOptions>,
+) -> Result<String, String> {
+    let mut options = options.unwrap_or_default();
+    let source = Path::new(&path);
+    let page = render_standalone(
+        &app,
+        source,
+        &mut options.render,
+        options.theme.as_deref(),
+        "",
+    )?;
+    let output = options
+        .output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| source.with_extension("html"));
+    store::write_atomic(&output, page)?;
+    Ok(output.to_string_lossy().to_string())
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct PdfExportOptions {
+    /// Output file; defaults to the note's path with a `.pdf` extension
+    output: Option<String>,
+    /// CSS page size, e.g. "A4", "Letter" or "210mm 297mm"
+    page_size: String,
+    landscape: bool,
+    /// CSS margin shorthand, e.g. "20mm" or "1in 0.75in"
+    margin: String,
+    /// Running header/footer text. `{title}`, `{page}`, `{pages}` and `{date}`
+    /// are substituted.
+    header: Option<String>,
+    footer: Option<String>,
+    /// "weasyprint" or "chrome"; the first one installed is used by default
+    engine: Option<String>,
+    /// Defaults to light, whatever the app theme
+    theme: Option<String>,
+    #[serde(flatten)]
+    render: RenderOptions,
+}
+
+impl Default for PdfExportOptions {
+    fn default() -> Self {
+        PdfExportOptions {
+            output: None,
+            page_size: "A4".to_string(),
+            landscape: false,
+            margin: "20mm".to_string(),
+            header: None,
+            footer: Some("{page} / {pages}".to_string()),
+            engine: None,
+            theme: None,
+            render: RenderOptions::default(),
+        }
+    }
+}
+
+// Page size and margins are spliced into CSS, so only plain lengths get through
+fn css_length(value: &str) -> Result<&str, String> {
+    let valid = !value.trim().is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == ' ');
+    if valid {
+        Ok(value.trim())
+    } else {
+        Err(crate::i18n::tf(
+            "error.invalid_page_setting",
+            &[("value", value)],
+        ))
+    }
+}
+
+/// Turns header/footer text into a CSS `content` value with page counters.
+fn margin_content(text: &str, title: &str) -> String {
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let text = text.replace("{title}", title).replace("{date}", &date);
+    let mut parts = Vec::new();
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find('{') {
+        let (counter, len) = if rest[start..].starts_with("{page}") {
+            ("counter(page)", "{page}".len())
+        } else if rest[start..].starts_with("{pages}") {
+            ("counter(pages)", "{pages}".len())
+        } else {
+            parts.push(css_string(&rest[..=start]));
+            rest = &rest[start + 1..];
+            continue;
+        };
+        parts.push(css_string(&rest[..start]));
+        parts.push(counter.to_string());
+        rest = &rest[start + len..];
+    }
+    parts.push(css_string(rest));
+    parts.retain(|p| p != "\"\"");
+    parts.join(" ")
+}
+
+fn css_string(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        text.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', " ")
+    )
+}
+
+fn print_css(options: &PdfExportOptions, title: &str) -> Result<String, String> {
+    let orientation = if options.landscape { " landscape" } else { "" };
+    let mut css = format!(
+        "@page {{ size: {}{}; margin: {};",
+        css_length(&options.page_size)?,
+        orientation,
+        css_length(&options.margin)?
+    );
+    let boxes = [
+        ("top-center", &options.header),
+        ("bottom-center", &options.footer),
+    ];
+    for (position, text) in boxes {
+        if let Some(text) = text.as_deref().filter(|t| !t.trim().is_empty()) {
+            css.push_str(&format!(
+                " @{} {{ content: {}; font: 9pt sans-serif; color: #656d76; }}",
+                position,
+                margin_content(text, title)
+            ));
+        }
+    }
+    css.push_str(
+        " }\n.markdown-body { max-width: none; padding: 0; }\npre, table, img, blockquote { break-inside: avoid; }\nh1, h2, h3, h4 { break-after: avoid; }",
+    );
+    Ok(css)
+}
+
+fn chrome_candidates() -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if cfg!(target_os = "macos") {
+        for app in [
+            "Google Chrome",
+            "Microsoft Edge",
+            "Chromium",
+            "Brave Browser",
+        ] {
+            candidates.push(PathBuf::from(format!(
+                "/Applications/{}.app/Contents/MacOS/{}",
+                app, app
+            )));
+        }
+    } else if cfg!(windows) {
+        for root in ["C:\\Program Files", "C:\\Program Files (x86)"] {
+            candidates.push(Path::new(root).join("Google\\Chrome\\Application\\chrome.exe"));
+            candidates.push(Path::new(root).join("Microsoft\\Edge\\Application\\msedge.exe"));
+        }
+    } else {
+        for name in [
+            "google-chrome",
+            "google-chrome-stable",
+            "chromium",
+            "chromium-browser",
+            "microsoft-edge",
+        ] {
+            candidates.push(PathBuf::from(name));
+        }
+    }
+    candidates
+}
+
+fn run_engine(command: &mut Command) -> Result<(), String> {
+    let output = command.output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+fn weasyprint(html: &Path, output: &Path) -> Result<(), String> {
+    run_engine(Command::new("weasyprint").arg(html).arg(output))
+}
+
+fn file_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    format!("file:///{}", path.trim_start_matches('/'))
+}
+
+fn chrome(html: &Path, output: &Path) -> Result<(), String> {
+    let mut last_error = crate::i18n::t("error.no_browser");
+    for browser in chrome_candidates() {
+        let result = run_engine(
+            Command::new(&browser)
+                .args(["--headless", "--disable-gpu", "--no-pdf-header-footer"])
+                .arg(format!("--print-to-pdf={}", output.display()))
+                .arg(file_url(html)),
+        );
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// Distinguishes temp pages of exports running at the same time.
+static TEMP_PAGES: AtomicU64 = AtomicU64::new(0);
+
+/// Writes an intermediate page for an external converter to read. Callers
+/// remove it once the converter is done.
+fn write_temp_page(app: &AppHandle, source: &Path, page: &str) -> Result<PathBuf, String> {
+    let temp_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("export");
+    fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
+    let name = blocks::fnv1a(source.to_string_lossy().as_bytes());
+    let html_path = temp_dir.join(format!(
+        "{:016x}-{}-{}.html",
+        name,
+        std::process::id(),
+        TEMP_PAGES.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&html_path, page).map_err(|e| e.to_string())?;
+    Ok(html_path)
+}
+
+/// Exports a note to PDF through a print engine (WeasyPrint, else a
+/// headless Chromium-based browser), returning the path written.
+#[tauri::command]
+pub async fn export_pdf(
+    app: AppHandle,
+    path: String,
+    options: Option<PdfExportOptions>,
+) -> Result<String, String> {
+    let mut options = options.unwrap_or_default();
+    let source = Path::new(&path);
+    let content = fs::read_to_string(source).map_err(|e| e.to_string())?;
+    let css = print_css(&options, &note_title(&content, source))?;
+    let theme = options.theme.take().unwrap_or_else(|| "light".to_string());
+    let page = render_standalone(&app, source, &mut options.render, Some(&theme), &css)?;
+
+    let html_path = write_temp_page(&app, source, &page)?;
+
+    let output = options
+        .output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| source.with_extension("pdf"));
+    let result = match options.engine.as_deref() {
+        Some("weasyprint") => weasyprint(&html_path, &output),
+        Some("chrome") => chrome(&html_path, &output),
+        Some(other) => Err(crate::i18n::tf(
+            "error.unknown_pdf_engine",
+            &[("engine", other)],
+        )),
+        None => weasyprint(&html_path, &output).or_else(|_| chrome(&html_path, &output)),
+    };
+    let _ = fs::remove_file(&html_path);
+    result
+        .map(|_| output.to_string_lossy().to_string())
+        .map_err(|e| format!("PDF export failed: {}", e))
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct DocumentExportOptions {
+    /// Output file; defaults to the note's path with the format's extension
+    output: Option<String>,
+    /// A .docx/.odt whose styles the output copies (pandoc's `--reference-doc`)
+    reference_doc: Option<String>,
+    #[serde(flatten)]
+    render: RenderOptions,
+}
+
+#[derive(Serialize)]
+pub struct PandocInfo {
+    installed: bool,
+    path: Option<String>,
+    version: Option<String>,
+}
+
+/// GUI apps on macOS don't inherit the shell PATH, so Homebrew's
+/// locations are checked explicitly.
+fn pandoc_candidates() -> Vec<PathBuf> {
+    let mut candidates = vec![PathBuf::from("pandoc")];
+    if cfg!(target_os = "macos") {
+        candidates.push(PathBuf::from("/opt/homebrew/bin/pandoc"));
+        candidates.push(PathBuf::from("/usr/local/bin/pandoc"));
+    } else if cfg!(windows) {
+        if let Ok(local) = std::env::var("LOCALAPPDATA") {
+            candidates.push(Path::new(&local).join("Pandoc\\pandoc.exe"));
+        }
+        candidates.push(PathBuf::from("C:\\Program Files\\Pandoc\\pandoc.exe"));
+    }
+    candidates
+}
+
+fn find_pandoc() -> Option<(PathBuf, String)> {
+    pandoc_candidates().into_iter().find_map(|path| {
+        let output = Command::new(&path).arg("--version").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let version = stdout
+            .lines()
+            .next()
+            .and_then(|l| l.split_whitespace().nth(1))
+            .unwrap_or_default()
+            .to_string();
+        Some((path, version))
+    })
+}
+
+#[tauri::command]
+pub fn get_pandoc_info() -> PandocInfo {
+    match find_pandoc() {
+        Some((path, version)) => PandocInfo {
+            installed: true,
+            path: Some(path.to_string_lossy().to_string()),
+            version: Some(version),
+        },
+        None => PandocInfo {
+            installed: false,
+            path: None,
+            version: None,
+        },
+    }
+}
+
+/// Converts a note to .docx or .odt with pandoc. The note is rendered first
+/// so wikilinks, embeds and images come through as they look in the preview.
+#[tauri::command]
+pub async fn export_document(
+    app: AppHandle,
+    path: String,
+    format: String,
+    options: Option<DocumentExportOptions>,
+) -> Result<String, String> {
+    if !matches!(format.as_str(), "docx" | "odt") {
+        return Err(crate::i18n::tf(
+            "error.unsupported_export_format",
+            &[("format", &format)],
+        ));
+    }
+    let (pandoc, _) = find_pandoc().ok_or_else(|| crate::i18n::t("error.pandoc_not_installed"))?;
+
+    let mut options = options.unwrap_or_default();
+    let source = Path::new(&path);
+    let page = render_standalone(&app, source, &mut options.render, Some("light"), "")?;
+    let html_path = write_temp_page(&app, source, &page)?;
+
+    let output = options
+        .output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| source.with_extension(&format));
+    let mut command = Command::new(pandoc);
+    command
+        .args(["--from", "html", "--to", &format, "--standalone"])
+        .arg("--output")
+        .arg(&output)
+        .arg(&html_path);
+    if let Some(reference) = options.reference_doc {
+        command.arg(format!("--reference-doc={}", reference));
+    }
+    if let Some(dir) = source.parent() {
+        command.current_dir(dir);
+    }
+    let result = run_engine(&mut command);
+    let _ = fs::remove_file(&html_path);
+    result
+        .map(|_| output.to_string_lossy().to_string())
+        .map_err(|e| crate::i18n::tf("error.pandoc_failed", &[("error", &e)]))
+}
+
+// Slide markup follows reveal.js (`.reveal > .slides > section`, notes in
+// `aside.notes`) but ships its own navigation so the deck works offline
+const DECK_CSS: &str = "html, body { height: 100%; overflow: hidden; border-radius: 0; }
+.reveal { position: fixed; inset: 0; background: var(--color-canvas-default); }
+.reveal .slides > section { display: none; position: absolute; inset: 0; box-sizing: border-box; padding: 6vh 8vw; overflow: auto; font-size: 28px; flex-direction: column; justify-content: center; }
+.reveal .slides > section.present { display: flex; }
+.reveal aside.notes { display: none; }
+.reveal .progress { position: fixed; left: 0; bottom: 0; height: 4px; background: var(--color-accent-fg); transition: width 0.2s; }
+.reveal .slide-number { position: fixed; right: 16px; bottom: 12px; font: 14px sans-serif; color: var(--color-fg-muted); }
+@media print {
+  html, body { height: auto; overflow: visible; }
+  .reveal { position: static; }
+  .reveal .slides > section { display: flex; position: relative; height: 100vh; break-after: page; }
+  .reveal .progress, .reveal .slide-number { display: none; }
+}";
+
+const DECK_JS: &str = r#"(function () {
+  var slides = document.querySelectorAll('.reveal .slides > section');
+  var progress = document.querySelector('.reveal .progress');
+  var number = document.querySelector('.reveal .slide-number');
+  var current = 0;
+  function show(index) {
+    current = Math.max(0, Math.min(slides.length - 1, index));
+    slides.forEach(function (slide, i) { slide.classList.toggle('present', i === current); });
+    progress.style.width = ((current + 1) / slides.length) * 100 + '%';
+    number.textContent = current + 1 + ' / ' + slides.length;
+    history.replaceState(null, '', '#/' + (current + 1));
+  }
+  document.addEventListener('keydown', function (e) {
+    if (['ArrowRight', 'ArrowDown', 'PageDown', ' ', 'Enter'].indexOf(e.key) >= 0) show(current + 1);
+    else if (['ArrowLeft', 'ArrowUp', 'PageUp', 'Backspace'].indexOf(e.key) >= 0) show(current - 1);
+    else if (e.key === 'Home') show(0);
+    else if (e.key === 'End') show(slides.length - 1);
+    else return;
+    e.preventDefault();
+  });
+  document.addEventListener('click', function (e) {
+    if (e.target.closest('a, audio, video')) return;
+    show(current + (e.clientX < window.innerWidth / 3 ? -1 : 1));
+  });
+  var start = parseInt((location.hash.match(/^#\/(\d+)/) || [])[1], 10);
+  show(isNaN(start) ? 0 : start - 1);
+})();"#;
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct SlidesExportOptions {
+    /// Output file; defaults to `<note>.slides.html` next to the note
+    output: Option<String>,
+    /// "light", "dark" or "system"; defaults to the app's saved theme
+    theme: Option<String>,
+    #[serde(flatten)]
+    render: RenderOptions,
+}
+
+/// Exports a note as a standalone HTML slide deck, one slide per section
+/// between horizontal rules, with speaker notes kept in `aside.notes`.
+#[tauri::command]
+pub async fn export_slides(
+    app: AppHandle,
+    path: String,
+    options: Option<SlidesExportOptions>,
+) -> Result<String, String> {
+    let mut options = options.unwrap_or_default();
+    let source = Path::new(&path);
+    let content = fs::read_to_string(source).map_err(|e| e.to_string())?;
+    let slides = presentation::split_slides(&content);
+    if slides.is_empty() {
+        return Err(crate::i18n::t("error.no_slides"));
+    }
+
+    let render = &mut options.render;
+    render.path.get_or_insert_with(|| path.clone());
+    let mut sections = String::new();
+    for slide in &slides {
+        let html = inline_assets(&crate::render_in_vault(&app, &slide.content, render));
+        let notes = if slide.notes.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "<aside class=\"notes\">{}</aside>",
+                crate::render_in_vault(&app, &slide.notes, render)
+            )
+        };
+        sections.push_str(&format!(
+            "<section class=\"markdown-body\">\n{}{}</section>\n",
+            html, notes
+        ));
+    }
+
+    let theme = options.theme.unwrap_or_else(|| saved_theme(&app));
+    let page = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\"{}>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>\n{}\n{}\n{}\n</style>\n</head>\n<body>\n<div class=\"reveal\">\n<div class=\"slides\">\n{}</div>\n<div class=\"progress\"></div>\n<div class=\"slide-number\"></div>\n</div>\n{}<script>\n{}\n</script>\n</body>\n</html>\n",
+        theme_attr(&theme),
+        crate::escape_attr(&note_title(&content, source)),
+        APP_CSS,
+        highlight::get_highlight_css().unwrap_or_default(),
+        DECK_CSS,
+        sections,
+        page_scripts(&app, &sections),
+        DECK_JS
+    );
+
+    let output = options.output.map(PathBuf::from).unwrap_or_else(|| {
+        let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+        source.with_file_name(format!("{}.slides.html", stem))
+    });
+    store::write_atomic(&output, page)?;
+    Ok(output.to_string_lossy().to_string())
+}
@@ -0,0 +1,311 @@
+use crate::autosave::AutosaveState;
+use crate::{encoding, git, index, pins, vault};
+use serde::Serialize;
+use std::fs::{self, File, FileTimes};
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Whether a rename failed only because source and target are on different
+/// filesystems, which a copy can work around.
+fn crosses_devices(e: &io::Error) -> bool {
+    // EXDEV on Unix, ERROR_NOT_SAME_DEVICE on Windows
+    let code = if cfg!(windows) { 17 } else { 18 };
+    e.raw_os_error() == Some(code)
+}
+
+/// Gives `dest` the access and modification times of `src`.
+fn copy_times(src: &Path, dest: &Path) -> io::Result<()> {
+    let meta = fs::metadata(src)?;
+    let times = FileTimes::new()
+        .set_accessed(meta.accessed()?)
+        .set_modified(meta.modified()?);
+    File::options().write(true).open(dest)?.set_times(times)
+}
+
+/// Copies a file, or a folder with everything in it, keeping timestamps and
+/// calling `on_file` after each file.
+pub fn copy_tree(src: &Path, dest: &Path, on_file: &mut dyn FnMut(&Path)) -> io::Result<()> {
+    if fs::symlink_metadata(src)?.is_symlink() {
+        // Links are recreated rather than followed, which also keeps cyclic
+        // links from recursing forever
+        copy_symlink(src, dest)?;
+        on_file(src);
+    } else if src.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &dest.join(entry.file_name()), on_file)?;
+        }
+        // Folders can't be opened for writing on every platform; their
+        // times are a nicety, so a failure here isn't fatal
+        let _ = copy_times(src, dest);
+    } else {
+        fs::copy(src, dest)?;
+        copy_times(src, dest)?;
+        on_file(src);
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn copy_symlink(src: &Path, dest: &Path) -> io::Result<()> {
+    let target = fs::read_link(src)?;
+    if src.is_dir() {
+        std::os::windows::fs::symlink_dir(target, dest)
+    } else {
+        std::os::windows::fs::symlink_file(target, dest)
+    }
+}
+
+#[cfg(not(windows))]
+fn copy_symlink(src: &Path, dest: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(src)?, dest)
+}
+
+fn count_files(path: &Path) -> usize {
+    if fs::symlink_metadata(path).is_ok_and(|m| m.is_symlink()) {
+        return 1;
+    }
+    match fs::read_dir(path) {
+        Ok(entries) => entries.flatten().map(|e| count_files(&e.path())).sum(),
+        Err(_) => 1,
+    }
+}
+
+pub fn remove_path(path: &Path) -> io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Whether two paths name the same file, e.g. `Note.md` and `note.md` on a
+/// case-insensitive filesystem.
+fn same_file(a: &Path, b: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (fs::symlink_metadata(a), fs::symlink_metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+            _ => false,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        matches!((fs::canonicalize(a), fs::canonicalize(b)), (Ok(a), Ok(b)) if a == b)
+    }
+}
+
+fn move_tree(old: &Path, new: &Path) -> Result<(), String> {
+    match fs::rename(old, new) {
+        Ok(()) => Ok(()),
+        Err(e) if crosses_devices(&e) => {
+            copy_tree(old, new, &mut |_| {}).map_err(|e| {
+                // Leave the original in place rather than half a copy
+                let _ = remove_path(new);
+                e.to_string()
+            })?;
+            remove_path(old).map_err(|e| e.to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Moves a file or folder. An existing destination is only replaced when
+/// `overwrite` is set, and moves between filesystems fall back to copying
+/// and deleting the original.
+#[tauri::command]
+pub fn move_path(
+    app: AppHandle,
+    old_path: String,
+    new_path: String,
+    overwrite: Option<bool>,
+) -> Result<(), String> {
+    let old = Path::new(&old_path);
+    let new = Path::new(&new_path);
+    if !old.exists() {
+        return Err(crate::i18n::tf(
+            "error.does_not_exist",
+            &[("path", &old.display().to_string())],
+        ));
+    }
+    if old == new {
+        return Ok(());
+    }
+    if new.starts_with(old) {
+        return Err(crate::i18n::t("error.move_into_itself"));
+    }
+    // A case-only rename finds the note itself at the destination
+    let mut displaced = None;
+    if new.exists() && !same_file(old, new) {
+        if !overwrite.unwrap_or(false) {
+            return Err(crate::i18n::tf(
+                "error.already_exists",
+                &[("path", &new.display().to_string())],
+            ));
+        }
+        // Moved aside and only deleted once the move succeeded
+        let name = new.file_name().unwrap_or_default().to_string_lossy();
+        let aside = new.with_file_name(format!(".{}.{}.replaced", name, std::process::id()));
+        fs::rename(new, &aside).map_err(|e| e.to_string())?;
+        displaced = Some(aside);
+    }
+    if let Some(parent) = new.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    if let Err(e) = move_tree(old, new) {
+        if let Some(aside) = &displaced {
+            let _ = fs::rename(aside, new);
+        }
+        return Err(e);
+    }
+    if let Some(aside) = &displaced {
+        let _ = remove_path(aside);
+    }
+    app.state::<AutosaveState>().moved(old, new);
+    pins::rewrite_pins(&app, old, new)
+}
+
+#[derive(Serialize, Clone)]
+struct CopyProgress {
+    source: String,
+    copied: usize,
+    total: usize,
+}
+
+/// Copies a file or folder (recursively) to `dest`, which must not exist,
+/// keeping timestamps. Emits "copy-progress" while copying large trees.
+#[tauri::command]
+pub async fn copy_path(app: AppHandle, source: String, dest: String) -> Result<(), String> {
+    let src = Path::new(&source);
+    let dest = Path::new(&dest);
+    if !src.exists() {
+        return Err(crate::i18n::tf(
+            "error.does_not_exist",
+            &[("path", &src.display().to_string())],
+        ));
+    }
+    if dest.exists() {
+        return Err(crate::i18n::tf(
+            "error.already_exists",
+            &[("path", &dest.display().to_string())],
+        ));
+    }
+    if dest.starts_with(src) {
+        return Err(crate::i18n::t("error.copy_into_itself"));
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let total = count_files(src);
+    let mut copied = 0;
+    let mut last_emit = Instant::now();
+    copy_tree(src, dest, &mut |_| {
+        copied += 1;
+        if copied == total || last_emit.elapsed() >= Duration::from_millis(100) {
+            last_emit = Instant::now();
+            let _ = app.emit(
+                "copy-progress",
+                CopyProgress {
+                    source: source.clone(),
+                    copied,
+                    total,
+                },
+            );
+        }
+    })
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Serialize)]
+pub struct FileInfo {
+    path: String,
+    is_dir: bool,
+    /// Bytes; for folders, the total of every file inside
+    size: u64,
+    created_at: Option<u64>,
+    modified_at: Option<u64>,
+    /// Files inside a folder, recursively
+    file_count: Option<usize>,
+    /// Counts for markdown notes
+    word_count: Option<usize>,
+    line_count: Option<usize>,
+    char_count: Option<usize>,
+    /// `None` outside a git repository
+    git_tracked: Option<bool>,
+}
+
+fn unix_secs(time: io::Result<SystemTime>) -> Option<u64> {
+    time.ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+}
+
+/// Total size and number of files below `path`.
+fn tree_size(path: &Path) -> (u64, usize) {
+    let Ok(entries) = fs::read_dir(path) else {
+        return (0, 0);
+    };
+    entries
+        .flatten()
+        .fold((0, 0), |(size, count), entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => {
+                let (s, c) = tree_size(&entry.path());
+                (size + s, count + c)
+            }
+            _ => (
+                size + entry.metadata().map(|m| m.len()).unwrap_or(0),
+                count + 1,
+            ),
+        })
+}
+
+fn git_tracked(path: &Path, is_dir: bool) -> Option<bool> {
+    let (repo, relative) = git::open_for(path).ok()?;
+    let index = repo.index().ok()?;
+    if !is_dir {
+        return Some(index.get_path(&relative, 0).is_some());
+    }
+    Some(
+        index
+            .iter()
+            .any(|entry| Path::new(&*String::from_utf8_lossy(&entry.path)).starts_with(&relative)),
+    )
+}
+
+/// Properties of a file or folder for the info panel.
+#[tauri::command]
+pub fn get_file_info(path: String) -> Result<FileInfo, String> {
+    let p = Path::new(&path);
+    let meta = fs::metadata(p).map_err(|e| e.to_string())?;
+    let is_dir = meta.is_dir();
+
+    let (size, file_count) = if is_dir {
+        let (size, count) = tree_size(p);
+        (size, Some(count))
+    } else {
+        (meta.len(), None)
+    };
+    let text = (!is_dir && vault::is_markdown(p))
+        .then(|| fs::read(p).ok())
+        .flatten()
+        .map(|bytes| encoding::decode(&bytes).0);
+
+    Ok(FileInfo {
+        is_dir,
+        size,
+        created_at: unix_secs(meta.created()),
+        modified_at: unix_secs(meta.modified()),
+        file_count,
+        word_count: text.as_deref().map(index::count_words),
+        line_count: text.as_deref().map(|t| t.lines().count()),
+        char_count: text.as_deref().map(|t| t.chars().count()),
+        git_tracked: git_tracked(p, is_dir),
+        path,
+    })
+}
@@ -0,0 +1,162 @@
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+struct Definition {
+    label: String,
+    lines: Vec<String>,
+}
+
+fn is_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+// Replace footnote references on a line, leaving inline code spans untouched
+fn replace_refs(line: &str, re: &Regex, numbers: &HashMap<String, usize>) -> String {
+    line.split('`')
+        .enumerate()
+        .map(|(i, segment)| {
+            if i % 2 == 1 {
+                return segment.to_string();
+            }
+            re.replace_all(segment, |caps: &Captures| match numbers.get(&caps[1]) {
+                Some(n) => format!("[^{}]", n),
+                None => caps[0].to_string(),
+            })
+            .into_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("`")
+}
+
+// Ends a definition; trailing blank lines captured after it belong to the body
+fn finish(mut def: Definition, definitions: &mut Vec<Definition>, body: &mut Vec<String>) {
+    let mut keep = def.lines.len();
+    while keep > 1 && def.lines[keep - 1].trim().is_empty() {
+        keep -= 1;
+    }
+    body.extend(def.lines.drain(keep..));
+    definitions.push(def);
+}
+
+/// Renumbers footnotes in order of first reference, drops definitions that are
+/// never referenced and moves all remaining definitions to the end of the document.
+pub fn cleanup_footnotes(content: &str) -> String {
+    let def_re = Regex::new(r"^\[\^([^\]\s]+)\]:[ \t]?(.*)$").unwrap();
+    let ref_re = Regex::new(r"\[\^([^\]\s]+)\]").unwrap();
+
+    let mut body: Vec<String> = Vec::new();
+    let mut definitions: Vec<Definition> = Vec::new();
+    let mut in_fence = false;
+    let mut current: Option<Definition> = None;
+
+    for line in content.lines() {
+        if let Some(def) = current.as_mut() {
+            // Continuation lines are indented; blank lines stay with the definition
+            // only if more indented content follows, so buffer them for now
+            if line.starts_with("    ") || line.starts_with('\t') || line.trim().is_empty() {
+                def.lines.push(line.to_string());
+                continue;
+            }
+            finish(current.take().unwrap(), &mut definitions, &mut body);
+        }
+
+        if is_fence(line) {
+            in_fence = !in_fence;
+        }
+
+        if !in_fence {
+            if let Some(caps) = def_re.captures(line) {
+                current = Some(Definition {
+                    label: caps[1].to_string(),
+                    lines: vec![caps[2].to_string()],
+                });
+                continue;
+            }
+        }
+
+        body.push(line.to_string());
+    }
+    if let Some(def) = current.take() {
+        finish(def, &mut definitions, &mut body);
+    }
+
+    let defined: HashMap<&str, usize> = definitions
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, d)| (d.label.as_str(), i))
+        .collect();
+
+    // Assign new numbers in order of first reference in the body
+    let mut numbers: HashMap<String, usize> = HashMap::new();
+    let mut order: Vec<usize> = Vec::new();
+    in_fence = false;
+    for line in &body {
+        if is_fence(line) {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        for (i, segment) in line.split('`').enumerate() {
+            if i % 2 == 1 {
+                continue;
+            }
+            for caps in ref_re.captures_iter(segment) {
+                let label = &caps[1];
+                if numbers.contains_key(label) {
+                    continue;
+                }
+                if let Some(&idx) = defined.get(label) {
+                    numbers.insert(label.to_string(), order.len() + 1);
+                    order.push(idx);
+                }
+            }
+        }
+    }
+
+    let mut output: Vec<String> = Vec::with_capacity(body.len());
+    in_fence = false;
+    for line in &body {
+        if is_fence(line) {
+            in_fence = !in_fence;
+            output.push(line.clone());
+        } else if in_fence {
+            output.push(line.clone());
+        } else {
+            output.push(replace_refs(line, &ref_re, &numbers));
+        }
+    }
+
+    while output.last().is_some_and(|l| l.trim().is_empty()) {
+        output.pop();
+    }
+
+    if !order.is_empty() {
+        output.push(String::new());
+        for (n, idx) in order.iter().enumerate() {
+            let def = &definitions[*idx];
+            output.push(format!(
+                "[^{}]: {}",
+                n + 1,
+                replace_refs(&def.lines[0], &ref_re, &numbers)
+            ));
+            for cont in &def.lines[1..] {
+                output.push(replace_refs(cont, &ref_re, &numbers));
+            }
+        }
+    }
+
+    let mut result = output.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+#[tauri::command]
+pub fn renumber_footnotes(content: String) -> String {
+    cleanup_footnotes(&content)
+}
@@ -0,0 +1,100 @@
+use crate::blocks;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+
+#[derive(Serialize, Default, Clone)]
+pub struct Frontmatter {
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub aliases: Vec<String>,
+    pub date: Option<String>,
+    /// Every key in the block, including the ones above
+    pub fields: Map<String, Value>,
+    /// Line index just past the closing `---`
+    pub end_line: usize,
+}
+
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+// Accepts both YAML lists and "a, b" / "a b" strings, as Obsidian does
+fn string_list(value: Option<&Value>, split_spaces: bool) -> Vec<String> {
+    let items: Vec<String> = match value {
+        Some(Value::Array(items)) => items.iter().filter_map(scalar_string).collect(),
+        Some(Value::String(s)) if split_spaces => {
+            s.split([',', ' ']).map(|item| item.to_string()).collect()
+        }
+        Some(Value::String(s)) => s.split(',').map(|item| item.to_string()).collect(),
+        _ => Vec::new(),
+    };
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Parses a leading YAML frontmatter block. Returns `Ok(None)` when the note
+/// has none and an error when the YAML is malformed.
+pub fn parse(content: &str) -> Result<Option<Frontmatter>, String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let end_line = blocks::frontmatter_len(&lines);
+    if end_line == 0 {
+        return Ok(None);
+    }
+
+    let yaml = lines[1..end_line - 1].join("\n");
+    let fields = match serde_yaml::from_str::<Value>(&yaml).map_err(|e| e.to_string())? {
+        Value::Object(fields) => fields,
+        Value::Null => Map::new(),
+        _ => return Err(crate::i18n::t("error.frontmatter_not_mapping")),
+    };
+
+    Ok(Some(Frontmatter {
+        title: fields.get("title").and_then(scalar_string),
+        tags: string_list(fields.get("tags").or(fields.get("tag")), true)
+            .into_iter()
+            .map(|tag| tag.trim_start_matches('#').to_string())
+            .collect(),
+        aliases: string_list(fields.get("aliases").or(fields.get("alias")), false),
+        date: fields.get("date").and_then(scalar_string),
+        fields,
+        end_line,
+    }))
+}
+
+/// Blanks out the frontmatter block, keeping line numbers (and so comrak's
+/// source positions) unchanged.
+pub fn blank_frontmatter(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let end_line = blocks::frontmatter_len(&lines);
+    if end_line == 0 {
+        return content.to_string();
+    }
+    let body: usize = content
+        .split_inclusive('\n')
+        .take(end_line)
+        .map(|line| line.len())
+        .sum();
+    format!("{}{}", "\n".repeat(end_line), &content[body..])
+}
+
+#[tauri::command]
+pub fn parse_frontmatter(
+    path: Option<String>,
+    content: Option<String>,
+) -> Result<Option<Frontmatter>, String> {
+    let content = match (content, path) {
+        (Some(content), _) => content,
+        (None, Some(path)) => fs::read_to_string(path).map_err(|e| e.to_string())?,
+        (None, None) => return Err(crate::i18n::t("error.path_or_content_required")),
+    };
+    parse(&content)
+}
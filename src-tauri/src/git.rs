@@ -0,0 +1,1100 @@
+use crate::git_credentials;
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{
+    BranchType, Commit, Cred, CredentialType, Diff, DiffOptions, FetchOptions, IndexAddOption,
+    ObjectType, Oid, Patch, PushOptions, RemoteCallbacks, Repository, RepositoryState, Sort,
+    StatusOptions,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Opens the repository containing `path`.
+pub fn discover(path: impl AsRef<Path>) -> Result<Repository, String> {
+    Repository::discover(path).map_err(|_| crate::i18n::t("error.not_a_git_repository"))
+}
+
+/// Opens the repository containing `path` and returns it with the path
+/// relative to its working directory.
+pub fn open_for(path: &Path) -> Result<(Repository, PathBuf), String> {
+    let repo = discover(path.parent().unwrap_or(path))?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| crate::i18n::t("error.bare_repository"))?;
+    let relative = path
+        .strip_prefix(workdir)
+        .map_err(|e| e.to_string())?
+        .to_path_buf();
+    Ok((repo, relative))
+}
+
+/// Whether the repository's ignore rules exclude `path`.
+pub fn is_ignored(repo: &Repository, path: &Path) -> bool {
+    let Some(relative) = repo.workdir().and_then(|w| path.strip_prefix(w).ok()) else {
+        return false;
+    };
+    // Directory patterns like `build/` only match with the trailing slash
+    let relative = if path.is_dir() {
+        PathBuf::from(format!("{}/", relative.to_string_lossy()))
+    } else {
+        relative.to_path_buf()
+    };
+    repo.is_path_ignored(relative).unwrap_or(false)
+}
+
+#[derive(Serialize)]
+pub struct GitCommit {
+    hash: String,
+    short_hash: String,
+    author: String,
+    email: String,
+    /// Unix seconds
+    date: i64,
+    /// First line of the message
+    summary: String,
+    message: String,
+}
+
+impl From<&Commit<'_>> for GitCommit {
+    fn from(commit: &Commit) -> Self {
+        let hash = commit.id().to_string();
+        let author = commit.author();
+        GitCommit {
+            short_hash: hash[..7].to_string(),
+            hash,
+            author: author.name().unwrap_or_default().to_string(),
+            email: author.email().unwrap_or_default().to_string(),
+            date: commit.time().seconds(),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            message: commit.message().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+/// The blob id of `path` in a commit's tree, if the file exists there.
+pub fn blob_id(commit: &Commit, path: &Path) -> Option<Oid> {
+    commit
+        .tree()
+        .ok()?
+        .get_path(path)
+        .ok()
+        .map(|entry| entry.id())
+}
+
+/// Whether `commit` changed `path`: it differs from every parent, which
+/// matches `git log -- path` history simplification for merges.
+fn touches(commit: &Commit, path: &Path) -> bool {
+    let own = blob_id(commit, path);
+    if commit.parent_count() == 0 {
+        return own.is_some();
+    }
+    commit.parents().all(|parent| blob_id(&parent, path) != own)
+}
+
+/// Commits on the current branch that touched `path`, newest first.
+#[tauri::command]
+pub fn git_file_history(
+    path: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<Vec<GitCommit>, String> {
+    let (repo, relative) = open_for(Path::new(&path))?;
+    let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+    if revwalk.push_head().is_err() {
+        // No commits yet
+        return Ok(Vec::new());
+    }
+    revwalk
+        .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .map_err(|e| e.to_string())?;
+
+    Ok(revwalk
+        .filter_map(|oid| repo.find_commit(oid.ok()?).ok())
+        .filter(|commit| touches(commit, &relative))
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(50))
+        .map(|commit| GitCommit::from(&commit))
+        .collect())
+}
+
+#[derive(Serialize)]
+pub struct DiffLine {
+    /// "context", "add" or "delete"
+    kind: &'static str,
+    content: String,
+    old_line: Option<u32>,
+    new_line: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct DiffHunk {
+    /// The `@@ -a,b +c,d @@` line
+    header: String,
+    old_start: u32,
+    old_lines: u32,
+    new_start: u32,
+    new_lines: u32,
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Serialize)]
+pub struct FileDiff {
+    /// Working tree against the last commit: everything not yet committed
+    head: Vec<DiffHunk>,
+    /// Working tree against the index: what staging would still add
+    index: Vec<DiffHunk>,
+    binary: bool,
+}
+
+/// The hunks of one file's patch.
+pub fn patch_hunks(patch: &Patch) -> Result<Vec<DiffHunk>, String> {
+    let mut hunks = Vec::new();
+    for h in 0..patch.num_hunks() {
+        let (hunk, count) = patch.hunk(h).map_err(|e| e.to_string())?;
+        let lines = (0..count)
+            .filter_map(|l| patch.line_in_hunk(h, l).ok())
+            .filter_map(|line| {
+                let kind = match line.origin() {
+                    ' ' => "context",
+                    '+' => "add",
+                    '-' => "delete",
+                    // "\ No newline at end of file" markers
+                    _ => return None,
+                };
+                Some(DiffLine {
+                    kind,
+                    content: String::from_utf8_lossy(line.content())
+                        .trim_end_matches(['\n', '\r'])
+                        .to_string(),
+                    old_line: line.old_lineno(),
+                    new_line: line.new_lineno(),
+                })
+            })
+            .collect();
+        hunks.push(DiffHunk {
+            header: String::from_utf8_lossy(hunk.header())
+                .trim_end()
+                .to_string(),
+            old_start: hunk.old_start(),
+            old_lines: hunk.old_lines(),
+            new_start: hunk.new_start(),
+            new_lines: hunk.new_lines(),
+            lines,
+        });
+    }
+    Ok(hunks)
+}
+
+/// Hunks of every file in `diff` (in practice the one the pathspec selects).
+pub fn diff_hunks(diff: &Diff) -> Result<(Vec<DiffHunk>, bool), String> {
+    let mut hunks = Vec::new();
+    let mut binary = false;
+    for i in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(diff, i).map_err(|e| e.to_string())? else {
+            continue;
+        };
+        binary |= patch.delta().flags().is_binary();
+        hunks.extend(patch_hunks(&patch)?);
+    }
+    Ok((hunks, binary))
+}
+
+pub fn diff_options(relative: &Path) -> DiffOptions {
+    let mut opts = DiffOptions::new();
+    opts.pathspec(relative)
+        .disable_pathspec_match(true)
+        .include_untracked(true)
+        .show_untracked_content(true)
+        .recurse_untracked_dirs(true);
+    opts
+}
+
+/// Uncommitted changes to a file as diff hunks, so they can be reviewed
+/// before committing.
+#[tauri::command]
+pub fn git_diff(path: String) -> Result<FileDiff, String> {
+    let (repo, relative) = open_for(Path::new(&path))?;
+    let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+
+    let vs_head = repo
+        .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut diff_options(&relative)))
+        .map_err(|e| e.to_string())?;
+    let vs_index = repo
+        .diff_index_to_workdir(None, Some(&mut diff_options(&relative)))
+        .map_err(|e| e.to_string())?;
+
+    let (head, head_binary) = diff_hunks(&vs_head)?;
+    let (index, index_binary) = diff_hunks(&vs_index)?;
+    Ok(FileDiff {
+        head,
+        index,
+        binary: head_binary || index_binary,
+    })
+}
+
+/// A commit from a hash, short hash or any revspec git understands.
+pub fn find_commit<'r>(repo: &'r Repository, spec: &str) -> Result<Commit<'r>, String> {
+    repo.revparse_single(spec)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| e.to_string())
+}
+
+/// Bytes of `relative` as of `commit`.
+pub fn blob_at(repo: &Repository, commit: &Commit, relative: &Path) -> Result<Vec<u8>, String> {
+    let entry = commit
+        .tree()
+        .and_then(|tree| tree.get_path(relative))
+        .map_err(|_| {
+            crate::i18n::tf(
+                "error.not_in_commit",
+                &[
+                    ("path", &relative.display().to_string()),
+                    ("commit", &commit.id().to_string()),
+                ],
+            )
+        })?;
+    let blob = repo.find_blob(entry.id()).map_err(|e| e.to_string())?;
+    Ok(blob.content().to_vec())
+}
+
+/// A file's content as of `commit`, for comparing against or restoring
+/// an older version.
+#[tauri::command]
+pub fn git_show_file(path: String, commit: String) -> Result<String, String> {
+    let (repo, relative) = open_for(Path::new(&path))?;
+    let commit = find_commit(&repo, &commit)?;
+    String::from_utf8(blob_at(&repo, &commit, &relative)?)
+        .map_err(|_| crate::i18n::t("error.binary_diff"))
+}
+
+/// Replaces the working copy of a file with its version from `commit`,
+/// leaving the change unstaged. Refuses when the file has uncommitted
+/// changes that would be lost, unless `force` is set.
+#[tauri::command]
+pub fn git_restore_file(path: String, commit: String, force: Option<bool>) -> Result<(), String> {
+    let file_path = Path::new(&path);
+    let (repo, relative) = open_for(file_path)?;
+    let commit = find_commit(&repo, &commit)?;
+    let content = blob_at(&repo, &commit, &relative)?;
+
+    if !force.unwrap_or(false) && file_path.exists() {
+        let status = repo.status_file(&relative).map_err(|e| e.to_string())?;
+        if !status.is_empty() && !status.is_ignored() {
+            return Err("uncommitted_changes".to_string());
+        }
+    }
+
+    if let Some(parent) = file_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    crate::store::write_atomic(file_path, content)
+}
+
+#[derive(Serialize)]
+pub struct BlameLine {
+    /// 1-based
+    line: usize,
+    /// None for lines changed since the last commit
+    hash: Option<String>,
+    author: String,
+    /// Unix seconds, 0 for uncommitted lines
+    date: i64,
+    summary: String,
+}
+
+/// Who last changed each line of a file and when. Unsaved-to-git edits in
+/// the working copy show up as uncommitted lines.
+#[tauri::command]
+pub fn git_blame(path: String) -> Result<Vec<BlameLine>, String> {
+    let file_path = Path::new(&path);
+    let (repo, relative) = open_for(file_path)?;
+    let committed = repo
+        .blame_file(&relative, None)
+        .map_err(|e| e.to_string())?;
+    let content = std::fs::read(file_path).map_err(|e| e.to_string())?;
+    let blame = committed
+        .blame_buffer(&content)
+        .map_err(|e| e.to_string())?;
+
+    let mut lines = Vec::new();
+    for hunk in blame.iter() {
+        let oid = hunk.final_commit_id();
+        let commit = (!oid.is_zero())
+            .then(|| repo.find_commit(oid).ok())
+            .flatten();
+        // Uncommitted hunks carry no signature, so read it off the commit
+        let author = commit.as_ref().map(|c| c.author());
+        for offset in 0..hunk.lines_in_hunk() {
+            lines.push(BlameLine {
+                line: hunk.final_start_line() + offset,
+                hash: commit.as_ref().map(|c| c.id().to_string()),
+                author: author
+                    .as_ref()
+                    .and_then(|a| a.name().map(|n| n.to_string()))
+                    .unwrap_or_default(),
+                date: commit.as_ref().map(|c| c.time().seconds()).unwrap_or(0),
+                summary: commit
+                    .as_ref()
+                    .and_then(|c| c.summary().map(|s| s.to_string()))
+                    .unwrap_or_default(),
+            });
+        }
+    }
+    Ok(lines)
+}
+
+/// Adds a file (or everything under a folder) to the index, including deletions.
+#[tauri::command]
+pub fn git_stage(path: String) -> Result<(), String> {
+    let (repo, relative) = open_for(Path::new(&path))?;
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    index
+        .add_all([&relative], IndexAddOption::DEFAULT, None)
+        .and_then(|_| index.update_all([&relative], None))
+        .and_then(|_| index.write())
+        .map_err(|e| e.to_string())
+}
+
+/// Resets a file's index entry to HEAD, keeping the working copy as is.
+#[tauri::command]
+pub fn git_unstage(path: String) -> Result<(), String> {
+    let (repo, relative) = open_for(Path::new(&path))?;
+    let head = repo.head().and_then(|head| head.peel(ObjectType::Commit));
+    match head {
+        Ok(head) => repo
+            .reset_default(Some(&head), [&relative])
+            .map_err(|e| e.to_string()),
+        // Before the first commit there is nothing to reset to
+        Err(_) => {
+            let mut index = repo.index().map_err(|e| e.to_string())?;
+            index
+                .remove_all([&relative], None)
+                .and_then(|_| index.write())
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Commits the index on top of HEAD and returns the new commit's hash.
+/// During a merge the merged commits become extra parents, which concludes
+/// the merge once every conflict is resolved.
+pub fn commit_index(repo: &Repository, message: &str) -> Result<String, String> {
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    if index.has_conflicts() {
+        return Err("merge_conflicts".to_string());
+    }
+    let tree_oid = index.write_tree().map_err(|e| e.to_string())?;
+    let tree = repo.find_tree(tree_oid).map_err(|e| e.to_string())?;
+    let mut parents: Vec<Commit> = repo
+        .head()
+        .ok()
+        .and_then(|h| h.peel_to_commit().ok())
+        .into_iter()
+        .collect();
+    let merging = repo.state() == RepositoryState::Merge;
+    if merging {
+        let merge_head =
+            std::fs::read_to_string(repo.path().join("MERGE_HEAD")).map_err(|e| e.to_string())?;
+        for line in merge_head.lines().filter(|l| !l.trim().is_empty()) {
+            let oid = Oid::from_str(line.trim()).map_err(|e| e.to_string())?;
+            parents.push(repo.find_commit(oid).map_err(|e| e.to_string())?);
+        }
+    } else if parents.first().is_some_and(|p| p.tree_id() == tree_oid) {
+        return Err("nothing_to_commit".to_string());
+    }
+
+    let sig = repo.signature().map_err(|e| e.to_string())?;
+    let parents: Vec<&Commit> = parents.iter().collect();
+    let oid = repo
+        .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .map_err(|e| e.to_string())?;
+    if merging {
+        repo.cleanup_state().map_err(|e| e.to_string())?;
+    }
+    Ok(oid.to_string())
+}
+
+/// Commits whatever has been staged in the repository containing `root`.
+#[tauri::command]
+pub fn git_commit_staged(root: String, message: String) -> Result<String, String> {
+    let repo = discover(&root)?;
+    commit_index(&repo, &message)
+}
+
+/// Stages every new, modified or deleted markdown file in the repository
+/// containing `root` (or only under `folder`) and commits them.
+#[tauri::command]
+pub fn git_commit_all(
+    root: String,
+    message: String,
+    folder: Option<String>,
+) -> Result<String, String> {
+    let repo = discover(&root)?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| crate::i18n::t("error.bare_repository"))?
+        .to_path_buf();
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+    if let Some(folder) = &folder {
+        let relative = Path::new(folder)
+            .strip_prefix(&workdir)
+            .map_err(|e| e.to_string())?;
+        opts.pathspec(relative);
+    }
+    let statuses = repo.statuses(Some(&mut opts)).map_err(|e| e.to_string())?;
+
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    for entry in statuses.iter() {
+        let Some(relative) = entry.path().map(PathBuf::from) else {
+            continue;
+        };
+        if !crate::vault::is_markdown(&relative) {
+            continue;
+        }
+        if entry.status().is_wt_deleted() {
+            index.remove_path(&relative)
+        } else {
+            index.add_path(&relative)
+        }
+        .map_err(|e| e.to_string())?;
+    }
+    index.write().map_err(|e| e.to_string())?;
+    commit_index(&repo, &message)
+}
+
+#[derive(Serialize, Clone)]
+struct SyncProgress {
+    /// "fetch" or "push"
+    stage: &'static str,
+    current: usize,
+    total: usize,
+}
+
+enum CredentialSource {
+    Agent,
+    Key(PathBuf),
+    Token(String),
+    Helper,
+}
+
+/// Credentials for fetch and push, in order: what is stored for the remote
+/// (see `git_credentials`), the SSH agent, the usual key files in `~/.ssh`,
+/// then git's configured credential helper. Each source is tried once,
+/// since libgit2 keeps asking for as long as it gets answers.
+pub fn remote_callbacks<'a>(
+    app: &'a AppHandle,
+    repo: Option<&'a Repository>,
+) -> RemoteCallbacks<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut attempt = 0;
+    callbacks.credentials(move |url, username, allowed| {
+        let stored = git_credentials::stored(app, url);
+        let user = stored
+            .username
+            .clone()
+            .or(username.map(|u| u.to_string()))
+            .unwrap_or_else(|| "git".to_string());
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(&user);
+        }
+
+        let mut sources = Vec::new();
+        if allowed.contains(CredentialType::SSH_KEY) {
+            sources.extend(stored.ssh_key.map(CredentialSource::Key));
+            sources.push(CredentialSource::Agent);
+            let home = app.path().home_dir().unwrap_or_default();
+            sources.extend(
+                ["id_ed25519", "id_ecdsa", "id_rsa"]
+                    .iter()
+                    .map(|name| home.join(".ssh").join(name))
+                    .filter(|key| key.is_file())
+                    .map(CredentialSource::Key),
+            );
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            sources.extend(stored.token.map(CredentialSource::Token));
+            sources.push(CredentialSource::Helper);
+        }
+
+        attempt += 1;
+        match sources.into_iter().nth(attempt - 1) {
+            Some(CredentialSource::Agent) => Cred::ssh_key_from_agent(&user),
+            Some(CredentialSource::Key(key)) => Cred::ssh_key(&user, None, &key, None),
+            Some(CredentialSource::Token(token)) => Cred::userpass_plaintext(&user, &token),
+            Some(CredentialSource::Helper) => {
+                let config = match repo {
+                    Some(repo) => repo.config()?,
+                    None => git2::Config::open_default()?,
+                };
+                Cred::credential_helper(&config, url, username)
+            }
+            None => Err(git2::Error::from_str(&crate::i18n::t(
+                "error.git_authentication_failed",
+            ))),
+        }
+    });
+    callbacks.transfer_progress(move |stats| {
+        let _ = app.emit(
+            "git-sync-progress",
+            SyncProgress {
+                stage: "fetch",
+                current: stats.received_objects(),
+                total: stats.total_objects(),
+            },
+        );
+        true
+    });
+    callbacks.push_transfer_progress(move |current, total, _| {
+        let _ = app.emit(
+            "git-sync-progress",
+            SyncProgress {
+                stage: "push",
+                current,
+                total,
+            },
+        );
+    });
+    callbacks
+}
+
+/// Merges the fetched `upstream` commit into the current branch: a
+/// fast-forward when possible, otherwise a merge commit. Conflicts leave the
+/// repository mid-merge for the user to resolve.
+fn merge_upstream(repo: &Repository, branch: &str, upstream: Oid) -> Result<(), String> {
+    let annotated = repo
+        .find_annotated_commit(upstream)
+        .map_err(|e| e.to_string())?;
+    let (analysis, _) = repo
+        .merge_analysis(&[&annotated])
+        .map_err(|e| e.to_string())?;
+
+    if analysis.is_up_to_date() {
+        return Ok(());
+    }
+    if analysis.is_fast_forward() || analysis.is_unborn() {
+        let target = repo
+            .find_object(upstream, None)
+            .map_err(|e| e.to_string())?;
+        // Safe checkout refuses to overwrite local edits to the same files
+        repo.checkout_tree(&target, Some(CheckoutBuilder::new().safe()))
+            .map_err(|e| e.to_string())?;
+        repo.reference(
+            &format!("refs/heads/{}", branch),
+            upstream,
+            true,
+            "sync: fast-forward",
+        )
+        .map_err(|e| e.to_string())?;
+        return repo
+            .set_head(&format!("refs/heads/{}", branch))
+            .map_err(|e| e.to_string());
+    }
+
+    repo.merge(&[&annotated], None, Some(CheckoutBuilder::new().safe()))
+        .map_err(|e| e.to_string())?;
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    if index.has_conflicts() {
+        return Err("merge_conflicts".to_string());
+    }
+    let tree_oid = index.write_tree().map_err(|e| e.to_string())?;
+    let tree = repo.find_tree(tree_oid).map_err(|e| e.to_string())?;
+    let head = repo
+        .head()
+        .and_then(|h| h.peel_to_commit())
+        .map_err(|e| e.to_string())?;
+    let theirs = repo.find_commit(upstream).map_err(|e| e.to_string())?;
+    let sig = repo.signature().map_err(|e| e.to_string())?;
+    repo.commit(
+        Some("HEAD"),
+        &sig,
+        &sig,
+        &format!("Merge remote-tracking branch into {}", branch),
+        &tree,
+        &[&head, &theirs],
+    )
+    .map_err(|e| e.to_string())?;
+    repo.cleanup_state().map_err(|e| e.to_string())
+}
+
+/// Pulls the current branch from its remote (fast-forward or merge) and
+/// pushes local commits back, using libgit2 so no git binary or terminal
+/// prompt is needed. Emits "git-sync-progress" while transferring.
+pub fn sync(app: &AppHandle, path: &str) -> Result<String, String> {
+    let repo = discover(path)?;
+    let head = repo.head().map_err(|e| e.to_string())?;
+    // A detached HEAD's shorthand is "HEAD", which isn't a branch to sync
+    if !head.is_branch() {
+        return Err(crate::i18n::t("error.detached_head"));
+    }
+    let branch = head.shorthand().unwrap_or_default().to_string();
+    let remote_name = repo
+        .branch_upstream_remote(&format!("refs/heads/{}", branch))
+        .ok()
+        .and_then(|name| name.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| "origin".to_string());
+    let mut remote = repo.find_remote(&remote_name).map_err(|e| e.to_string())?;
+
+    let mut fetch = FetchOptions::new();
+    fetch.remote_callbacks(remote_callbacks(app, Some(&repo)));
+    remote
+        .fetch(&[&branch], Some(&mut fetch), None)
+        .map_err(|e| crate::i18n::tf("error.fetch_failed", &[("error", &e.to_string())]))?;
+
+    let tracking = format!("refs/remotes/{}/{}", remote_name, branch);
+    if let Ok(upstream) = repo.refname_to_id(&tracking) {
+        merge_upstream(&repo, &branch, upstream)?;
+    }
+
+    let mut rejected: Option<String> = None;
+    {
+        let mut callbacks = remote_callbacks(app, Some(&repo));
+        callbacks.push_update_reference(|_, status| {
+            if let Some(status) = status {
+                rejected = Some(status.to_string());
+            }
+            Ok(())
+        });
+        let mut push = PushOptions::new();
+        push.remote_callbacks(callbacks);
+        let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch);
+        remote
+            .push(&[&refspec], Some(&mut push))
+            .map_err(|e| crate::i18n::tf("error.push_failed", &[("error", &e.to_string())]))?;
+    }
+    if let Some(status) = rejected {
+        return Err(crate::i18n::tf(
+            "error.push_rejected",
+            &[("status", &status)],
+        ));
+    }
+
+    Ok(crate::i18n::t("message.sync_complete"))
+}
+
+#[tauri::command]
+pub async fn git_sync(app: AppHandle, path: String) -> Result<String, String> {
+    sync(&app, &path)
+}
+
+/// The remote `sync` would use for the repository containing `path`.
+pub fn sync_remote_url(path: &str) -> Option<String> {
+    let repo = Repository::discover(path).ok()?;
+    let head = repo.head().ok()?;
+    let refname = head.name()?;
+    let remote = repo
+        .branch_upstream_remote(refname)
+        .ok()
+        .and_then(|name| name.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| "origin".to_string());
+    let url = repo.find_remote(&remote).ok()?.url()?.to_string();
+    Some(url)
+}
+
+#[derive(Serialize)]
+pub struct ConflictFile {
+    path: String,
+    /// Common ancestor; None when both sides added the file
+    base: Option<String>,
+    /// The local side; None when deleted locally
+    ours: Option<String>,
+    /// The incoming side; None when deleted remotely
+    theirs: Option<String>,
+}
+
+/// Files left conflicted by a merge, with the three versions of each for a
+/// side-by-side resolver.
+#[tauri::command]
+pub fn git_list_conflicts(root: String) -> Result<Vec<ConflictFile>, String> {
+    let repo = discover(&root)?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| crate::i18n::t("error.bare_repository"))?
+        .to_path_buf();
+    let index = repo.index().map_err(|e| e.to_string())?;
+    let content = |entry: &Option<git2::IndexEntry>| {
+        let blob = repo.find_blob(entry.as_ref()?.id).ok()?;
+        Some(String::from_utf8_lossy(blob.content()).into_owned())
+    };
+
+    let mut conflicts = Vec::new();
+    for conflict in index.conflicts().map_err(|e| e.to_string())? {
+        let conflict = conflict.map_err(|e| e.to_string())?;
+        let Some(entry) = [&conflict.our, &conflict.their, &conflict.ancestor]
+            .into_iter()
+            .find_map(|e| e.as_ref())
+        else {
+            continue;
+        };
+        let relative = String::from_utf8_lossy(&entry.path).into_owned();
+        conflicts.push(ConflictFile {
+            path: workdir.join(relative).to_string_lossy().to_string(),
+            base: content(&conflict.ancestor),
+            ours: content(&conflict.our),
+            theirs: content(&conflict.their),
+        });
+    }
+    Ok(conflicts)
+}
+
+/// Marks a conflicted file as resolved, first writing `content` to it when
+/// given. A missing file resolves the conflict as a deletion.
+#[tauri::command]
+pub fn git_mark_resolved(path: String, content: Option<String>) -> Result<(), String> {
+    let file_path = Path::new(&path);
+    let (repo, relative) = open_for(file_path)?;
+    if let Some(content) = content {
+        crate::store::write_atomic(file_path, content)?;
+    }
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    if file_path.exists() {
+        index.add_path(&relative)
+    } else {
+        index.remove_path(&relative)
+    }
+    .and_then(|_| index.write())
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Serialize)]
+pub struct CommitDiff {
+    hunks: Vec<DiffHunk>,
+    binary: bool,
+}
+
+/// How a file changed between two commits (`from` older, `to` newer).
+#[tauri::command]
+pub fn git_diff_commits(path: String, from: String, to: String) -> Result<CommitDiff, String> {
+    let (repo, relative) = open_for(Path::new(&path))?;
+    let tree = |spec: &str| {
+        find_commit(&repo, spec).and_then(|commit| commit.tree().map_err(|e| e.to_string()))
+    };
+    let (old, new) = (tree(&from)?, tree(&to)?);
+    let diff = repo
+        .diff_tree_to_tree(Some(&old), Some(&new), Some(&mut diff_options(&relative)))
+        .map_err(|e| e.to_string())?;
+    let (hunks, binary) = diff_hunks(&diff)?;
+    Ok(CommitDiff { hunks, binary })
+}
+
+const DEFAULT_GITIGNORE: &str = "# OS clutter
+.DS_Store
+Thumbs.db
+desktop.ini
+
+# Temp files from interrupted saves
+.*.tmp
+";
+
+/// Turns a folder into a repository. Optionally writes a starter
+/// `.gitignore` (kept if one exists) and commits everything in an
+/// initial commit. Returns the initial commit's hash, if one was made.
+#[tauri::command]
+pub fn git_init(
+    folder: String,
+    initial_commit: Option<bool>,
+    gitignore: Option<bool>,
+) -> Result<Option<String>, String> {
+    let folder = Path::new(&folder);
+    if !folder.is_dir() {
+        return Err(crate::i18n::t("error.not_a_directory"));
+    }
+    if Repository::open(folder).is_ok() {
+        return Err(crate::i18n::t("error.already_git_repository"));
+    }
+    let repo = Repository::init(folder).map_err(|e| e.to_string())?;
+
+    let ignore_path = folder.join(".gitignore");
+    if gitignore.unwrap_or(true) && !ignore_path.exists() {
+        crate::store::write_atomic(&ignore_path, DEFAULT_GITIGNORE)?;
+    }
+    if !initial_commit.unwrap_or(true) {
+        return Ok(None);
+    }
+
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    index
+        .add_all(["*"], IndexAddOption::DEFAULT, None)
+        .and_then(|_| index.write())
+        .map_err(|e| e.to_string())?;
+    commit_index(&repo, "Initial commit").map(Some)
+}
+
+/// Clones a remote vault into `dest`, which must not exist yet or be empty.
+/// Reports "git-sync-progress" events while downloading and returns `dest`.
+#[tauri::command]
+pub async fn git_clone(app: AppHandle, url: String, dest: String) -> Result<String, String> {
+    let dest = Path::new(&dest);
+    if dest
+        .read_dir()
+        .is_ok_and(|mut entries| entries.next().is_some())
+    {
+        return Err(crate::i18n::tf(
+            "error.not_empty",
+            &[("path", &dest.display().to_string())],
+        ));
+    }
+    let mut fetch = FetchOptions::new();
+    fetch.remote_callbacks(remote_callbacks(&app, None));
+    RepoBuilder::new()
+        .fetch_options(fetch)
+        .clone(&url, dest)
+        .map_err(|e| crate::i18n::tf("error.clone_failed", &[("error", &e.to_string())]))?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+#[derive(Serialize)]
+pub struct GitRemote {
+    name: String,
+    url: Option<String>,
+    /// Only set when pushes go somewhere other than `url`
+    push_url: Option<String>,
+}
+
+#[tauri::command]
+pub fn git_list_remotes(root: String) -> Result<Vec<GitRemote>, String> {
+    let repo = discover(&root)?;
+    let names = repo.remotes().map_err(|e| e.to_string())?;
+    Ok(names
+        .iter()
+        .flatten()
+        .filter_map(|name| repo.find_remote(name).ok())
+        .map(|remote| GitRemote {
+            name: remote.name().unwrap_or_default().to_string(),
+            url: remote.url().map(|u| u.to_string()),
+            push_url: remote.pushurl().map(|u| u.to_string()),
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn git_add_remote(root: String, name: String, url: String) -> Result<(), String> {
+    discover(&root)?
+        .remote(&name, &url)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn git_remove_remote(root: String, name: String) -> Result<(), String> {
+    discover(&root)?
+        .remote_delete(&name)
+        .map_err(|e| e.to_string())
+}
+
+/// Points an existing remote at a new URL, e.g. after moving the vault to
+/// another host.
+#[tauri::command]
+pub fn git_set_remote_url(root: String, name: String, url: String) -> Result<(), String> {
+    let repo = discover(&root)?;
+    repo.find_remote(&name).map_err(|e| e.to_string())?;
+    repo.remote_set_url(&name, &url).map_err(|e| e.to_string())
+}
+
+/// Discards working-tree changes touching lines `start_line..=end_line`
+/// (1-based, in the current file) by putting back the staged, or else
+/// committed, text of just those changes. Passing a hunk's new-side range
+/// from `git_diff` discards that hunk. Returns how many changes were undone.
+#[tauri::command]
+pub fn git_discard_lines(path: String, start_line: u32, end_line: u32) -> Result<usize, String> {
+    let file_path = Path::new(&path);
+    let (repo, relative) = open_for(file_path)?;
+    let index = repo.index().map_err(|e| e.to_string())?;
+    let entry = index
+        .get_path(&relative, 0)
+        .ok_or_else(|| crate::i18n::t("error.discard_untracked"))?;
+    let old = repo
+        .find_blob(entry.id)
+        .map_err(|e| e.to_string())?
+        .content()
+        .to_vec();
+    let new = std::fs::read(file_path).map_err(|e| e.to_string())?;
+
+    // Zero context makes every hunk exactly one change
+    let mut opts = DiffOptions::new();
+    opts.context_lines(0);
+    let patch =
+        Patch::from_buffers(&old, None, &new, None, Some(&mut opts)).map_err(|e| e.to_string())?;
+
+    let old_lines: Vec<&[u8]> = old.split_inclusive(|b| *b == b'\n').collect();
+    let new_lines: Vec<&[u8]> = new.split_inclusive(|b| *b == b'\n').collect();
+    let mut out: Vec<u8> = Vec::with_capacity(new.len());
+    let mut copied = 0;
+    let mut discarded = 0;
+    for h in 0..patch.num_hunks() {
+        let (hunk, _) = patch.hunk(h).map_err(|e| e.to_string())?;
+        let (new_start, new_count) = (hunk.new_start(), hunk.new_lines());
+        let selected = if new_count == 0 {
+            // A pure deletion sits between lines new_start and new_start + 1
+            start_line <= new_start + 1 && end_line >= new_start
+        } else {
+            start_line < new_start + new_count && end_line >= new_start
+        };
+        if !selected {
+            continue;
+        }
+        let begin = if new_count == 0 {
+            new_start
+        } else {
+            new_start - 1
+        } as usize;
+        let old_begin = hunk.old_start().saturating_sub(1) as usize;
+        for line in &new_lines[copied..begin] {
+            out.extend_from_slice(line);
+        }
+        for line in &old_lines[old_begin..old_begin + hunk.old_lines() as usize] {
+            out.extend_from_slice(line);
+        }
+        copied = begin + new_count as usize;
+        discarded += 1;
+    }
+    if discarded == 0 {
+        return Ok(0);
+    }
+    for line in &new_lines[copied..] {
+        out.extend_from_slice(line);
+    }
+    crate::store::write_atomic(file_path, out)?;
+    Ok(discarded)
+}
+
+/// The remote-tracking ref a local branch (`refs/heads/...`) is compared
+/// against: its configured upstream, else `origin/<branch>` if that exists.
+pub fn upstream_ref(repo: &Repository, branch_ref: &str) -> Option<String> {
+    if let Ok(name) = repo.branch_upstream_name(branch_ref) {
+        return name.as_str().map(|s| s.to_string());
+    }
+    let fallback = format!(
+        "refs/remotes/origin/{}",
+        branch_ref.strip_prefix("refs/heads/")?
+    );
+    repo.find_reference(&fallback).ok().map(|_| fallback)
+}
+
+#[derive(Serialize)]
+pub struct BranchStatus {
+    name: String,
+    is_head: bool,
+    /// e.g. `origin/main`; None when the branch tracks nothing
+    upstream: Option<String>,
+    ahead: usize,
+    behind: usize,
+}
+
+/// Ahead/behind counts for every local branch against its upstream, as of
+/// the last fetch.
+#[tauri::command]
+pub fn git_branch_status(root: String) -> Result<Vec<BranchStatus>, String> {
+    let repo = discover(&root)?;
+    let branches = repo
+        .branches(Some(BranchType::Local))
+        .map_err(|e| e.to_string())?;
+    let mut statuses = Vec::new();
+    for (branch, _) in branches.flatten() {
+        let reference = branch.get();
+        let (Some(name), Some(refname), Some(local)) = (
+            branch.name().ok().flatten(),
+            reference.name(),
+            reference.target(),
+        ) else {
+            continue;
+        };
+        let upstream =
+            upstream_ref(&repo, refname).and_then(|upstream| repo.find_reference(&upstream).ok());
+        let (ahead, behind) = upstream
+            .as_ref()
+            .and_then(|u| u.target())
+            .and_then(|remote| repo.graph_ahead_behind(local, remote).ok())
+            .unwrap_or((0, 0));
+        statuses.push(BranchStatus {
+            name: name.to_string(),
+            is_head: branch.is_head(),
+            upstream: upstream.and_then(|u| u.shorthand().map(|s| s.to_string())),
+            ahead,
+            behind,
+        });
+    }
+    Ok(statuses)
+}
+
+/// Updates remote-tracking branches without touching the working tree, so
+/// ahead/behind counts are current. `remote` defaults to the current
+/// branch's remote, then `origin`. Emits "git-sync-progress".
+#[tauri::command]
+pub async fn git_fetch(app: AppHandle, root: String, remote: Option<String>) -> Result<(), String> {
+    let repo = discover(&root)?;
+    let name = remote
+        .or_else(|| {
+            let head = repo.head().ok()?;
+            let remote = repo.branch_upstream_remote(head.name()?).ok()?;
+            remote.as_str().map(|s| s.to_string())
+        })
+        .unwrap_or_else(|| "origin".to_string());
+    let mut remote = repo.find_remote(&name).map_err(|e| e.to_string())?;
+    let mut fetch = FetchOptions::new();
+    fetch.remote_callbacks(remote_callbacks(&app, Some(&repo)));
+    remote
+        .fetch::<&str>(&[], Some(&mut fetch), None)
+        .map_err(|e| crate::i18n::tf("error.fetch_failed", &[("error", &e.to_string())]))
+}
+
+/// `get_git_status` results per working directory. Only repositories inside
+/// the watched folder are cached, since the folder watcher is what tells
+/// us they changed.
+#[derive(Default)]
+pub struct GitStatusState {
+    watched: Mutex<Vec<PathBuf>>,
+    cache: Mutex<HashMap<PathBuf, HashMap<String, String>>>,
+}
+
+impl GitStatusState {
+    /// Called when a folder watcher starts; statuses below it can be cached.
+    pub fn watch(&self, root: &Path) {
+        self.watched.lock().unwrap().push(root.to_path_buf());
+    }
+
+    /// Called when a folder watcher stops (with None, all of them).
+    pub fn unwatch(&self, root: Option<&Path>) {
+        let mut watched = self.watched.lock().unwrap();
+        match root {
+            Some(root) => {
+                if let Some(i) = watched.iter().position(|w| w == root) {
+                    watched.remove(i);
+                }
+            }
+            None => watched.clear(),
+        }
+        // Entries for folders nobody watches any more could go stale
+        self.cache
+            .lock()
+            .unwrap()
+            .retain(|workdir, _| watched.iter().any(|w| workdir.starts_with(w)));
+    }
+
+    pub fn get(&self, workdir: &Path) -> Option<HashMap<String, String>> {
+        self.cache.lock().unwrap().get(workdir).cloned()
+    }
+
+    pub fn put(&self, workdir: &Path, statuses: &HashMap<String, String>) {
+        let watched = self.watched.lock().unwrap();
+        if watched.iter().any(|w| workdir.starts_with(w)) {
+            self.cache
+                .lock()
+                .unwrap()
+                .insert(workdir.to_path_buf(), statuses.clone());
+        }
+    }
+
+    /// Drops cached statuses of repositories containing any of `paths`.
+    pub fn invalidate(&self, paths: &[PathBuf]) {
+        self.cache
+            .lock()
+            .unwrap()
+            .retain(|workdir, _| !paths.iter().any(|p| p.starts_with(workdir)));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{secrets, store};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const CREDENTIALS_FILE: &str = "git_credentials.json";
+
+/// Per-remote settings that aren't secret; tokens live in the OS keyring.
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+struct RemoteSettings {
+    username: Option<String>,
+    ssh_key: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct RemoteCredentials {
+    url: String,
+    username: Option<String>,
+    ssh_key: Option<String>,
+    has_token: bool,
+}
+
+/// Credentials stored for a remote, as used by fetch and push.
+pub struct Stored {
+    pub username: Option<String>,
+    pub ssh_key: Option<PathBuf>,
+    pub token: Option<String>,
+}
+
+fn token_account(url: &str) -> String {
+    format!("git-token:{}", url)
+}
+
+fn load(app: &AppHandle) -> HashMap<String, RemoteSettings> {
+    store::load_json(app, CREDENTIALS_FILE)
+}
+
+fn update(app: &AppHandle, url: &str, f: impl FnOnce(&mut RemoteSettings)) -> Result<(), String> {
+    let mut remotes = load(app);
+    let settings = remotes.entry(url.to_string()).or_default();
+    f(settings);
+    if settings.username.is_none() && settings.ssh_key.is_none() {
+        remotes.remove(url);
+    }
+    store::save_json(app, CREDENTIALS_FILE, &remotes)
+}
+
+pub fn stored(app: &AppHandle, url: &str) -> Stored {
+    let settings = load(app).remove(url).unwrap_or_default();
+    Stored {
+        username: settings.username,
+        ssh_key: settings.ssh_key.map(PathBuf::from),
+        token: secrets::get_secret(&token_account(url)).ok().flatten(),
+    }
+}
+
+/// What is configured for a remote, without revealing the token.
+#[tauri::command]
+pub fn get_git_credentials(app: AppHandle, url: String) -> Result<RemoteCredentials, String> {
+    let settings = load(&app).remove(&url).unwrap_or_default();
+    Ok(RemoteCredentials {
+        has_token: secrets::get_secret(&token_account(&url))?.is_some(),
+        url,
+        username: settings.username,
+        ssh_key: settings.ssh_key,
+    })
+}
+
+/// Stores an HTTPS access token (and optionally the username to send with
+/// it) for a remote URL. A `None` token removes the stored one.
+#[tauri::command]
+pub fn set_git_token(
+    app: AppHandle,
+    url: String,
+    username: Option<String>,
+    token: Option<String>,
+) -> Result<(), String> {
+    match token.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        Some(token) => secrets::set_secret(&token_account(&url), token)?,
+        None => secrets::delete_secret(&token_account(&url))?,
+    }
+    update(&app, &url, |settings| settings.username = username)
+}
+
+/// Chooses the private key used for an SSH remote instead of the agent and
+/// the default keys. `None` goes back to the defaults.
+#[tauri::command]
+pub fn set_git_ssh_key(
+    app: AppHandle,
+    url: String,
+    key_path: Option<String>,
+) -> Result<(), String> {
+    if let Some(path) = &key_path {
+        if !PathBuf::from(path).is_file() {
+            return Err(crate::i18n::tf("error.does_not_exist", &[("path", path)]));
+        }
+    }
+    update(&app, &url, |settings| settings.ssh_key = key_path)
+}
+
+#[tauri::command]
+pub fn clear_git_credentials(app: AppHandle, url: String) -> Result<(), String> {
+    secrets::delete_secret(&token_account(&url))?;
+    update(&app, &url, |settings| *settings = RemoteSettings::default())
+}
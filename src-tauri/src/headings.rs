@@ -0,0 +1,67 @@
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// GitHub-style heading slug: lowercase, punctuation dropped, spaces to
+/// hyphens. Letters and digits outside ASCII are kept.
+pub fn heading_slug(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Visible text of an HTML fragment, with tags dropped and entities decoded.
+pub fn text_content(html: &str) -> String {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    let tag_re = TAG.get_or_init(|| Regex::new(r"<[^>]*>").unwrap());
+    tag_re
+        .replace_all(html, "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Adds `id` attributes to rendered headings. Repeated headings get `-1`,
+/// `-2`, ... suffixes in document order so every anchor is unique and stable.
+pub fn add_heading_ids(html: &str) -> String {
+    static HEADING: OnceLock<Regex> = OnceLock::new();
+    let re = HEADING.get_or_init(|| Regex::new(r"(?s)<h([1-6])([^>]*)>(.*?)</h[1-6]>").unwrap());
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    re.replace_all(html, |caps: &Captures| {
+        if caps[2].contains(" id=") {
+            return caps[0].to_string();
+        }
+        let base = heading_slug(&text_content(&caps[3]));
+        let count = seen.entry(base.clone()).or_insert(0);
+        let slug = if *count == 0 {
+            base
+        } else {
+            format!("{}-{}", base, count)
+        };
+        *count += 1;
+        format!(
+            "<h{}{} id=\"{}\">{}</h{}>",
+            &caps[1],
+            &caps[2],
+            crate::escape_attr(&slug),
+            &caps[3],
+            &caps[1]
+        )
+    })
+    .into_owned()
+}
+
+#[tauri::command]
+pub fn slugify(text: String) -> String {
+    heading_slug(&text)
+}
@@ -0,0 +1,126 @@
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::sync::OnceLock;
+use syntect::highlighting::ThemeSet;
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+const LIGHT_THEME: &str = "InspiredGitHub";
+const DARK_THEME: &str = "base16-ocean.dark";
+
+#[derive(Serialize)]
+pub struct HighlightLanguage {
+    name: String,
+    /// Fence info strings that select this language, e.g. "rs" or "rust"
+    tokens: Vec<String>,
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn highlight(code: &str, lang: &str) -> Option<String> {
+    let syntaxes = syntax_set();
+    let syntax = syntaxes.find_syntax_by_token(lang)?;
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, syntaxes, CLASS_STYLE);
+    for line in LinesWithEndings::from(code) {
+        generator
+            .parse_html_for_line_which_includes_newline(line)
+            .ok()?;
+    }
+    Some(generator.finalize())
+}
+
+/// Replaces the contents of comrak's `<pre><code class="language-x">` blocks
+/// with classed highlight spans. Mermaid blocks become `<div class="mermaid">`
+/// for mermaid.js to render; unknown languages are left untouched.
+pub fn highlight_code_blocks(html: &str) -> String {
+    static CODE_BLOCK: OnceLock<Regex> = OnceLock::new();
+    let re = CODE_BLOCK.get_or_init(|| {
+        Regex::new(r#"(?s)<pre([^>]*)><code class="language-([^"\s]+)">(.*?)</code></pre>"#)
+            .unwrap()
+    });
+
+    re.replace_all(html, |caps: &Captures| {
+        let lang = &caps[2];
+        if lang == "mermaid" {
+            // Still HTML-escaped, mermaid reads the element's text content
+            return format!("<div class=\"mermaid\"{}>{}</div>", &caps[1], &caps[3]);
+        }
+        match highlight(&unescape_html(&caps[3]), lang) {
+            Some(code) => format!(
+                "<pre{} class=\"hl-code\"><code class=\"language-{}\">{}</code></pre>",
+                &caps[1], lang, code
+            ),
+            None => caps[0].to_string(),
+        }
+    })
+    .into_owned()
+}
+
+fn theme_css(name: &str) -> Result<String, String> {
+    let themes = ThemeSet::load_defaults();
+    let theme = themes
+        .themes
+        .get(name)
+        .ok_or_else(|| crate::i18n::tf("error.unknown_highlight_theme", &[("name", name)]))?;
+    css_for_theme_with_class_style(theme, CLASS_STYLE).map_err(|e| e.to_string())
+}
+
+// Scopes every selector of a stylesheet under `scope`
+fn scope_css(css: &str, scope: &str) -> String {
+    css.lines()
+        .map(|line| match line.strip_suffix('{') {
+            Some(selectors) if !selectors.trim().is_empty() => format!(
+                "{} {{",
+                selectors
+                    .split(',')
+                    .map(|s| format!("{} {}", scope, s.trim()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Stylesheet for highlighted code that follows the app theme: light by
+/// default, dark for `data-theme="dark"` or a dark system preference.
+#[tauri::command]
+pub fn get_highlight_css() -> Result<String, String> {
+    let light = theme_css(LIGHT_THEME)?;
+    let dark = theme_css(DARK_THEME)?;
+    Ok(format!(
+        "{}\n{}\n@media (prefers-color-scheme: dark) {{\n{}\n}}\n",
+        light,
+        scope_css(&dark, ":root[data-theme=\"dark\"]"),
+        scope_css(&dark, ":root:not([data-theme=\"light\"])")
+    ))
+}
+
+#[tauri::command]
+pub fn list_highlight_languages() -> Vec<HighlightLanguage> {
+    let mut languages: Vec<HighlightLanguage> = syntax_set()
+        .syntaxes()
+        .iter()
+        .filter(|s| s.name != "Plain Text")
+        .map(|s| HighlightLanguage {
+            name: s.name.clone(),
+            tokens: s.file_extensions.clone(),
+        })
+        .collect();
+    languages.sort_by_key(|l| l.name.to_lowercase());
+    languages
+}
@@ -0,0 +1,297 @@
+use crate::blocks;
+use crate::encoding;
+use crate::git::{self, DiffHunk};
+use crate::store;
+use git2::Patch;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+const HISTORY_CONFIG_FILE: &str = "history.json";
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    max_bytes: u64,
+    max_per_file: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        HistoryConfig {
+            max_bytes: 100 * 1024 * 1024,
+            max_per_file: 50,
+        }
+    }
+}
+
+/// Serializes snapshot writes so manifests and blob garbage collection don't
+/// race. Holds the bytes of blobs on disk once measured, so a save only
+/// collects garbage when the limit is actually exceeded.
+#[derive(Default)]
+pub struct HistoryState {
+    lock: Mutex<Option<u64>>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Snapshot {
+    id: String,
+    /// Milliseconds since the epoch
+    timestamp: i64,
+    size: u64,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    blob: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Manifest {
+    path: String,
+    /// Oldest first
+    snapshots: Vec<Snapshot>,
+}
+
+fn history_root(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("history"))
+}
+
+fn manifest_file(root: &Path, path: &str) -> PathBuf {
+    root.join("manifests")
+        .join(format!("{:016x}.json", blocks::fnv1a(path.as_bytes())))
+}
+
+fn blob_key(content: &[u8]) -> String {
+    format!("{:016x}-{:x}", blocks::fnv1a(content), content.len())
+}
+
+fn load(root: &Path, path: &str) -> Manifest {
+    fs::read_to_string(manifest_file(root, path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(|| Manifest {
+            path: path.to_string(),
+            ..Manifest::default()
+        })
+}
+
+fn save(root: &Path, manifest: &Manifest) -> Result<(), String> {
+    let file = manifest_file(root, &manifest.path);
+    if manifest.snapshots.is_empty() {
+        let _ = fs::remove_file(file);
+        return Ok(());
+    }
+    fs::create_dir_all(root.join("manifests")).map_err(|e| e.to_string())?;
+    let content = serde_json::to_string(manifest).map_err(|e| e.to_string())?;
+    store::write_atomic(&file, content)
+}
+
+fn all_manifests(root: &Path) -> Vec<Manifest> {
+    let Ok(entries) = fs::read_dir(root.join("manifests")) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect()
+}
+
+/// Drops the oldest snapshots across all files until the blobs fit in
+/// `max_bytes`, then deletes blobs no manifest references any more. The latest
+/// snapshot of each file is kept regardless. Returns the bytes of blobs left.
+fn enforce_limit(root: &Path, max_bytes: u64) -> u64 {
+    let mut manifests = all_manifests(root);
+    let blob_size = |blob: &str| {
+        fs::metadata(root.join("blobs").join(blob))
+            .map(|m| m.len())
+            .unwrap_or(0)
+    };
+    let mut referenced: HashSet<String> = manifests
+        .iter()
+        .flat_map(|m| m.snapshots.iter().map(|s| s.blob.clone()))
+        .collect();
+    let mut total: u64 = referenced.iter().map(|blob| blob_size(blob)).sum();
+
+    if total > max_bytes {
+        let mut candidates: Vec<(i64, usize)> = manifests
+            .iter()
+            .enumerate()
+            .flat_map(|(i, m)| {
+                let prunable = m.snapshots.len().saturating_sub(1);
+                m.snapshots[..prunable]
+                    .iter()
+                    .map(move |s| (s.timestamp, i))
+            })
+            .collect();
+        candidates.sort();
+        let mut changed = HashSet::new();
+        for (_, i) in candidates {
+            if total <= max_bytes {
+                break;
+            }
+            let removed = manifests[i].snapshots.remove(0);
+            changed.insert(i);
+            let still_used = manifests
+                .iter()
+                .any(|m| m.snapshots.iter().any(|s| s.blob == removed.blob));
+            if !still_used && referenced.remove(&removed.blob) {
+                total = total.saturating_sub(blob_size(&removed.blob));
+            }
+        }
+        for i in changed {
+            let _ = save(root, &manifests[i]);
+        }
+    }
+
+    let Ok(blobs) = fs::read_dir(root.join("blobs")) else {
+        return total;
+    };
+    for blob in blobs.flatten() {
+        if !referenced.contains(blob.file_name().to_string_lossy().as_ref()) {
+            let _ = fs::remove_file(blob.path());
+        }
+    }
+    total
+}
+
+/// Total size of the stored blobs, referenced or not.
+fn blobs_size(root: &Path) -> u64 {
+    fs::read_dir(root.join("blobs"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok())
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Records `content` as a new version of `path`, unless it matches the latest
+/// snapshot. Identical contents share one blob.
+pub fn snapshot(app: &AppHandle, path: &str, content: &[u8]) -> Result<(), String> {
+    let state = app.state::<HistoryState>();
+    let mut stored = state.lock.lock().unwrap();
+
+    let root = history_root(app)?;
+    let mut manifest = load(&root, path);
+    let blob = blob_key(content);
+    if manifest.snapshots.last().is_some_and(|s| s.blob == blob) {
+        return Ok(());
+    }
+
+    let blobs = root.join("blobs");
+    fs::create_dir_all(&blobs).map_err(|e| e.to_string())?;
+    let blob_path = blobs.join(&blob);
+    let mut added = 0;
+    if !blob_path.exists() {
+        store::write_atomic(&blob_path, content)?;
+        added = content.len() as u64;
+    }
+
+    let now = chrono::Utc::now();
+    manifest.snapshots.push(Snapshot {
+        id: format!("{:x}", now.timestamp_nanos_opt().unwrap_or(0)),
+        timestamp: now.timestamp_millis(),
+        size: content.len() as u64,
+        blob,
+    });
+    let config: HistoryConfig = store::load_json(app, HISTORY_CONFIG_FILE);
+    let excess = manifest
+        .snapshots
+        .len()
+        .saturating_sub(config.max_per_file.max(1));
+    manifest.snapshots.drain(..excess);
+    save(&root, &manifest)?;
+
+    let total = match *stored {
+        Some(total) => total + added,
+        None => blobs_size(&root),
+    };
+    *stored = Some(if total > config.max_bytes {
+        enforce_limit(&root, config.max_bytes)
+    } else {
+        total
+    });
+    Ok(())
+}
+
+fn read_blob(app: &AppHandle, path: &str, id: &str) -> Result<Vec<u8>, String> {
+    let root = history_root(app)?;
+    let manifest = load(&root, path);
+    let snapshot = manifest
+        .snapshots
+        .iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| crate::i18n::t("error.snapshot_not_found"))?;
+    fs::read(root.join("blobs").join(&snapshot.blob)).map_err(|e| e.to_string())
+}
+
+/// Snapshots of `path`, newest first.
+#[tauri::command]
+pub fn list_snapshots(app: AppHandle, path: String) -> Result<Vec<Snapshot>, String> {
+    let manifest = load(&history_root(&app)?, &path);
+    Ok(manifest
+        .snapshots
+        .into_iter()
+        .rev()
+        .map(|s| Snapshot {
+            blob: String::new(),
+            ..s
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn read_snapshot(app: AppHandle, path: String, id: String) -> Result<String, String> {
+    let bytes = read_blob(&app, &path, &id)?;
+    Ok(encoding::decode(&bytes).0)
+}
+
+/// Changes from the snapshot to the file as it is on disk now.
+#[tauri::command]
+pub fn diff_snapshot(app: AppHandle, path: String, id: String) -> Result<Vec<DiffHunk>, String> {
+    let old = encoding::decode(&read_blob(&app, &path, &id)?).0;
+    let new = encoding::decode(&fs::read(&path).unwrap_or_default()).0;
+    let patch = Patch::from_buffers(old.as_bytes(), None, new.as_bytes(), None, None)
+        .map_err(|e| e.to_string())?;
+    git::patch_hunks(&patch)
+}
+
+/// Writes the snapshot back to `path` and returns its content. The current
+/// version is snapshotted first so the restore can itself be undone.
+#[tauri::command]
+pub fn restore_snapshot(app: AppHandle, path: String, id: String) -> Result<String, String> {
+    let bytes = read_blob(&app, &path, &id)?;
+    if let Ok(current) = fs::read(&path) {
+        snapshot(&app, &path, &current)?;
+    }
+    store::write_atomic(Path::new(&path), &bytes)?;
+    encoding::read(&app, &path)
+}
+
+#[tauri::command]
+pub fn set_history_limits(
+    app: AppHandle,
+    state: State<'_, HistoryState>,
+    max_bytes: u64,
+    max_per_file: usize,
+) -> Result<(), String> {
+    store::save_json(
+        &app,
+        HISTORY_CONFIG_FILE,
+        &HistoryConfig {
+            max_bytes,
+            max_per_file,
+        },
+    )?;
+    let mut stored = state.lock.lock().unwrap();
+    *stored = Some(enforce_limit(&history_root(&app)?, max_bytes));
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{OnceLock, RwLock};
+use tauri::{AppHandle, Manager};
+
+const LOCALE_CONFIG_FILE: &str = "locale.json";
+const FALLBACK_LOCALE: &str = "en";
+
+// English ships inside the binary so lookups never come back empty
+const FALLBACK_STRINGS: &str = include_str!("../locales/en.json");
+
+struct Catalog {
+    locale: String,
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct LocaleConfig {
+    /// Overrides the system locale when set, e.g. "de"
+    locale: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct LocaleInfo {
+    locale: String,
+    override_locale: Option<String>,
+    available: Vec<String>,
+}
+
+fn catalog() -> &'static RwLock<Catalog> {
+    static CATALOG: OnceLock<RwLock<Catalog>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        let fallback: HashMap<String, String> =
+            serde_json::from_str(FALLBACK_STRINGS).unwrap_or_default();
+        RwLock::new(Catalog {
+            locale: FALLBACK_LOCALE.to_string(),
+            strings: HashMap::new(),
+            fallback,
+        })
+    })
+}
+
+fn read_locale(app: &AppHandle, locale: &str) -> Option<HashMap<String, String>> {
+    let dir = app.path().resource_dir().ok()?.join("locales");
+    let content = fs::read_to_string(dir.join(format!("{}.json", locale))).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn available_locales(app: &AppHandle) -> Vec<String> {
+    let mut locales: Vec<String> = app
+        .path()
+        .resource_dir()
+        .ok()
+        .and_then(|dir| fs::read_dir(dir.join("locales")).ok())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            Some(path.file_stem()?.to_string_lossy().to_string())
+        })
+        .collect();
+    if !locales.iter().any(|l| l == FALLBACK_LOCALE) {
+        locales.push(FALLBACK_LOCALE.to_string());
+    }
+    locales.sort();
+    locales
+}
+
+/// Loads the override locale, or the system locale, falling back from a
+/// regional variant ("de-AT") to its language ("de") and finally to English.
+pub fn init(app: &AppHandle) {
+    let config: LocaleConfig = store::load_json(app, LOCALE_CONFIG_FILE);
+    let requested = config
+        .locale
+        .or_else(sys_locale::get_locale)
+        .unwrap_or_else(|| FALLBACK_LOCALE.to_string())
+        .replace('_', "-");
+
+    let language = requested.split('-').next().unwrap_or(FALLBACK_LOCALE);
+    let candidates = [requested.as_str(), language];
+    let (locale, strings) = candidates
+        .iter()
+        .find_map(|l| read_locale(app, l).map(|s| (l.to_string(), s)))
+        .unwrap_or_else(|| (FALLBACK_LOCALE.to_string(), HashMap::new()));
+
+    let mut catalog = catalog().write().unwrap();
+    catalog.locale = locale;
+    catalog.strings = strings;
+}
+
+/// Looks up a translated string by key, returning the key itself if unknown.
+pub fn t(key: &str) -> String {
+    let catalog = catalog().read().unwrap();
+    catalog
+        .strings
+        .get(key)
+        .or_else(|| catalog.fallback.get(key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Like `t`, substituting `{name}` placeholders.
+pub fn tf(key: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(t(key), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// All strings for the active locale, for the frontend to render its own UI.
+#[tauri::command]
+pub fn get_translations() -> HashMap<String, String> {
+    let catalog = catalog().read().unwrap();
+    let mut strings = catalog.fallback.clone();
+    strings.extend(catalog.strings.clone());
+    strings
+}
+
+#[tauri::command]
+pub fn get_locale_info(app: AppHandle) -> LocaleInfo {
+    let config: LocaleConfig = store::load_json(&app, LOCALE_CONFIG_FILE);
+    LocaleInfo {
+        locale: catalog().read().unwrap().locale.clone(),
+        override_locale: config.locale,
+        available: available_locales(&app),
+    }
+}
+
+/// Sets or clears (`None` = follow the system) the locale override and reloads
+/// the strings.
+#[tauri::command]
+pub fn set_locale(app: AppHandle, locale: Option<String>) -> Result<LocaleInfo, String> {
+    let locale = locale.filter(|l| !l.trim().is_empty());
+    store::save_json(&app, LOCALE_CONFIG_FILE, &LocaleConfig { locale })?;
+    init(&app);
+    // Rendered notes contain translated embed messages
+    app.state::<crate::render_cache::RenderCacheState>().clear();
+    Ok(get_locale_info(app))
+}
@@ -0,0 +1,123 @@
+use crate::listing::DirectoryListingState;
+use crate::{index, store, vault_config};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use tauri::{AppHandle, Manager};
+
+const IGNORE_FILE: &str = ".markoignore";
+const IGNORE_SETTINGS_FILE: &str = "ignore.json";
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct IgnoreSettings {
+    /// gitignore-style patterns applied in every vault
+    patterns: Vec<String>,
+}
+
+impl Default for IgnoreSettings {
+    fn default() -> Self {
+        IgnoreSettings {
+            patterns: vec!["node_modules/".to_string()],
+        }
+    }
+}
+
+// The walkers run without an AppHandle (index builds, search), so the
+// settings patterns are kept here once loaded
+fn global_patterns() -> &'static RwLock<Vec<String>> {
+    static PATTERNS: OnceLock<RwLock<Vec<String>>> = OnceLock::new();
+    PATTERNS.get_or_init(|| RwLock::new(IgnoreSettings::default().patterns))
+}
+
+pub fn init(app: &AppHandle) {
+    let settings: IgnoreSettings = store::load_json(app, IGNORE_SETTINGS_FILE);
+    *global_patterns().write().unwrap() = settings.patterns;
+}
+
+/// The settings patterns plus the `.markoignore` of the vault a path is in.
+pub struct IgnoreRules {
+    matcher: Gitignore,
+}
+
+impl IgnoreRules {
+    /// Rules for `path`, rooted at the nearest ancestor (or `path` itself)
+    /// holding a `.markoignore` or a vault config; without one, rooted at
+    /// `path`. Patterns in the vault config replace the global ones.
+    pub fn discover(path: &Path) -> IgnoreRules {
+        let root = path
+            .ancestors()
+            .find(|dir| dir.join(IGNORE_FILE).is_file() || vault_config::is_vault(dir))
+            .unwrap_or(path);
+        let mut builder = GitignoreBuilder::new(root);
+        let patterns = match vault_config::load(root).ignore_patterns {
+            Some(patterns) => patterns,
+            None => global_patterns().read().unwrap().clone(),
+        };
+        for pattern in &patterns {
+            let _ = builder.add_line(None, pattern);
+        }
+        // Bad lines are skipped; the rest of the file still applies
+        let _ = builder.add(root.join(IGNORE_FILE));
+        IgnoreRules {
+            matcher: builder.build().unwrap_or_else(|_| Gitignore::empty()),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        self.matcher.path()
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.matcher.is_empty() || !path.starts_with(self.root()) {
+            return false;
+        }
+        self.matcher
+            .matched_path_or_any_parents(path, is_dir)
+            .is_ignore()
+    }
+}
+
+/// Whether a changed path is the ignore file or a vault config, so cached
+/// rules can be rebuilt.
+pub fn is_ignore_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == IGNORE_FILE) || vault_config::is_config_file(path)
+}
+
+/// Surfaces syntax errors instead of silently dropping the pattern.
+pub fn validate(patterns: &[String]) -> Result<(), String> {
+    let mut builder = GitignoreBuilder::new(PathBuf::new());
+    for pattern in patterns {
+        builder.add_line(None, pattern).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_ignore_patterns() -> Vec<String> {
+    global_patterns().read().unwrap().clone()
+}
+
+/// Replaces the global ignore patterns. Vault-specific patterns live in the
+/// vault's `.markoignore`.
+#[tauri::command]
+pub fn set_ignore_patterns(app: AppHandle, patterns: Vec<String>) -> Result<(), String> {
+    let patterns: Vec<String> = patterns
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    validate(&patterns)?;
+    store::save_json(
+        &app,
+        IGNORE_SETTINGS_FILE,
+        &IgnoreSettings {
+            patterns: patterns.clone(),
+        },
+    )?;
+    *global_patterns().write().unwrap() = patterns;
+    app.state::<index::IndexState>().invalidate();
+    app.state::<DirectoryListingState>().clear();
+    Ok(())
+}
@@ -0,0 +1,509 @@
+use crate::frontmatter::Frontmatter;
+use crate::ignores::{self, IgnoreRules};
+use crate::vault;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+
+#[derive(Clone)]
+pub struct NoteEntry {
+    pub words: usize,
+    pub size: u64,
+    pub created: Option<SystemTime>,
+    pub modified: Option<SystemTime>,
+    /// Raw link targets ([[wikilinks]] and relative markdown links), one per occurrence
+    pub links: Vec<String>,
+    /// Inline #tags (one per occurrence) followed by frontmatter `tags`
+    pub tags: Vec<String>,
+    /// Frontmatter `aliases`, also accepted as wikilink targets
+    pub aliases: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct VaultIndex {
+    pub root: PathBuf,
+    pub notes: BTreeMap<PathBuf, NoteEntry>,
+    pub attachments: BTreeSet<PathBuf>,
+    /// Lowercased file name -> paths, for wikilink resolution
+    names: HashMap<String, Vec<PathBuf>>,
+    /// Lowercased alias -> notes declaring it
+    aliases: HashMap<String, Vec<PathBuf>>,
+}
+
+/// Vault indexes shared by renderers, one per root, kept until the vault
+/// changes or the frontend asks for a refresh.
+#[derive(Default)]
+pub struct IndexState {
+    /// Shared out to readers, so lookups run without holding the lock
+    indexes: Mutex<HashMap<PathBuf, Arc<VaultIndex>>>,
+    /// Bumped on every change, so an index built meanwhile isn't kept stale
+    generation: AtomicU64,
+}
+
+impl IndexState {
+    /// Runs `f` against the index for `root`, building it on first use.
+    pub fn with<R>(&self, root: &Path, f: impl FnOnce(&VaultIndex) -> R) -> R {
+        f(&self.get(root))
+    }
+
+    fn get(&self, root: &Path) -> Arc<VaultIndex> {
+        let indexes = self.indexes.lock().unwrap();
+        if let Some(index) = indexes.get(root) {
+            return index.clone();
+        }
+        // Built without the lock, so other vaults aren't held up by a scan
+        let generation = self.generation.load(Ordering::SeqCst);
+        drop(indexes);
+        let built = Arc::new(VaultIndex::build(root));
+
+        let mut indexes = self.indexes.lock().unwrap();
+        if self.generation.load(Ordering::SeqCst) == generation {
+            indexes.insert(root.to_path_buf(), built.clone());
+        }
+        built
+    }
+
+    /// Drops every index so the next use rebuilds it.
+    pub fn invalidate(&self) {
+        let mut indexes = self.indexes.lock().unwrap();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        indexes.clear();
+    }
+
+    /// Re-reads changed paths into the indexes built so far. Returns whether
+    /// any of them belonged to one.
+    pub fn update(&self, paths: &[PathBuf]) -> bool {
+        let mut indexes = self.indexes.lock().unwrap();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        let mut changed = false;
+        for index in indexes.values_mut() {
+            if !paths.iter().any(|p| p.starts_with(&index.root)) {
+                continue;
+            }
+            // Copied first if a reader still holds the current one
+            changed |= Arc::make_mut(index).update_paths(paths);
+        }
+        changed
+    }
+}
+
+/// Starts a thread that applies file change batches to the vault index, so
+/// the folder watcher keeps it current without full rescans. The thread
+/// exits once the returned sender (held by the watcher) is dropped.
+pub fn spawn_indexer(app: AppHandle) -> Sender<Vec<PathBuf>> {
+    let (sender, receiver) = mpsc::channel::<Vec<PathBuf>>();
+    thread::spawn(move || {
+        while let Ok(mut paths) = receiver.recv() {
+            // A single save is often several events; handle them together
+            while let Ok(more) = receiver.recv_timeout(Duration::from_millis(100)) {
+                paths.extend(more);
+            }
+            paths.sort();
+            paths.dedup();
+            if app.state::<IndexState>().update(&paths) {
+                app.state::<crate::render_cache::RenderCacheState>().clear();
+            }
+        }
+    });
+    sender
+}
+
+pub fn wiki_link_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"!?\[\[([^\]\|#]*)(?:#[^\]\|]*)?(?:\|[^\]]*)?\]\]").unwrap())
+}
+
+pub fn md_link_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"!?\[[^\]]*\]\(<?([^)\s>]+)>?(?:\s+[^)]*)?\)").unwrap())
+}
+
+fn tag_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:^|\s)#([\w/-]*[A-Za-z_/-][\w/-]*)").unwrap())
+}
+
+/// Blanks out fenced code blocks and inline code spans while keeping line
+/// numbers intact, so scanners don't pick up links or tags from code.
+pub fn strip_code(content: &str) -> String {
+    let mut in_fence = false;
+    content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+                return String::new();
+            }
+            if in_fence {
+                return String::new();
+            }
+            line.split('`')
+                .enumerate()
+                .filter(|(i, _)| i % 2 == 0)
+                .map(|(_, s)| s)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Link targets on a single line: wikilink names and local markdown link paths.
+pub fn line_links(line: &str) -> Vec<String> {
+    let mut links: Vec<String> = wiki_link_re()
+        .captures_iter(line)
+        .map(|c| c[1].trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    for caps in md_link_re().captures_iter(line) {
+        let target = &caps[1];
+        if target.contains("://") || target.starts_with('#') || target.starts_with("mailto:") {
+            continue;
+        }
+        let target = target.split('#').next().unwrap_or(target);
+        if let Ok(decoded) = urlencoding::decode(target) {
+            links.push(decoded.into_owned());
+        }
+    }
+
+    links
+}
+
+pub fn line_tags(line: &str) -> Vec<String> {
+    tag_re()
+        .captures_iter(line)
+        .map(|c| c[1].trim_end_matches('/').to_string())
+        .collect()
+}
+
+/// Inline #tags from code-stripped content (one per occurrence), followed
+/// by the frontmatter `tags`.
+pub fn note_tags(scannable: &str, frontmatter: Option<&Frontmatter>) -> Vec<String> {
+    let mut tags: Vec<String> = scannable.lines().flat_map(line_tags).collect();
+    if let Some(fm) = frontmatter {
+        tags.extend(fm.tags.iter().cloned());
+    }
+    tags
+}
+
+/// Strips markdown syntax down to readable prose: drops frontmatter, code
+/// blocks and images, keeps link text and heading text.
+pub fn plain_text(content: &str) -> String {
+    static INLINE: OnceLock<[(Regex, &'static str); 6]> = OnceLock::new();
+    let inline = INLINE.get_or_init(|| {
+        [
+            (
+                Regex::new(r"!\[\[[^\]]*\]\]|!\[[^\]]*\]\([^)]*\)").unwrap(),
+                "",
+            ),
+            (
+                Regex::new(r"\[\[(?:[^\]\|]*\|)?([^\]]*)\]\]").unwrap(),
+                "$1",
+            ),
+            (Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap(), "$1"),
+            (Regex::new(r"<[^>]+>").unwrap(), ""),
+            (Regex::new(r"(\*\*|__|\*|~~|==|`)").unwrap(), ""),
+            (
+                Regex::new(r"^\s*(#{1,6}\s+|>\s?|[-*+]\s+(\[[ xX]\]\s+)?|\d+[.)]\s+)").unwrap(),
+                "",
+            ),
+        ]
+    });
+
+    let lines: Vec<&str> = content.lines().collect();
+    let start = crate::blocks::frontmatter_len(&lines);
+    let mut in_fence = false;
+    let mut out: Vec<String> = Vec::new();
+
+    for line in &lines[start..] {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence || trimmed.starts_with('|') && trimmed.contains("---") {
+            continue;
+        }
+        let mut text = line.to_string();
+        for (re, replacement) in inline.iter() {
+            text = re.replace_all(&text, *replacement).into_owned();
+        }
+        out.push(text.replace('|', " ").trim().to_string());
+    }
+
+    out.join("\n")
+}
+
+pub fn count_words(content: &str) -> usize {
+    content.split_whitespace().count()
+}
+
+impl NoteEntry {
+    pub fn read(path: &Path) -> Option<NoteEntry> {
+        let content = fs::read_to_string(path).ok()?;
+        let meta = fs::metadata(path).ok();
+        let scannable = strip_code(&content);
+        let frontmatter = crate::frontmatter::parse(&content).ok().flatten();
+
+        Some(NoteEntry {
+            words: count_words(&content),
+            size: meta.as_ref().map(|m| m.len()).unwrap_or(0),
+            created: meta.as_ref().and_then(|m| m.created().ok()),
+            modified: meta.as_ref().and_then(|m| m.modified().ok()),
+            links: scannable.lines().flat_map(line_links).collect(),
+            tags: note_tags(&scannable, frontmatter.as_ref()),
+            aliases: frontmatter.map(|f| f.aliases).unwrap_or_default(),
+        })
+    }
+}
+
+/// Resolves `.` and `..` components without touching the filesystem, so
+/// `sub/../note.md` matches the indexed `note.md`.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    out.push(component);
+                }
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+impl VaultIndex {
+    pub fn build(root: &Path) -> VaultIndex {
+        let mut index = VaultIndex {
+            root: root.to_path_buf(),
+            notes: BTreeMap::new(),
+            attachments: BTreeSet::new(),
+            names: HashMap::new(),
+            aliases: HashMap::new(),
+        };
+        for file in vault::walk_files(root) {
+            index.add_file(&file);
+        }
+        index
+    }
+
+    fn name_key(path: &Path) -> String {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    }
+
+    fn remove_aliases(&mut self, path: &Path) {
+        self.aliases.retain(|_, paths| {
+            paths.retain(|p| p != path);
+            !paths.is_empty()
+        });
+    }
+
+    /// Adds or refreshes a single file in the index.
+    pub fn add_file(&mut self, path: &Path) {
+        if vault::is_markdown(path) {
+            match NoteEntry::read(path) {
+                Some(entry) => {
+                    self.remove_aliases(path);
+                    for alias in &entry.aliases {
+                        let paths = self.aliases.entry(alias.to_lowercase()).or_default();
+                        if !paths.iter().any(|p| p == path) {
+                            paths.push(path.to_path_buf());
+                            paths.sort();
+                        }
+                    }
+                    self.notes.insert(path.to_path_buf(), entry);
+                }
+                None => return,
+            }
+        } else {
+            self.attachments.insert(path.to_path_buf());
+        }
+        let paths = self.names.entry(Self::name_key(path)).or_default();
+        if !paths.iter().any(|p| p == path) {
+            paths.push(path.to_path_buf());
+            paths.sort();
+        }
+    }
+
+    pub fn remove_file(&mut self, path: &Path) {
+        if self.notes.remove(path).is_some() {
+            self.remove_aliases(path);
+        }
+        self.attachments.remove(path);
+        let key = Self::name_key(path);
+        if let Some(paths) = self.names.get_mut(&key) {
+            paths.retain(|p| p != path);
+            if paths.is_empty() {
+                self.names.remove(&key);
+            }
+        }
+    }
+
+    /// Brings the given paths up to date after they were created, modified,
+    /// removed or renamed. Paths may be folders; hidden and ignored ones are
+    /// skipped as in `build`.
+    pub fn update_paths(&mut self, paths: &[PathBuf]) -> bool {
+        // Edited ignore rules can change what belongs in the index anywhere
+        if paths.iter().any(|p| ignores::is_ignore_file(p)) {
+            *self = VaultIndex::build(&self.root);
+            return true;
+        }
+        let mut changed = false;
+        let rules = IgnoreRules::discover(&self.root);
+        for path in paths {
+            let Ok(relative) = path.strip_prefix(&self.root) else {
+                continue;
+            };
+            if relative
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+            {
+                continue;
+            }
+            changed = true;
+            let stale: Vec<PathBuf> = self
+                .notes
+                .keys()
+                .chain(self.attachments.iter())
+                .filter(|p| p.starts_with(path))
+                .cloned()
+                .collect();
+            for p in &stale {
+                self.remove_file(p);
+            }
+            if rules.is_ignored(path, path.is_dir()) {
+                continue;
+            }
+            if path.is_dir() {
+                for file in vault::walk_files_with(path, &rules) {
+                    self.add_file(&file);
+                }
+            } else if path.is_file() {
+                self.add_file(path);
+            }
+        }
+        changed
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.notes.contains_key(path) || self.attachments.contains(path)
+    }
+
+    /// Resolves a link target found in `from` to a note or attachment path.
+    /// Markdown-style relative paths are tried first, then wikilink-style
+    /// lookup by file name (case-insensitive) anywhere in the vault, then
+    /// frontmatter aliases.
+    pub fn resolve(&self, target: &str, from: &Path) -> Option<PathBuf> {
+        let relative = normalize(&from.parent().unwrap_or(&self.root).join(target));
+        if self.contains(&relative) {
+            return Some(relative);
+        }
+        let relative_md = PathBuf::from(format!("{}.md", relative.to_string_lossy()));
+        if !vault::is_markdown(&relative) && self.contains(&relative_md) {
+            return Some(relative_md);
+        }
+
+        let wanted = target
+            .trim_start_matches("./")
+            .replace('\\', "/")
+            .to_lowercase();
+        let wanted_md = if vault::is_markdown(Path::new(&wanted)) {
+            wanted.clone()
+        } else {
+            format!("{}.md", wanted)
+        };
+
+        if !wanted.contains('/') {
+            return [&wanted_md, &wanted]
+                .iter()
+                .find_map(|name| self.names.get(*name).and_then(|p| p.first()))
+                .or_else(|| self.aliases.get(&wanted).and_then(|p| p.first()))
+                .cloned();
+        }
+
+        // Path-qualified wikilinks like [[folder/note]] match by suffix
+        self.notes
+            .keys()
+            .chain(self.attachments.iter())
+            .find(|path| {
+                let rel = path
+                    .strip_prefix(&self.root)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .replace('\\', "/")
+                    .to_lowercase();
+                rel == wanted_md
+                    || rel == wanted
+                    || rel.ends_with(&format!("/{}", wanted_md))
+                    || rel.ends_with(&format!("/{}", wanted))
+            })
+            .cloned()
+    }
+
+    /// Number of incoming links per note.
+    pub fn link_counts(&self) -> BTreeMap<PathBuf, usize> {
+        let mut counts = BTreeMap::new();
+        for (path, note) in &self.notes {
+            for link in &note.links {
+                if let Some(target) = self.resolve(link, path) {
+                    if target != *path {
+                        *counts.entry(target).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        counts
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct AliasEntry {
+    alias: String,
+    path: String,
+}
+
+/// Every frontmatter alias in the vault with the note it points to, sorted
+/// for wikilink completion.
+#[tauri::command]
+pub fn list_aliases(state: tauri::State<'_, IndexState>, root: String) -> Vec<AliasEntry> {
+    state.with(Path::new(&root), |index| {
+        let mut aliases: Vec<AliasEntry> = index
+            .notes
+            .iter()
+            .flat_map(|(path, note)| {
+                note.aliases.iter().map(move |alias| AliasEntry {
+                    alias: alias.clone(),
+                    path: path.to_string_lossy().to_string(),
+                })
+            })
+            .collect();
+        aliases.sort_by_key(|a| (a.alias.to_lowercase(), a.path.clone()));
+        aliases
+    })
+}
+
+/// Drops the cached vault index so the next render rebuilds it, e.g. after
+/// files were added or renamed. Cached renders go too, since their links and
+/// embeds were resolved against the old index.
+#[tauri::command]
+pub fn refresh_vault_index(
+    state: tauri::State<'_, IndexState>,
+    renders: tauri::State<'_, crate::render_cache::RenderCacheState>,
+) {
+    state.invalidate();
+    renders.clear();
+}
@@ -0,0 +1,198 @@
+use crate::store;
+use regex::Regex;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Serialize)]
+pub struct KanbanCard {
+    text: String,
+    checked: bool,
+    /// Indented lines below the task item (sub-tasks, notes)
+    details: Vec<String>,
+    /// 1-based line of the task item in the source file
+    line: usize,
+    #[serde(skip)]
+    start: usize,
+    #[serde(skip)]
+    end: usize,
+}
+
+#[derive(Serialize)]
+pub struct KanbanColumn {
+    title: String,
+    line: usize,
+    cards: Vec<KanbanCard>,
+    #[serde(skip)]
+    heading: usize,
+}
+
+#[derive(Serialize)]
+pub struct KanbanBoard {
+    columns: Vec<KanbanColumn>,
+}
+
+fn frontmatter_end(lines: &[String]) -> usize {
+    if lines.first().map(|l| l.trim_end()) != Some("---") {
+        return 0;
+    }
+    lines
+        .iter()
+        .skip(1)
+        .position(|l| l.trim_end() == "---")
+        .map(|i| i + 2)
+        .unwrap_or(0)
+}
+
+fn parse_board(lines: &[String]) -> KanbanBoard {
+    let task_re = Regex::new(r"^[-*+] \[([ xX])\] ?(.*)$").unwrap();
+    let mut columns: Vec<KanbanColumn> = Vec::new();
+    let mut in_fence = false;
+
+    let mut i = frontmatter_end(lines);
+    while i < lines.len() {
+        let line = &lines[i];
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if in_fence {
+            i += 1;
+            continue;
+        }
+
+        // Obsidian Kanban keeps its settings in a trailing %% comment block
+        if trimmed.starts_with("%%") {
+            break;
+        }
+
+        if let Some(title) = line.strip_prefix("## ") {
+            columns.push(KanbanColumn {
+                title: title.trim().to_string(),
+                line: i + 1,
+                cards: Vec::new(),
+                heading: i,
+            });
+            i += 1;
+            continue;
+        }
+
+        if let (Some(column), Some(caps)) = (columns.last_mut(), task_re.captures(line)) {
+            let start = i;
+            let mut end = i + 1;
+            while end < lines.len()
+                && !lines[end].trim().is_empty()
+                && lines[end].starts_with([' ', '\t'])
+            {
+                end += 1;
+            }
+            column.cards.push(KanbanCard {
+                text: caps[2].to_string(),
+                checked: &caps[1] != " ",
+                details: lines[start + 1..end].to_vec(),
+                line: start + 1,
+                start,
+                end,
+            });
+            i = end;
+            continue;
+        }
+
+        i += 1;
+    }
+
+    KanbanBoard { columns }
+}
+
+fn read_lines(path: &str) -> Result<(Vec<String>, bool), String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let lines = content.lines().map(|l| l.to_string()).collect();
+    Ok((lines, content.ends_with('\n')))
+}
+
+fn write_lines(path: &str, lines: &[String], trailing_newline: bool) -> Result<(), String> {
+    let mut content = lines.join("\n");
+    if trailing_newline {
+        content.push('\n');
+    }
+    store::write_atomic(Path::new(path), content)
+}
+
+fn move_card_lines(
+    lines: &mut Vec<String>,
+    from_column: usize,
+    from_index: usize,
+    to_column: usize,
+    to_index: usize,
+) -> Result<(), String> {
+    let board = parse_board(lines);
+    let card = board
+        .columns
+        .get(from_column)
+        .and_then(|c| c.cards.get(from_index))
+        .ok_or_else(|| crate::i18n::t("error.card_not_found"))?;
+    if to_column >= board.columns.len() {
+        return Err(crate::i18n::t("error.column_not_found"));
+    }
+
+    let moved: Vec<String> = lines.drain(card.start..card.end).collect();
+
+    // Re-parse so insertion points reflect the removal
+    let board = parse_board(lines);
+    let column = &board.columns[to_column];
+    let insert_at = match column.cards.get(to_index) {
+        Some(card) => card.start,
+        None => match column.cards.last() {
+            Some(card) => card.end,
+            None => {
+                let mut at = column.heading + 1;
+                if lines.get(at).is_some_and(|l| l.trim().is_empty()) {
+                    at += 1;
+                }
+                at
+            }
+        },
+    };
+
+    let count = moved.len();
+    lines.splice(insert_at..insert_at, moved);
+
+    // Keep a blank line between the last card and the next heading
+    let after = insert_at + count;
+    if lines.get(after).is_some_and(|l| l.starts_with('#')) {
+        lines.insert(after, String::new());
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_kanban_board(path: String) -> Result<KanbanBoard, String> {
+    let (lines, _) = read_lines(&path)?;
+    Ok(parse_board(&lines))
+}
+
+#[tauri::command]
+pub fn move_kanban_card(
+    path: String,
+    from_column: usize,
+    from_index: usize,
+    to_column: usize,
+    to_index: usize,
+) -> Result<KanbanBoard, String> {
+    let (mut lines, trailing_newline) = read_lines(&path)?;
+    move_card_lines(&mut lines, from_column, from_index, to_column, to_index)?;
+    write_lines(&path, &lines, trailing_newline)?;
+    Ok(parse_board(&lines))
+}
+
+#[tauri::command]
+pub fn reorder_kanban_card(
+    path: String,
+    column: usize,
+    from_index: usize,
+    to_index: usize,
+) -> Result<KanbanBoard, String> {
+    move_kanban_card(path, column, from_index, column, to_index)
+}
@@ -0,0 +1,197 @@
+use crate::{i18n, store};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter};
+
+const KEYBINDINGS_FILE: &str = "keybindings.json";
+
+/// Every command that can be bound to a key, with its default binding.
+const COMMANDS: &[(&str, Option<&str>)] = &[
+    ("file.new", Some("CmdOrCtrl+N")),
+    ("file.save", Some("CmdOrCtrl+S")),
+    ("tab.new", Some("CmdOrCtrl+T")),
+    ("tab.close", Some("CmdOrCtrl+W")),
+    ("tab.undo_close", Some("CmdOrCtrl+Shift+T")),
+    ("tab.next", Some("CmdOrCtrl+Tab")),
+    ("tab.previous", Some("CmdOrCtrl+Shift+Tab")),
+    ("view.zoom_in", Some("CmdOrCtrl+=")),
+    ("view.zoom_out", Some("CmdOrCtrl+-")),
+    ("view.zoom_reset", Some("CmdOrCtrl+0")),
+    ("view.toggle_toc", Some("CmdOrCtrl+Shift+E")),
+    ("view.toggle_sidebar", None),
+    ("view.toggle_zen_mode", None),
+];
+
+/// Modifiers in the order they're written in a normalized binding.
+const MODIFIERS: &[&str] = &["CmdOrCtrl", "Ctrl", "Cmd", "Alt", "Shift"];
+
+/// The user's changes to the defaults: command -> binding, `None` for a
+/// command they unbound.
+type KeybindingStore = HashMap<String, Option<String>>;
+
+#[derive(Serialize, Clone)]
+pub struct Keybinding {
+    command: String,
+    label: String,
+    default: Option<String>,
+    binding: Option<String>,
+}
+
+fn modifier(name: &str) -> Option<&'static str> {
+    match name.to_lowercase().as_str() {
+        "cmdorctrl" | "commandorcontrol" | "mod" => Some("CmdOrCtrl"),
+        "ctrl" | "control" => Some("Ctrl"),
+        "cmd" | "command" | "meta" | "super" => Some("Cmd"),
+        "alt" | "option" => Some("Alt"),
+        "shift" => Some("Shift"),
+        _ => None,
+    }
+}
+
+/// Writes a binding the canonical way ("shift+ctrl+e" -> "Ctrl+Shift+E") so
+/// equal bindings compare equal.
+fn normalize(binding: &str) -> Result<String, String> {
+    let invalid = || i18n::tf("error.invalid_keybinding", &[("binding", binding)]);
+    let binding = binding.trim();
+    // "Ctrl++" binds the plus key
+    let (mods, key) = match binding.strip_suffix("++") {
+        Some(mods) => (mods, "+"),
+        None => binding.rsplit_once('+').unwrap_or(("", binding)),
+    };
+    let key = key.trim();
+    if key.is_empty() || modifier(key).is_some() {
+        return Err(invalid());
+    }
+    let mut found = Vec::new();
+    for name in mods.split('+').map(str::trim).filter(|m| !m.is_empty()) {
+        let name = modifier(name).ok_or_else(invalid)?;
+        if !found.contains(&name) {
+            found.push(name);
+        }
+    }
+    let mut normalized: Vec<String> = MODIFIERS
+        .iter()
+        .filter(|m| found.contains(m))
+        .map(|m| m.to_string())
+        .collect();
+    let mut chars = key.chars();
+    normalized.push(match (chars.next(), chars.next()) {
+        (Some(c), None) => c.to_uppercase().to_string(),
+        _ => key.to_string(),
+    });
+    Ok(normalized.join("+"))
+}
+
+/// A binding as the current platform sees it, where "CmdOrCtrl" is either
+/// Cmd or Ctrl.
+fn on_platform(binding: &str) -> String {
+    let primary = if cfg!(target_os = "macos") {
+        "Cmd"
+    } else {
+        "Ctrl"
+    };
+    let binding = binding.replace("CmdOrCtrl", primary);
+    normalize(&binding).unwrap_or(binding)
+}
+
+fn bindings_from(store: &KeybindingStore) -> Vec<Keybinding> {
+    COMMANDS
+        .iter()
+        .map(|(command, default)| Keybinding {
+            command: command.to_string(),
+            label: i18n::t(&format!("command.{}", command)),
+            default: default.map(str::to_string),
+            binding: match store.get(*command) {
+                Some(binding) => binding.clone(),
+                None => default.map(str::to_string),
+            },
+        })
+        .collect()
+}
+
+pub fn bindings(app: &AppHandle) -> Vec<Keybinding> {
+    bindings_from(&store::load_json(app, KEYBINDINGS_FILE))
+}
+
+/// The key currently bound to `command`, as a menu accelerator.
+pub fn accelerator(app: &AppHandle, command: &str) -> Option<String> {
+    bindings(app)
+        .into_iter()
+        .find(|b| b.command == command)
+        .and_then(|b| b.binding)
+}
+
+fn save(app: &AppHandle, store: &KeybindingStore) -> Result<Vec<Keybinding>, String> {
+    store::save_json(app, KEYBINDINGS_FILE, store)?;
+    let bindings = bindings_from(store);
+    let _ = app.emit("keybindings-changed", bindings.clone());
+    Ok(bindings)
+}
+
+#[tauri::command]
+pub fn list_keybindings(app: AppHandle) -> Vec<Keybinding> {
+    bindings(&app)
+}
+
+/// Binds `command` to `binding`, or unbinds it with `None`. A binding already
+/// used by another command fails with "keybinding_conflict:<command>" unless
+/// `replace` is set, which unbinds the other command.
+#[tauri::command]
+pub fn set_keybinding(
+    app: AppHandle,
+    command: String,
+    binding: Option<String>,
+    replace: Option<bool>,
+) -> Result<Vec<Keybinding>, String> {
+    if !COMMANDS.iter().any(|(id, _)| *id == command) {
+        return Err(i18n::tf("error.unknown_command", &[("command", &command)]));
+    }
+    let binding = binding.as_deref().map(normalize).transpose()?;
+    let mut store: KeybindingStore = store::load_json(&app, KEYBINDINGS_FILE);
+
+    if let Some(binding) = &binding {
+        let key = on_platform(binding);
+        let conflicts: Vec<String> = bindings_from(&store)
+            .into_iter()
+            .filter(|b| b.command != command)
+            .filter(|b| b.binding.as_deref().map(on_platform).as_ref() == Some(&key))
+            .map(|b| b.command)
+            .collect();
+        if let Some(other) = conflicts.first() {
+            if !replace.unwrap_or(false) {
+                return Err(format!("keybinding_conflict:{}", other));
+            }
+        }
+        for other in conflicts {
+            store.insert(other, None);
+        }
+    }
+
+    let default = COMMANDS
+        .iter()
+        .find(|(id, _)| *id == command)
+        .and_then(|(_, default)| *default);
+    // Only differences from the defaults are stored, so new defaults apply
+    if binding.as_deref() == default {
+        store.remove(&command);
+    } else {
+        store.insert(command, binding);
+    }
+    save(&app, &store)
+}
+
+/// Restores the default binding of `command`, or of every command.
+#[tauri::command]
+pub fn reset_keybindings(
+    app: AppHandle,
+    command: Option<String>,
+) -> Result<Vec<Keybinding>, String> {
+    let mut store: KeybindingStore = store::load_json(&app, KEYBINDINGS_FILE);
+    match command {
+        Some(command) => {
+            store.remove(&command);
+        }
+        None => store.clear(),
+    }
+    save(&app, &store)
+}
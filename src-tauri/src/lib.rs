@@ -1,25 +1,97 @@
 use comrak::{markdown_to_html, ComrakExtensionOptions, ComrakOptions};
 use git2::{Repository, StatusOptions};
-use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{RecursiveMode, Watcher};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult};
 use regex::{Captures, Regex};
 use serde::Serialize;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use tauri::menu::ContextMenu;
 use tauri::{AppHandle, Emitter, Manager, State};
 
 struct WatcherState {
-    watcher: Mutex<Option<RecommendedWatcher>>,
+    watcher: Mutex<Option<watch_events::DebouncedWatcher>>,
 }
 
+struct FolderWatch {
+    root: PathBuf,
+    _watcher: watch_events::DebouncedWatcher,
+}
+
+/// Folder watchers keyed by the id passed to `watch_folder`, so several
+/// vaults or external folders can be watched at once.
+#[derive(Default)]
 struct FolderWatcherState {
-    watcher: Mutex<Option<RecommendedWatcher>>,
+    watchers: Mutex<HashMap<String, FolderWatch>>,
 }
 
+mod ai;
+mod annotations;
+mod assets;
+mod attachments;
+mod auto_backup;
+mod auto_sync;
+mod autosave;
+mod backup;
+mod blocks;
+mod cache;
+mod calendar;
+mod chunked;
+mod citations;
+mod clipboard;
+mod csv_embed;
+mod duplicates;
+mod encoding;
+mod epub;
+mod export;
+mod files;
+mod footnotes;
+mod frontmatter;
+mod git;
+mod git_credentials;
+mod headings;
+mod highlight;
+mod history;
+mod i18n;
+mod ignores;
+mod index;
+mod kanban;
+mod keymap;
+mod links;
+mod listing;
+mod math;
+mod outline;
+mod pagination;
+mod pdf;
+mod pins;
+mod presentation;
+mod query;
+mod quick_open;
+mod recent;
+mod related;
+mod render_cache;
+mod render_map;
+mod render_settings;
+mod search;
+mod secrets;
+mod session;
+mod settings;
 mod setup;
+mod site;
+mod stats;
+mod store;
+mod tags;
+mod tasks;
+mod transclusion;
+mod trash_bin;
+mod tts;
+mod vault;
+mod vault_config;
+mod voice;
+mod watch_events;
 
 #[tauri::command]
 async fn show_window(window: tauri::Window) {
@@ -37,6 +109,52 @@ fn process_obsidian_embeds(content: &str) -> Cow<'_, str> {
 
         let path_escaped = path.replace(" ", "%20");
 
+        // PDFs are rendered to an image by the frontend via render_pdf_page
+        let (file, fragment) = path.split_once('#').unwrap_or((path, ""));
+        if file.to_lowercase().ends_with(".pdf") {
+            let page = fragment
+                .strip_prefix("page=")
+                .and_then(|p| p.parse::<u16>().ok())
+                .unwrap_or(1);
+            let width = size
+                .filter(|s| !s.contains('x'))
+                .map(|s| format!(" width=\"{}\"", s))
+                .unwrap_or_default();
+            return format!(
+                "<img class=\"pdf-embed\" data-pdf=\"{}\" data-page=\"{}\"{} alt=\"{}\" />",
+                file.replace(" ", "%20"),
+                page,
+                width,
+                file
+            );
+        }
+
+        let extension = Path::new(file)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "mp3" | "wav" | "m4a" | "ogg" | "flac" | "aac" | "3gp" => {
+                return format!(
+                    "<audio controls src=\"{}\" title=\"{}\"></audio>",
+                    file.replace(" ", "%20"),
+                    file
+                );
+            }
+            "mp4" | "webm" | "ogv" | "mov" | "mkv" => {
+                let width = size
+                    .map(|s| format!(" width=\"{}\"", s.split('x').next().unwrap_or(s)))
+                    .unwrap_or_default();
+                return format!(
+                    "<video controls src=\"{}\"{} title=\"{}\"></video>",
+                    file.replace(" ", "%20"),
+                    width,
+                    file
+                );
+            }
+            _ => {}
+        }
+
         if let Some(size_str) = size {
             if size_str.contains('x') {
                 let mut dims = size_str.split('x');
@@ -58,72 +176,343 @@ fn process_obsidian_embeds(content: &str) -> Cow<'_, str> {
     })
 }
 
+/// Removes Obsidian `%% comments %%` (inline or spanning lines) outside code.
+/// Newlines inside comments are kept so source positions still line up.
+fn strip_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut in_fence = false;
+    let mut in_comment = false;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if !in_comment && (trimmed.starts_with("```") || trimmed.starts_with("~~~")) {
+            in_fence = !in_fence;
+        }
+        if in_fence || (!in_comment && !line.contains("%%")) {
+            out.push_str(line);
+            continue;
+        }
+
+        let mut rest = line;
+        while !rest.is_empty() {
+            if in_comment {
+                match rest.find("%%") {
+                    Some(end) => {
+                        in_comment = false;
+                        rest = &rest[end + 2..];
+                    }
+                    None => {
+                        if rest.ends_with('\n') {
+                            out.push('\n');
+                        }
+                        rest = "";
+                    }
+                }
+                continue;
+            }
+            let start = rest.find("%%").unwrap_or(rest.len());
+            // Don't treat %% inside an inline code span as a comment marker
+            match rest[..start].find('`') {
+                Some(tick) => {
+                    let close = rest[tick + 1..].find('`').map(|i| tick + 1 + i + 1);
+                    let end = close.unwrap_or(rest.len());
+                    out.push_str(&rest[..end]);
+                    rest = &rest[end..];
+                }
+                None if start < rest.len() => {
+                    out.push_str(&rest[..start]);
+                    in_comment = true;
+                    rest = &rest[start + 2..];
+                }
+                None => {
+                    out.push_str(rest);
+                    rest = "";
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Replaces GitHub-style `:shortcode:` emoji outside code; unknown codes stay as typed.
+fn process_emoji(content: &str) -> String {
+    let re = Regex::new(r":([a-z0-9_+-]+):").unwrap();
+    blocks::replace_outside_code(content, &re, |caps| {
+        emojis::get_by_shortcode(&caps[1])
+            .map(|emoji| emoji.as_str().to_string())
+            .unwrap_or_else(|| caps[0].to_string())
+    })
+}
+
+/// Converts `==highlighted text==` into `<mark>` elements, leaving code untouched.
+fn process_highlights(content: &str) -> String {
+    let re = Regex::new(r"==([^=\s](?:[^=\n]*[^=\s])?)==").unwrap();
+    blocks::replace_outside_code(content, &re, |caps| format!("<mark>{}</mark>", &caps[1]))
+}
+
+pub(crate) fn escape_attr(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn wikilink_anchor(caps: &Captures, links: Option<&index::VaultIndex>, from: &Path) -> String {
+    let inner = &caps[1];
+    let (target, label) = inner.split_once('|').unwrap_or((inner, inner));
+    let (note, heading) = target.split_once('#').unwrap_or((target, ""));
+    let heading_attr = if heading.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " data-heading=\"{}\" data-anchor=\"{}\"",
+            escape_attr(heading),
+            escape_attr(&headings::heading_slug(heading))
+        )
+    };
+
+    match links.and_then(|index| index.resolve(note.trim(), from)) {
+        Some(path) => format!(
+            "<a class=\"wikilink\" href=\"#\" data-path=\"{}\"{}>{}</a>",
+            escape_attr(&path.to_string_lossy()),
+            heading_attr,
+            escape_attr(label.trim())
+        ),
+        None => format!(
+            "<a class=\"wikilink unresolved\" href=\"#\" data-target=\"{}\"{}>{}</a>",
+            escape_attr(note.trim()),
+            heading_attr,
+            escape_attr(label.trim())
+        ),
+    }
+}
+
+/// Turns `[[Note]]`, `[[folder/Note#Heading|label]]` and alias links into
+/// anchors carrying the resolved `data-path`, leaving code untouched.
+fn process_wikilinks(content: &str, links: Option<&index::VaultIndex>, from: &Path) -> String {
+    let re = Regex::new(r"\[\[([^\[\]\n]+?)\]\]").unwrap();
+    blocks::replace_outside_code(content, &re, |caps| wikilink_anchor(caps, links, from))
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+#[serde(default)]
+pub struct RenderOptions {
+    /// Render `$...$` / `$$...$$` as math instead of literal text
+    pub math: bool,
+    /// Hide Obsidian `%% comments %%` from the preview
+    pub strip_comments: bool,
+    /// Vault to resolve `[[wikilinks]]` against
+    pub vault_root: Option<String>,
+    /// The note being rendered, for relative link resolution
+    pub path: Option<String>,
+    /// Folder relative asset paths resolve against; defaults to the note's folder
+    pub base_dir: Option<String>,
+    /// Persisted renderer preferences, filled in by the render commands
+    #[serde(skip_deserializing)]
+    pub settings: render_settings::RenderSettings,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            math: true,
+            strip_comments: true,
+            vault_root: None,
+            path: None,
+            base_dir: None,
+            settings: render_settings::RenderSettings::default(),
+        }
+    }
+}
+
 #[tauri::command]
 fn convert_markdown(content: &str) -> String {
-    let processed = process_obsidian_embeds(content);
+    convert_markdown_with(content, &RenderOptions::default(), None)
+}
+
+fn convert_markdown_with(
+    content: &str,
+    render: &RenderOptions,
+    links: Option<&index::VaultIndex>,
+) -> String {
+    let from = PathBuf::from(
+        render
+            .path
+            .as_deref()
+            .or(render.vault_root.as_deref())
+            .unwrap_or(""),
+    );
+    let base = render.base_dir.as_deref().map(PathBuf::from).or_else(|| {
+        let path = Path::new(render.path.as_deref()?);
+        Some(path.parent()?.to_path_buf())
+    });
+    let mut stack: Vec<PathBuf> = render.path.iter().map(PathBuf::from).collect();
+    render_note(content, render, links, &from, base.as_deref(), &mut stack)
+}
+
+fn render_note(
+    content: &str,
+    render: &RenderOptions,
+    links: Option<&index::VaultIndex>,
+    from: &Path,
+    base: Option<&Path>,
+    stack: &mut Vec<PathBuf>,
+) -> String {
+    let mut content = frontmatter::blank_frontmatter(content);
+    if render.strip_comments {
+        content = strip_comments(&content);
+    }
+    let (content, math) = if render.math {
+        math::protect_math(&content)
+    } else {
+        (content, Vec::new())
+    };
+    let (content, embeds) =
+        transclusion::protect_embeds(&content, from, links, stack, &mut |note, path, stack| {
+            render_note(note, render, links, path, path.parent(), stack)
+        });
+    let content = match base.or(from.parent()) {
+        Some(dir) => csv_embed::process_csv_embeds(&content, dir, links, from),
+        None => content,
+    };
+    let processed = process_obsidian_embeds(&content);
+    let processed = process_wikilinks(&processed, links, from);
+    let mut processed = process_highlights(&processed);
+    if render.settings.emoji_shortcodes {
+        processed = process_emoji(&processed);
+    }
 
+    let settings = &render.settings;
     let mut options = ComrakOptions {
         extension: ComrakExtensionOptions {
             strikethrough: true,
             table: true,
             autolink: true,
             tasklist: true,
-            superscript: false,
-            footnotes: true,
+            superscript: settings.superscript,
+            footnotes: settings.footnotes,
             description_lists: true,
             ..ComrakExtensionOptions::default()
         },
         ..ComrakOptions::default()
     };
+    options.parse.smart = settings.smart_punctuation;
+    // Embeds and wikilinks are emitted as inline HTML, so this stays on
     options.render.unsafe_ = true;
-    options.render.hardbreaks = true;
+    options.render.hardbreaks = settings.hardbreaks;
     options.render.sourcepos = true;
 
-    markdown_to_html(&processed, &options)
+    let html = highlight::highlight_code_blocks(&markdown_to_html(&processed, &options));
+    let mut html = headings::add_heading_ids(&math::restore_math(&html, &math));
+    if let Some(base) = base {
+        html = assets::resolve_asset_paths(&html, base, links, from);
+    }
+    transclusion::restore_embeds(&html, &embeds)
+}
+
+fn render_in_vault(app: &AppHandle, content: &str, options: &mut RenderOptions) -> String {
+    options.settings = app.state::<render_settings::RenderSettingsState>().get(app);
+    let cache = app.state::<render_cache::RenderCacheState>();
+    let key = render_cache::key(content, options);
+    if let Some(html) = cache.get(&key) {
+        return html;
+    }
+    let (html, reads) = render_cache::tracking(|| match options.vault_root.as_deref() {
+        Some(root) => app.state::<index::IndexState>().with(Path::new(root), |index| {
+            convert_markdown_with(content, options, Some(index))
+        }),
+        None => convert_markdown_with(content, options, None),
+    });
+    cache.put(app, key, html.clone(), reads);
+    html
 }
 
 #[tauri::command]
-fn open_markdown(path: String) -> Result<String, String> {
-    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
-    Ok(convert_markdown(&content))
+fn open_markdown(
+    app: AppHandle,
+    path: String,
+    options: Option<RenderOptions>,
+) -> Result<String, String> {
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let mut options = options.unwrap_or_default();
+    options.path.get_or_insert(path);
+    Ok(render_in_vault(&app, &content, &mut options))
 }
 
+#[derive(Serialize)]
+struct MarkdownChunk {
+    html: String,
+    /// Pass back as `offset` to render the next blocks; `None` once done
+    next_offset: Option<u64>,
+    total_bytes: u64,
+}
+
+/// Renders only the first `max_blocks` blocks of a file from `offset`, so very
+/// large notes can be shown incrementally with "load more".
 #[tauri::command]
-fn render_markdown(content: String) -> String {
-    convert_markdown(&content)
+fn open_markdown_chunk(
+    app: AppHandle,
+    path: String,
+    offset: Option<u64>,
+    max_blocks: Option<usize>,
+    options: Option<RenderOptions>,
+) -> Result<MarkdownChunk, String> {
+    let (content, next_offset, total_bytes) =
+        chunked::read_blocks(&path, offset.unwrap_or(0), max_blocks.unwrap_or(500))?;
+    let mut options = options.unwrap_or_default();
+    options.path.get_or_insert(path);
+    Ok(MarkdownChunk {
+        html: render_in_vault(&app, &content, &mut options),
+        next_offset,
+        total_bytes,
+    })
 }
 
 #[tauri::command]
-fn read_file_content(path: String) -> Result<String, String> {
-    fs::read_to_string(path).map_err(|e| e.to_string())
+fn render_markdown(app: AppHandle, content: String, options: Option<RenderOptions>) -> String {
+    render_in_vault(&app, &content, &mut options.unwrap_or_default())
 }
 
+/// Rendered block boundaries paired with their source lines, for scroll sync.
 #[tauri::command]
-fn save_file_content(path: String, content: String) -> Result<(), String> {
-    fs::write(path, content).map_err(|e| e.to_string())
+fn get_render_map(
+    app: AppHandle,
+    content: String,
+    options: Option<RenderOptions>,
+) -> Vec<render_map::RenderedBlock> {
+    let html = render_in_vault(&app, &content, &mut options.unwrap_or_default());
+    render_map::build(&content, &html)
 }
 
 #[tauri::command]
-fn open_file_folder(path: String) -> Result<(), String> {
-    opener::reveal(path).map_err(|e| e.to_string())
+fn read_file_content(app: AppHandle, path: String) -> Result<String, String> {
+    encoding::read(&app, &path)
 }
 
 #[tauri::command]
-fn rename_file(old_path: String, new_path: String) -> Result<(), String> {
-    fs::rename(old_path, new_path).map_err(|e| e.to_string())
+async fn save_file_content(app: AppHandle, path: String, content: String) -> Result<(), String> {
+    let bytes = encoding::encode_for(&app, &path, &content)?;
+    store::write_atomic(Path::new(&path), &bytes)?;
+    // History is best effort; a failed snapshot must not fail the save
+    let _ = history::snapshot(&app, &path, &bytes);
+    Ok(())
 }
 
 #[tauri::command]
-fn trash_file(path: String) -> Result<(), String> {
-    trash::delete(&path).map_err(|e| e.to_string())
+fn open_file_folder(path: String) -> Result<(), String> {
+    opener::reveal(path).map_err(|e| e.to_string())
 }
 
-#[derive(Serialize)]
-struct DirEntry {
-    name: String,
-    path: String,
-    is_dir: bool,
-    modified_at: u64,
+#[tauri::command]
+fn rename_file(app: AppHandle, old_path: String, new_path: String) -> Result<(), String> {
+    fs::rename(&old_path, &new_path).map_err(|e| e.to_string())?;
+    pins::rewrite_pins(&app, Path::new(&old_path), Path::new(&new_path))
+}
+
+#[tauri::command]
+fn trash_file(app: AppHandle, path: String) -> Result<(), String> {
+    trash_bin::trash_path(&app, &path)
 }
 
 #[tauri::command]
@@ -134,49 +523,14 @@ fn is_directory(path: String) -> bool {
 }
 
 #[tauri::command]
-fn read_directory(path: String) -> Result<Vec<DirEntry>, String> {
-    let dir_path = Path::new(&path);
-    if !dir_path.is_dir() {
-        return Err("Path is not a directory".to_string());
-    }
-
-    let mut entries: Vec<DirEntry> = fs::read_dir(dir_path)
-        .map_err(|e| e.to_string())?
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-            let name = entry.file_name().to_string_lossy().to_string();
-
-            // Skip hidden files/folders (starting with .)
-            if name.starts_with('.') {
-                return None;
-            }
-
-            let modified_at = entry
-                .metadata()
-                .ok()
-                .and_then(|m| m.modified().ok())
-                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                .map(|d| d.as_secs())
-                .unwrap_or(0);
-
-            Some(DirEntry {
-                name,
-                path: path.to_string_lossy().to_string(),
-                is_dir: path.is_dir(),
-                modified_at,
-            })
-        })
-        .collect();
-
-    // Sort: directories first, then files, both alphabetically
-    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
-        (true, false) => std::cmp::Ordering::Less,
-        (false, true) => std::cmp::Ordering::Greater,
-        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-    });
-
-    Ok(entries)
+fn read_directory(
+    app: AppHandle,
+    path: String,
+    respect_gitignore: Option<bool>,
+    show_hidden: Option<bool>,
+) -> Result<Vec<listing::DirEntry>, String> {
+    let show_hidden = show_hidden.unwrap_or_else(|| listing::show_hidden_for(&app, &path));
+    listing::list_directory(&path, respect_gitignore.unwrap_or(false), show_hidden)
 }
 
 #[tauri::command]
@@ -189,22 +543,65 @@ fn watch_file(
 
     *watcher_lock = None;
 
-    let path_to_watch = path.clone();
     let app_handle = handle.clone();
+    let mut watched = PathBuf::from(&path);
+    // The parent is watched rather than the file, so a rename reports the
+    // new name and the watch can follow it
+    let parent = watched
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .to_path_buf();
 
-    let mut watcher = RecommendedWatcher::new(
-        move |res: Result<notify::Event, notify::Error>| {
-            if let Ok(_) = res {
-                let _ = app_handle.emit("file-changed", ());
+    let mut watcher = new_debouncer(
+        watch_events::debounce_window(&handle),
+        None,
+        move |result: DebounceEventResult| {
+            let Ok(mut events) = result else {
+                return;
+            };
+            watch_events::fold_saves(&mut events);
+            events.retain(|e| e.paths.contains(&watched));
+            for (from, to) in watch_events::renames(&events) {
+                if from != watched {
+                    continue;
+                }
+                let _ = app_handle.emit(
+                    "file-renamed",
+                    watch_events::RenameEvent {
+                        id: None,
+                        from: from.to_string_lossy().to_string(),
+                        to: to.to_string_lossy().to_string(),
+                    },
+                );
+                watched = to;
+            }
+            let Some(batch) = watch_events::batch(None, &events) else {
+                return;
+            };
+            // Removed, trashed or moved out of its folder. A save that
+            // replaces the file has recreated it by the end of the batch.
+            if !watched.exists() {
+                let _ = app_handle.emit(
+                    "file-deleted",
+                    watch_events::DeletedEvent {
+                        path: watched.to_string_lossy().to_string(),
+                    },
+                );
+                return;
             }
+            let _ = app_handle.emit("file-changed", batch);
         },
-        Config::default(),
     )
     .map_err(|e| e.to_string())?;
 
     watcher
-        .watch(Path::new(&path_to_watch), RecursiveMode::NonRecursive)
+        .watcher()
+        .watch(&parent, RecursiveMode::NonRecursive)
         .map_err(|e| e.to_string())?;
+    watcher
+        .cache()
+        .add_root(&parent, RecursiveMode::NonRecursive);
 
     *watcher_lock = Some(watcher);
 
@@ -218,39 +615,116 @@ fn unwatch_file(state: State<'_, WatcherState>) -> Result<(), String> {
     Ok(())
 }
 
+/// Watches a folder recursively under `id` (defaults to the path), replacing
+/// only an earlier watch with the same id.
 #[tauri::command]
 fn watch_folder(
     handle: AppHandle,
     state: State<'_, FolderWatcherState>,
     path: String,
+    id: Option<String>,
 ) -> Result<(), String> {
-    let mut watcher_lock = state.watcher.lock().unwrap();
-    *watcher_lock = None;
+    let id = id.unwrap_or_else(|| path.clone());
+    let mut watchers = state.watchers.lock().unwrap();
+    if let Some(old) = watchers.remove(&id) {
+        handle
+            .state::<git::GitStatusState>()
+            .unwatch(Some(&old.root));
+    }
 
     let app_handle = handle.clone();
-    let watcher = RecommendedWatcher::new(
-        move |res: Result<notify::Event, notify::Error>| {
-            if let Ok(_) = res {
-                let _ = app_handle.emit("folder-changed", ());
+    let indexer = index::spawn_indexer(handle.clone());
+    let watch_id = id.clone();
+    let root = PathBuf::from(&path);
+    let mut rules = ignores::IgnoreRules::discover(&root);
+    // A checkout or sync touching hundreds of files arrives as one batch
+    let mut watcher = new_debouncer(
+        watch_events::debounce_window(&handle),
+        None,
+        move |result: DebounceEventResult| {
+            let Ok(mut events) = result else {
+                return;
+            };
+            if events
+                .iter()
+                .any(|e| e.paths.iter().any(|p| ignores::is_ignore_file(p)))
+            {
+                rules = ignores::IgnoreRules::discover(&root);
+                app_handle.state::<listing::DirectoryListingState>().clear();
+            }
+            // Churn in ignored folders (node_modules, build output) is dropped
+            for event in &mut events {
+                event.paths.retain(|p| !rules.is_ignored(p, p.is_dir()));
             }
+            watch_events::fold_saves(&mut events);
+            for (from, to) in watch_events::renames(&events) {
+                let _ = app_handle.emit(
+                    "file-renamed",
+                    watch_events::RenameEvent {
+                        id: Some(watch_id.clone()),
+                        from: from.to_string_lossy().to_string(),
+                        to: to.to_string_lossy().to_string(),
+                    },
+                );
+            }
+            let Some(batch) = watch_events::batch(Some(watch_id.clone()), &events) else {
+                return;
+            };
+            let paths: Vec<PathBuf> = events
+                .into_iter()
+                .filter(|e| !e.kind.is_access())
+                .flat_map(|e| e.event.paths)
+                .collect();
+            app_handle.state::<git::GitStatusState>().invalidate(&paths);
+            app_handle
+                .state::<listing::DirectoryListingState>()
+                .invalidate(&paths);
+            let _ = indexer.send(paths);
+            let _ = app_handle.emit("folder-changed", batch);
         },
-        Config::default(),
     )
     .map_err(|e| e.to_string())?;
 
-    let mut watcher = watcher;
     watcher
+        .watcher()
         .watch(Path::new(&path), RecursiveMode::Recursive)
         .map_err(|e| e.to_string())?;
-
-    *watcher_lock = Some(watcher);
+    watcher
+        .cache()
+        .add_root(Path::new(&path), RecursiveMode::Recursive);
+
+    handle
+        .state::<git::GitStatusState>()
+        .watch(Path::new(&path));
+    watchers.insert(
+        id,
+        FolderWatch {
+            root: PathBuf::from(&path),
+            _watcher: watcher,
+        },
+    );
     Ok(())
 }
 
+/// Stops the watch registered under `id`, or every folder watch without one.
 #[tauri::command]
-fn unwatch_folder(state: State<'_, FolderWatcherState>) -> Result<(), String> {
-    let mut watcher_lock = state.watcher.lock().unwrap();
-    *watcher_lock = None;
+fn unwatch_folder(
+    state: State<'_, FolderWatcherState>,
+    git_status: State<'_, git::GitStatusState>,
+    id: Option<String>,
+) -> Result<(), String> {
+    let mut watchers = state.watchers.lock().unwrap();
+    match id {
+        Some(id) => {
+            if let Some(old) = watchers.remove(&id) {
+                git_status.unwatch(Some(&old.root));
+            }
+        }
+        None => {
+            watchers.clear();
+            git_status.unwatch(None);
+        }
+    }
     Ok(())
 }
 
@@ -274,14 +748,6 @@ fn send_markdown_path(state: State<'_, AppState>) -> Vec<String> {
     files
 }
 
-#[tauri::command]
-fn save_theme(app: AppHandle, theme: String) -> Result<(), String> {
-    let config_dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
-    fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
-    let theme_path = config_dir.join("theme.txt");
-    fs::write(theme_path, theme).map_err(|e| e.to_string())
-}
-
 #[tauri::command]
 async fn get_app_mode() -> String {
     let args: Vec<String> = std::env::args().collect();
@@ -372,7 +838,7 @@ fi
                     .args(["+x", "/usr/local/bin/marko"])
                     .output()
                     .map_err(|e| e.to_string())?;
-                Ok("CLI installed successfully".to_string())
+                Ok(i18n::t("message.cli_installed"))
             }
             Err(_) => {
                 // Need elevated permissions - write to temp file first, then use osascript to copy
@@ -387,7 +853,7 @@ fi
                     .map_err(|e| e.to_string())?;
 
                 if output.status.success() {
-                    Ok("CLI installed successfully".to_string())
+                    Ok(i18n::t("message.cli_installed"))
                 } else {
                     let _ = fs::remove_file(temp_path);
                     Err(String::from_utf8_lossy(&output.stderr).to_string())
@@ -451,9 +917,9 @@ fi
             }
         }
 
-        Ok(format!(
-            "CLI installed to {}. Please restart your terminal.",
-            bat_path.display()
+        Ok(i18n::tf(
+            "message.cli_installed_to",
+            &[("path", &bat_path.display().to_string())],
         ))
     }
 
@@ -494,7 +960,7 @@ fi
                     .args(["+x", "/usr/local/bin/marko"])
                     .output()
                     .map_err(|e| e.to_string())?;
-                Ok("CLI installed successfully".to_string())
+                Ok(i18n::t("message.cli_installed"))
             }
             Err(_) => {
                 // Use pkexec for elevated permissions - write to temp first
@@ -507,7 +973,7 @@ fi
                     .map_err(|e| e.to_string())?;
 
                 if output.status.success() {
-                    Ok("CLI installed successfully".to_string())
+                    Ok(i18n::t("message.cli_installed"))
                 } else {
                     let _ = fs::remove_file(temp_path);
                     Err(String::from_utf8_lossy(&output.stderr).to_string())
@@ -540,7 +1006,10 @@ fn git_status_to_string(status: git2::Status) -> Option<&'static str> {
 }
 
 #[tauri::command]
-fn get_git_status(path: String) -> Result<HashMap<String, String>, String> {
+fn get_git_status(
+    cache: State<'_, git::GitStatusState>,
+    path: String,
+) -> Result<HashMap<String, String>, String> {
     let repo = match Repository::discover(&path) {
         Ok(r) => r,
         Err(_) => return Err("not_a_git_repo".to_string()),
@@ -548,8 +1017,11 @@ fn get_git_status(path: String) -> Result<HashMap<String, String>, String> {
 
     let workdir = repo
         .workdir()
-        .ok_or("Bare repository")?
+        .ok_or_else(|| i18n::t("error.bare_repository"))?
         .to_path_buf();
+    if let Some(cached) = cache.get(&workdir) {
+        return Ok(cached);
+    }
 
     let mut opts = StatusOptions::new();
     opts.include_untracked(true)
@@ -568,6 +1040,7 @@ fn get_git_status(path: String) -> Result<HashMap<String, String>, String> {
         }
     }
 
+    cache.put(&workdir, &result);
     Ok(result)
 }
 
@@ -579,7 +1052,10 @@ fn get_file_git_status(path: String) -> Result<Option<String>, String> {
         Err(_) => return Ok(None),
     };
 
-    let workdir = repo.workdir().ok_or("Bare repository")?.to_path_buf();
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| i18n::t("error.bare_repository"))?
+        .to_path_buf();
     let rel_path = file_path
         .strip_prefix(&workdir)
         .map_err(|e| e.to_string())?;
@@ -597,7 +1073,10 @@ fn git_commit_file(path: String, message: String) -> Result<(), String> {
     let repo = Repository::discover(file_path.parent().unwrap_or(file_path))
         .map_err(|e| e.to_string())?;
 
-    let workdir = repo.workdir().ok_or("Bare repository")?.to_path_buf();
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| i18n::t("error.bare_repository"))?
+        .to_path_buf();
     let rel_path = file_path
         .strip_prefix(&workdir)
         .map_err(|e| e.to_string())?;
@@ -632,7 +1111,10 @@ fn git_revert_file(path: String) -> Result<(), String> {
     let repo = Repository::discover(file_path.parent().unwrap_or(file_path))
         .map_err(|e| e.to_string())?;
 
-    let workdir = repo.workdir().ok_or("Bare repository")?.to_path_buf();
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| i18n::t("error.bare_repository"))?
+        .to_path_buf();
     let rel_path = file_path
         .strip_prefix(&workdir)
         .map_err(|e| e.to_string())?;
@@ -640,7 +1122,7 @@ fn git_revert_file(path: String) -> Result<(), String> {
     // Check if the file is untracked (new file not yet in HEAD)
     let status = repo.status_file(rel_path).map_err(|e| e.to_string())?;
     if status.is_wt_new() {
-        return Err("Cannot revert an untracked file".to_string());
+        return Err(i18n::t("error.revert_untracked"));
     }
 
     // Checkout the file from HEAD to discard working tree changes
@@ -658,6 +1140,8 @@ fn git_revert_file(path: String) -> Result<(), String> {
 struct GitAheadBehind {
     ahead: usize,
     behind: usize,
+    /// The remote-tracking branch compared against, e.g. `origin/main`
+    upstream: Option<String>,
 }
 
 #[tauri::command]
@@ -677,15 +1161,21 @@ fn get_git_ahead_behind(path: String) -> Result<Option<GitAheadBehind>, String>
         None => return Ok(None),
     };
 
-    let branch_name = match head.shorthand() {
-        Some(name) => name.to_string(),
-        None => return Ok(None),
+    let upstream_name = match head.name().and_then(|name| git::upstream_ref(&repo, name)) {
+        Some(name) => name,
+        None => {
+            return Ok(Some(GitAheadBehind {
+                ahead: 0,
+                behind: 0,
+                upstream: None,
+            }))
+        }
     };
-
-    let upstream_name = format!("refs/remotes/origin/{}", branch_name);
+    // The upstream is configured but its remote-tracking branch is gone,
+    // e.g. deleted on the remote and pruned, or never fetched
     let upstream_ref = match repo.find_reference(&upstream_name) {
         Ok(r) => r,
-        Err(_) => return Ok(Some(GitAheadBehind { ahead: 0, behind: 0 })),
+        Err(_) => return Ok(None),
     };
 
     let upstream_oid = match upstream_ref.target() {
@@ -697,40 +1187,11 @@ fn get_git_ahead_behind(path: String) -> Result<Option<GitAheadBehind>, String>
         .graph_ahead_behind(local_oid, upstream_oid)
         .map_err(|e| e.to_string())?;
 
-    Ok(Some(GitAheadBehind { ahead, behind }))
-}
-
-#[tauri::command]
-async fn git_sync(path: String) -> Result<String, String> {
-    let repo = Repository::discover(&path).map_err(|_| "Not a git repository".to_string())?;
-    let workdir = repo
-        .workdir()
-        .ok_or("Bare repository")?
-        .to_path_buf();
-
-    let pull = std::process::Command::new("git")
-        .args(["pull", "--ff-only"])
-        .current_dir(&workdir)
-        .output()
-        .map_err(|e| format!("Failed to run git pull: {}", e))?;
-
-    if !pull.status.success() {
-        let stderr = String::from_utf8_lossy(&pull.stderr);
-        return Err(format!("git pull failed: {}", stderr));
-    }
-
-    let push = std::process::Command::new("git")
-        .args(["push"])
-        .current_dir(&workdir)
-        .output()
-        .map_err(|e| format!("Failed to run git push: {}", e))?;
-
-    if !push.status.success() {
-        let stderr = String::from_utf8_lossy(&push.stderr);
-        return Err(format!("git push failed: {}", stderr));
-    }
-
-    Ok("Sync complete".to_string())
+    Ok(Some(GitAheadBehind {
+        ahead,
+        behind,
+        upstream: upstream_ref.shorthand().map(|s| s.to_string()),
+    }))
 }
 
 #[tauri::command]
@@ -757,9 +1218,9 @@ fn show_context_menu(
             let new_tab = tauri::menu::MenuItem::with_id(
                 &app,
                 "ctx_tab_new",
-                "New Tab",
+                i18n::t("menu.new_tab"),
                 true,
-                Some("Ctrl+T"),
+                keymap::accelerator(&app, "tab.new").as_deref(),
             )
             .map_err(|e| e.to_string())?;
             menu.append(&new_tab).map_err(|e| e.to_string())?;
@@ -767,9 +1228,9 @@ fn show_context_menu(
             let undo = tauri::menu::MenuItem::with_id(
                 &app,
                 "ctx_tab_undo",
-                "Undo Close Tab",
+                i18n::t("menu.undo_close_tab"),
                 true,
-                Some("Ctrl+Shift+T"),
+                keymap::accelerator(&app, "tab.undo_close").as_deref(),
             )
             .map_err(|e| e.to_string())?;
             menu.append(&undo).map_err(|e| e.to_string())?;
@@ -777,7 +1238,7 @@ fn show_context_menu(
             let rename = tauri::menu::MenuItem::with_id(
                 &app,
                 "ctx_tab_rename",
-                "Rename",
+                i18n::t("menu.rename"),
                 true,
                 None::<&str>,
             )
@@ -791,9 +1252,9 @@ fn show_context_menu(
             let close = tauri::menu::MenuItem::with_id(
                 &app,
                 "ctx_tab_close",
-                "Close Tab",
+                i18n::t("menu.close_tab"),
                 true,
-                Some("Ctrl+W"),
+                keymap::accelerator(&app, "tab.close").as_deref(),
             )
             .map_err(|e| e.to_string())?;
             menu.append(&close).map_err(|e| e.to_string())?;
@@ -801,7 +1262,7 @@ fn show_context_menu(
             let close_others = tauri::menu::MenuItem::with_id(
                 &app,
                 "ctx_tab_close_others",
-                "Close Other Tabs",
+                i18n::t("menu.close_other_tabs"),
                 true,
                 None::<&str>,
             )
@@ -811,7 +1272,7 @@ fn show_context_menu(
             let close_right = tauri::menu::MenuItem::with_id(
                 &app,
                 "ctx_tab_close_right",
-                "Close Tabs to Right",
+                i18n::t("menu.close_tabs_right"),
                 true,
                 None::<&str>,
             )
@@ -822,9 +1283,9 @@ fn show_context_menu(
             let new_tab = tauri::menu::MenuItem::with_id(
                 &app,
                 "ctx_tab_new",
-                "New Tab",
+                i18n::t("menu.new_tab"),
                 true,
-                Some("Ctrl+T"),
+                keymap::accelerator(&app, "tab.new").as_deref(),
             )
             .map_err(|e| e.to_string())?;
             menu.append(&new_tab).map_err(|e| e.to_string())?;
@@ -832,18 +1293,18 @@ fn show_context_menu(
             let undo = tauri::menu::MenuItem::with_id(
                 &app,
                 "ctx_tab_undo",
-                "Undo Close Tab",
+                i18n::t("menu.undo_close_tab"),
                 true,
-                Some("Ctrl+Shift+T"),
+                keymap::accelerator(&app, "tab.undo_close").as_deref(),
             )
             .map_err(|e| e.to_string())?;
             menu.append(&undo).map_err(|e| e.to_string())?;
         }
         "file_tree" => {
             let reveal_label = if cfg!(target_os = "macos") {
-                "Reveal in Finder"
+                i18n::t("menu.reveal_in_finder")
             } else {
-                "Show in Explorer"
+                i18n::t("menu.show_in_explorer")
             };
             let reveal = tauri::menu::MenuItem::with_id(
                 &app,
@@ -862,7 +1323,7 @@ fn show_context_menu(
             let copy_name = tauri::menu::MenuItem::with_id(
                 &app,
                 "ctx_file_copy_name",
-                "Copy Name",
+                i18n::t("menu.copy_name"),
                 true,
                 None::<&str>,
             )
@@ -872,7 +1333,7 @@ fn show_context_menu(
             let copy_path = tauri::menu::MenuItem::with_id(
                 &app,
                 "ctx_file_copy_path",
-                "Copy Path",
+                i18n::t("menu.copy_path"),
                 true,
                 None::<&str>,
             )
@@ -886,7 +1347,7 @@ fn show_context_menu(
             let trash = tauri::menu::MenuItem::with_id(
                 &app,
                 "ctx_file_trash",
-                "Move to Trash",
+                i18n::t("menu.move_to_trash"),
                 true,
                 None::<&str>,
             )
@@ -896,7 +1357,7 @@ fn show_context_menu(
         _ => {
             // Document / Default
             if has_selection {
-                let copy = tauri::menu::PredefinedMenuItem::copy(&app, Some("Copy"))
+                let copy = tauri::menu::PredefinedMenuItem::copy(&app, Some(&i18n::t("menu.copy")))
                     .map_err(|e| e.to_string())?;
                 menu.append(&copy).map_err(|e| e.to_string())?;
 
@@ -907,7 +1368,7 @@ fn show_context_menu(
                 let code_block = tauri::menu::MenuItem::with_id(
                     &app,
                     "ctx_doc_code_block",
-                    "Add Code Block",
+                    i18n::t("menu.add_code_block"),
                     true,
                     None::<&str>,
                 )
@@ -917,7 +1378,7 @@ fn show_context_menu(
                 let quote = tauri::menu::MenuItem::with_id(
                     &app,
                     "ctx_doc_quote",
-                    "Add Quote",
+                    i18n::t("menu.add_quote"),
                     true,
                     None::<&str>,
                 )
@@ -925,7 +1386,7 @@ fn show_context_menu(
                 menu.append(&quote).map_err(|e| e.to_string())?;
             }
 
-            let select_all = tauri::menu::PredefinedMenuItem::select_all(&app, Some("Select All"))
+            let select_all = tauri::menu::PredefinedMenuItem::select_all(&app, Some(&i18n::t("menu.select_all")))
                 .map_err(|e| e.to_string())?;
             menu.append(&select_all).map_err(|e| e.to_string())?;
 
@@ -937,7 +1398,7 @@ fn show_context_menu(
                 let open_folder = tauri::menu::MenuItem::with_id(
                     &app,
                     "ctx_open_folder",
-                    "Open File Location",
+                    i18n::t("menu.open_file_location"),
                     true,
                     None::<&str>,
                 )
@@ -954,7 +1415,7 @@ fn show_context_menu(
                 let inspect = tauri::menu::MenuItem::with_id(
                     &app,
                     "ctx_inspect",
-                    "Inspect Element",
+                    i18n::t("menu.inspect_element"),
                     true,
                     None::<&str>,
                 )
@@ -990,13 +1451,31 @@ pub fn run() {
         .manage(WatcherState {
             watcher: Mutex::new(None),
         })
-        .manage(FolderWatcherState {
-            watcher: Mutex::new(None),
-        })
+        .manage(FolderWatcherState::default())
         .manage(ContextMenuState {
             active_path: Mutex::new(None),
             active_tab_id: Mutex::new(None),
         })
+        .manage(ai::AiState::default())
+        .manage(presentation::PresentationState::default())
+        .manage(citations::BibliographyState::default())
+        .manage(cache::CacheState::default())
+        .manage(history::HistoryState::default())
+        .manage(encoding::EncodingState::default())
+        .manage(listing::DirectoryListingState::default())
+        .manage(trash_bin::PermanentDeleteState::default())
+        .manage(voice::RecordingState::default())
+        .manage(index::IndexState::default())
+        .manage(render_cache::RenderCacheState::default())
+        .manage(search::SearchState::default())
+        .manage(recent::RecentState::default())
+        .manage(auto_sync::AutoSyncState::default())
+        .manage(auto_backup::AutoBackupState::default())
+        .manage(git::GitStatusState::default())
+        .manage(autosave::AutosaveState::default())
+        .manage(render_settings::RenderSettingsState::default())
+        .manage(settings::SettingsState::default())
+        .manage(session::SessionState::default())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
@@ -1150,6 +1629,10 @@ pub fn run() {
             }
         })
         .setup(|app| {
+            i18n::init(app.handle());
+            ignores::init(app.handle());
+            auto_sync::resume(app.handle());
+            auto_backup::resume(app.handle());
             let args: Vec<String> = std::env::args().collect();
 
             let current_exe = std::env::current_exe().unwrap_or_default();
@@ -1183,17 +1666,17 @@ pub fn run() {
             .visible(false)
             .build()?;
 
-            let config_dir = app.path().app_config_dir()?;
-            let theme_path = config_dir.join("theme.txt");
-            let theme_pref =
-                fs::read_to_string(theme_path).unwrap_or_else(|_| "system".to_string());
+            let theme_pref = app
+                .state::<settings::SettingsState>()
+                .get(app.handle())
+                .theme;
 
             let window = app.get_webview_window(label).unwrap();
 
-            let bg_color = match theme_pref.as_str() {
-                "dark" => Some(tauri::window::Color(24, 24, 24, 255)),
-                "light" => Some(tauri::window::Color(253, 253, 253, 255)),
-                _ => {
+            let bg_color = match theme_pref {
+                settings::Theme::Dark => Some(tauri::window::Color(24, 24, 24, 255)),
+                settings::Theme::Light => Some(tauri::window::Color(253, 253, 253, 255)),
+                settings::Theme::System => {
                     if let Ok(t) = window.theme() {
                         match t {
                             tauri::Theme::Dark => Some(tauri::window::Color(24, 24, 24, 255)),
@@ -1230,6 +1713,7 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             open_markdown,
+            open_markdown_chunk,
             render_markdown,
             send_markdown_path,
             read_file_content,
@@ -1251,18 +1735,191 @@ pub fn run() {
             unwatch_folder,
             show_context_menu,
             show_window,
-            save_theme,
+            settings::save_theme,
             install_cli,
             get_git_status,
             get_file_git_status,
             git_commit_file,
-            git_sync,
             get_git_ahead_behind,
-            git_revert_file
+            git_revert_file,
+            footnotes::renumber_footnotes,
+            kanban::get_kanban_board,
+            kanban::move_kanban_card,
+            kanban::reorder_kanban_card,
+            calendar::get_daily_notes_config,
+            calendar::save_daily_notes_config,
+            calendar::get_calendar_data,
+            stats::get_vault_stats,
+            pagination::paginate_markdown,
+            ai::get_ai_config,
+            ai::save_ai_config,
+            ai::has_ai_api_key,
+            ai::set_ai_api_key,
+            ai::ai_summarize,
+            ai::ai_rewrite,
+            ai::ai_cancel,
+            tts::list_tts_voices,
+            tts::export_audio,
+            presentation::start_presentation,
+            presentation::next_slide,
+            presentation::previous_slide,
+            presentation::goto_slide,
+            presentation::get_presentation_state,
+            presentation::stop_presentation,
+            annotations::get_block_anchors,
+            annotations::get_annotations,
+            annotations::add_highlight,
+            annotations::update_highlight,
+            annotations::remove_highlight,
+            annotations::add_bookmark,
+            annotations::remove_bookmark,
+            annotations::export_highlights,
+            citations::load_bibliography,
+            citations::complete_citation,
+            citations::lint_citations,
+            cache::get_cache_stats,
+            cache::clear_cache,
+            cache::set_cache_limit,
+            pdf::render_pdf_page,
+            voice::record_audio,
+            voice::stop_audio_recording,
+            voice::get_recording_status,
+            i18n::get_translations,
+            i18n::get_locale_info,
+            i18n::set_locale,
+            highlight::get_highlight_css,
+            highlight::list_highlight_languages,
+            frontmatter::parse_frontmatter,
+            index::refresh_vault_index,
+            render_settings::get_render_settings,
+            render_settings::save_render_settings,
+            headings::slugify,
+            tasks::toggle_task,
+            get_render_map,
+            render_cache::get_render_cache_stats,
+            render_cache::clear_render_cache,
+            render_cache::set_render_cache_size,
+            export::export_html,
+            export::export_pdf,
+            export::get_pandoc_info,
+            export::export_document,
+            export::export_slides,
+            epub::export_epub,
+            site::export_site,
+            outline::get_folder_outline,
+            search::search_vault,
+            search::cancel_search,
+            quick_open::quick_open,
+            links::get_backlinks,
+            tags::list_tags,
+            tags::files_for_tag,
+            links::validate_links,
+            links::rename_with_links,
+            links::find_orphaned_attachments,
+            search::list_saved_searches,
+            search::save_search,
+            search::delete_saved_search,
+            recent::list_recent,
+            recent::add_recent,
+            recent::remove_recent,
+            recent::pop_closed_tab,
+            recent::clear_recent,
+            recent::set_recent_limit,
+            pins::list_pins,
+            pins::pin_path,
+            pins::unpin_path,
+            pins::reorder_pins,
+            related::get_related_notes,
+            query::query_notes,
+            index::list_aliases,
+            duplicates::find_duplicate_notes,
+            git::git_file_history,
+            git::git_diff,
+            git::git_show_file,
+            git::git_restore_file,
+            git::git_blame,
+            git::git_stage,
+            git::git_unstage,
+            git::git_commit_staged,
+            git::git_commit_all,
+            git::git_sync,
+            git_credentials::get_git_credentials,
+            git_credentials::set_git_token,
+            git_credentials::set_git_ssh_key,
+            git_credentials::clear_git_credentials,
+            auto_sync::get_auto_sync,
+            auto_sync::set_auto_sync,
+            git::git_list_conflicts,
+            git::git_mark_resolved,
+            git::git_diff_commits,
+            git::git_init,
+            git::git_clone,
+            git::git_list_remotes,
+            git::git_add_remote,
+            git::git_remove_remote,
+            git::git_set_remote_url,
+            git::git_discard_lines,
+            git::git_branch_status,
+            git::git_fetch,
+            files::move_path,
+            files::copy_path,
+            autosave::autosave_update,
+            autosave::autosave_flush,
+            autosave::autosave_cancel,
+            autosave::get_autosave_settings,
+            autosave::set_autosave_settings,
+            history::list_snapshots,
+            history::read_snapshot,
+            history::diff_snapshot,
+            history::restore_snapshot,
+            history::set_history_limits,
+            encoding::get_file_encoding,
+            encoding::set_file_encoding,
+            chunked::read_file_range,
+            chunked::read_file_lines,
+            listing::read_directory_page,
+            listing::get_show_hidden,
+            listing::set_show_hidden,
+            ignores::get_ignore_patterns,
+            ignores::set_ignore_patterns,
+            files::get_file_info,
+            trash_bin::list_trash,
+            trash_bin::restore_trashed,
+            trash_bin::request_permanent_delete,
+            trash_bin::delete_permanently,
+            clipboard::save_clipboard_image,
+            attachments::get_attachment_location,
+            attachments::set_attachment_location,
+            attachments::get_attachment_folder,
+            attachments::import_attachment,
+            backup::backup_vault,
+            backup::list_backups,
+            backup::restore_backup,
+            auto_backup::get_auto_backup,
+            auto_backup::set_auto_backup,
+            watch_events::get_watch_debounce,
+            watch_events::set_watch_debounce,
+            settings::get_all_settings,
+            settings::get_setting,
+            settings::set_setting,
+            keymap::list_keybindings,
+            keymap::set_keybinding,
+            keymap::reset_keybindings,
+            vault_config::get_vault_config,
+            vault_config::set_vault_config,
+            vault_config::find_vault_root,
+            vault_config::new_note_content,
+            session::save_session_tabs,
+            session::save_session_scroll,
+            session::save_session_folder,
+            session::restore_session
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|_app_handle, _event| {
+            if let tauri::RunEvent::Exit = _event {
+                _app_handle.state::<session::SessionState>().flush(_app_handle);
+            }
             #[cfg(target_os = "macos")]
             if let tauri::RunEvent::Opened { urls } = _event {
                 log::info!("[macOS file open] Received Opened event with {} URLs", urls.len());
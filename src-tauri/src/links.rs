@@ -0,0 +1,507 @@
+use crate::autosave::AutosaveState;
+use crate::index::{self, IndexState, VaultIndex};
+use crate::render_cache::RenderCacheState;
+use crate::{store, vault};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, State};
+
+#[derive(Serialize)]
+pub struct LinkReference {
+    /// 1-based
+    line: usize,
+    /// The whole source line, trimmed
+    text: String,
+}
+
+#[derive(Serialize)]
+pub struct Backlink {
+    path: String,
+    references: Vec<LinkReference>,
+}
+
+/// Lines in `source` with a link (outside code) that resolves to `target`.
+fn references_to(index: &VaultIndex, source: &Path, target: &Path) -> Vec<LinkReference> {
+    let Ok(content) = fs::read_to_string(source) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    index::strip_code(&content)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            index::line_links(line)
+                .iter()
+                .any(|link| index.resolve(link, source).as_deref() == Some(target))
+        })
+        .map(|(i, _)| LinkReference {
+            line: i + 1,
+            text: lines.get(i).unwrap_or(&"").trim().to_string(),
+        })
+        .collect()
+}
+
+/// Notes linking to `target` via [[wikilinks]] or markdown links.
+pub fn backlinks(index: &VaultIndex, target: &Path) -> Vec<Backlink> {
+    index
+        .notes
+        .iter()
+        .filter(|(source, note)| {
+            source.as_path() != target
+                && note
+                    .links
+                    .iter()
+                    .any(|link| index.resolve(link, source).as_deref() == Some(target))
+        })
+        .map(|(source, _)| Backlink {
+            path: source.to_string_lossy().to_string(),
+            references: references_to(index, source, target),
+        })
+        .filter(|backlink| !backlink.references.is_empty())
+        .collect()
+}
+
+/// Backlinks to a note or attachment. `vault_root` defaults to the file's folder.
+#[tauri::command]
+pub fn get_backlinks(
+    state: State<'_, IndexState>,
+    path: String,
+    vault_root: Option<String>,
+) -> Result<Vec<Backlink>, String> {
+    let target = PathBuf::from(&path);
+    let root = match vault_root {
+        Some(root) => PathBuf::from(root),
+        None => target
+            .parent()
+            .ok_or_else(|| crate::i18n::t("error.invalid_file_path"))?
+            .to_path_buf(),
+    };
+    Ok(state.with(&root, |index| backlinks(index, &target)))
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BrokenLinkKind {
+    /// `[[note]]` to a note that doesn't exist
+    MissingNote,
+    /// `![[file]]` / `![alt](file)` whose file doesn't exist
+    MissingEmbed,
+    /// `[text](path)` to a file that doesn't exist
+    MissingFile,
+}
+
+#[derive(Serialize)]
+pub struct BrokenLink {
+    path: String,
+    /// 1-based
+    line: usize,
+    /// 1-based character column of the link
+    column: usize,
+    target: String,
+    kind: BrokenLinkKind,
+}
+
+fn link_exists(index: &VaultIndex, target: &str, source: &Path) -> bool {
+    if index.resolve(target, source).is_some() {
+        return true;
+    }
+    // Files the index skips (hidden folders, outside the vault) still count
+    let path = Path::new(target);
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        source.parent().unwrap_or(&index.root).join(path)
+    };
+    path.exists()
+}
+
+fn broken_links_in(index: &VaultIndex, source: &Path) -> Vec<BrokenLink> {
+    let Ok(content) = fs::read_to_string(source) else {
+        return Vec::new();
+    };
+    let original: Vec<&str> = content.lines().collect();
+    let mut broken = Vec::new();
+    for (i, line) in index::strip_code(&content).lines().enumerate() {
+        let mut report = |link: &str, target: String, kind: BrokenLinkKind| {
+            // Stripped inline code shifts columns, so locate the link in the real line
+            let source_line = original.get(i).copied().unwrap_or(line);
+            let start = source_line.find(link).unwrap_or(0);
+            broken.push(BrokenLink {
+                path: source.to_string_lossy().to_string(),
+                line: i + 1,
+                column: source_line[..start].chars().count() + 1,
+                target,
+                kind,
+            });
+        };
+
+        for caps in index::wiki_link_re().captures_iter(line) {
+            let whole = caps.get(0).unwrap();
+            let target = caps[1].trim();
+            // `[[#Heading]]` points into the same note
+            if target.is_empty() || link_exists(index, target, source) {
+                continue;
+            }
+            let kind = if whole.as_str().starts_with('!') {
+                BrokenLinkKind::MissingEmbed
+            } else {
+                BrokenLinkKind::MissingNote
+            };
+            report(whole.as_str(), target.to_string(), kind);
+        }
+
+        for caps in index::md_link_re().captures_iter(line) {
+            let whole = caps.get(0).unwrap();
+            let raw = &caps[1];
+            if raw.contains("://") || raw.starts_with('#') || raw.starts_with("mailto:") {
+                continue;
+            }
+            let raw = raw.split('#').next().unwrap_or(raw);
+            let target = urlencoding::decode(raw)
+                .map(|t| t.into_owned())
+                .unwrap_or_else(|_| raw.to_string());
+            if link_exists(index, &target, source) {
+                continue;
+            }
+            let kind = if whole.as_str().starts_with('!') {
+                BrokenLinkKind::MissingEmbed
+            } else {
+                BrokenLinkKind::MissingFile
+            };
+            report(whole.as_str(), target, kind);
+        }
+    }
+    broken
+}
+
+/// Every wikilink, markdown link and embed in the vault whose target is
+/// missing, with its location.
+#[tauri::command]
+pub async fn validate_links(root: String) -> Result<Vec<BrokenLink>, String> {
+    let root = PathBuf::from(root);
+    if !root.is_dir() {
+        return Err(crate::i18n::t("error.not_a_directory"));
+    }
+    // A fresh index: a stale cached one would report links to new files
+    let index = VaultIndex::build(&root);
+    Ok(index
+        .notes
+        .keys()
+        .flat_map(|source| broken_links_in(&index, source))
+        .collect())
+}
+
+#[derive(Serialize)]
+pub struct LineChange {
+    /// 1-based
+    line: usize,
+    before: String,
+    after: String,
+}
+
+#[derive(Serialize)]
+pub struct LinkUpdate {
+    /// The file as it will be after the rename
+    path: String,
+    changes: Vec<LineChange>,
+    #[serde(skip)]
+    content: String,
+}
+
+/// `to` relative to the directory `from_dir`, with `/` separators.
+fn relative_path(from_dir: &Path, to: &Path) -> String {
+    let from: Vec<_> = from_dir.components().collect();
+    let target: Vec<_> = to.components().collect();
+    let common = from.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        target[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+/// New text for a `[[target]]` that pointed at the renamed file, keeping the
+/// style it was written in: bare name or vault path, with or without `.md`.
+fn new_wiki_target(old_target: &str, root: &Path, new: &Path) -> String {
+    let keep_ext = !vault::is_markdown(new) || vault::is_markdown(Path::new(old_target));
+    let target = if old_target.contains('/') {
+        relative_path(root, new)
+    } else {
+        new.file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    };
+    if keep_ext {
+        target
+    } else {
+        Path::new(&target)
+            .with_extension("")
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+fn encode_md_target(target: &str) -> String {
+    target.replace(' ', "%20")
+}
+
+/// Rewrites the links on one line. `resolve_old` maps a link target written
+/// in the note to its destination before the rename; `rebase` is the note's
+/// folder after the rename, for its relative markdown links.
+fn rewrite_line(
+    line: &str,
+    root: &Path,
+    resolve_old: &dyn Fn(&str) -> Option<PathBuf>,
+    moved: &dyn Fn(&Path) -> PathBuf,
+    rebase: &Path,
+    old: &Path,
+) -> String {
+    let line = index::wiki_link_re().replace_all(line, |caps: &regex::Captures| {
+        let whole = &caps[0];
+        let target = caps[1].trim();
+        if target.is_empty() || resolve_old(target).as_deref() != Some(old) {
+            return whole.to_string();
+        }
+        let start = caps.get(1).unwrap().start() - caps.get(0).unwrap().start();
+        let end = caps.get(1).unwrap().end() - caps.get(0).unwrap().start();
+        format!(
+            "{}{}{}",
+            &whole[..start],
+            new_wiki_target(target, root, &moved(old)),
+            &whole[end..]
+        )
+    });
+    index::md_link_re()
+        .replace_all(&line, |caps: &regex::Captures| {
+            let whole = &caps[0];
+            let raw = &caps[1];
+            if raw.contains("://") || raw.starts_with('#') || raw.starts_with("mailto:") {
+                return whole.to_string();
+            }
+            let (path_part, fragment) = match raw.find('#') {
+                Some(i) => (&raw[..i], &raw[i..]),
+                None => (raw, ""),
+            };
+            let decoded = urlencoding::decode(path_part)
+                .map(|t| t.into_owned())
+                .unwrap_or_else(|_| path_part.to_string());
+            let Some(destination) = resolve_old(&decoded) else {
+                return whole.to_string();
+            };
+            let destination = moved(&destination);
+            let new_target = format!(
+                "{}{}",
+                encode_md_target(&relative_path(rebase, &destination)),
+                fragment
+            );
+            if new_target == raw {
+                return whole.to_string();
+            }
+            let start = caps.get(1).unwrap().start() - caps.get(0).unwrap().start();
+            let end = caps.get(1).unwrap().end() - caps.get(0).unwrap().start();
+            format!("{}{}{}", &whole[..start], new_target, &whole[end..])
+        })
+        .into_owned()
+}
+
+/// Every change needed to keep links working when `old` becomes `new`:
+/// links in other notes that pointed at `old`, and the moved note's own
+/// relative links if it changes folder.
+pub fn plan_link_updates(index: &VaultIndex, old: &Path, new: &Path) -> Vec<LinkUpdate> {
+    let moved = |path: &Path| -> PathBuf {
+        if path == old {
+            new.to_path_buf()
+        } else {
+            path.to_path_buf()
+        }
+    };
+
+    let mut updates = Vec::new();
+    for (source, note) in &index.notes {
+        let is_moved = source.as_path() == old;
+        let links_old = note
+            .links
+            .iter()
+            .any(|link| index.resolve(link, source).as_deref() == Some(old));
+        let changes_folder = is_moved && old.parent() != new.parent();
+        if !links_old && !changes_folder {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(source) else {
+            continue;
+        };
+
+        let rebase = moved(source);
+        let rebase = rebase.parent().unwrap_or(&index.root);
+        let resolve_old = |target: &str| index.resolve(target, source);
+        let stripped = index::strip_code(&content);
+        let mut changes = Vec::new();
+        let mut content_after = String::with_capacity(content.len());
+        // Split inclusively so each line keeps its own ending, LF or CRLF
+        for (i, (raw, scannable)) in content
+            .split_inclusive('\n')
+            // Fence lines strip to nothing, so pad in case they end the note
+            .zip(stripped.lines().chain(std::iter::repeat("")))
+            .enumerate()
+        {
+            let line = raw.trim_end_matches(['\n', '\r']);
+            let ending = &raw[line.len()..];
+            // Lines in fenced code have nothing to scan
+            if index::line_links(scannable).is_empty() {
+                content_after.push_str(raw);
+                continue;
+            }
+            // Odd segments are inline code spans, left as written
+            let after = line
+                .split('`')
+                .enumerate()
+                .map(|(i, part)| {
+                    if i % 2 == 1 {
+                        part.to_string()
+                    } else {
+                        rewrite_line(part, &index.root, &resolve_old, &moved, rebase, old)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("`");
+            if after != line {
+                changes.push(LineChange {
+                    line: i + 1,
+                    before: line.to_string(),
+                    after: after.clone(),
+                });
+            }
+            content_after.push_str(&after);
+            content_after.push_str(ending);
+        }
+        if changes.is_empty() {
+            continue;
+        }
+        updates.push(LinkUpdate {
+            path: moved(source).to_string_lossy().to_string(),
+            changes,
+            content: content_after,
+        });
+    }
+    updates
+}
+
+/// Renames or moves a file and rewrites the links that pointed at it.
+/// With `dry_run` nothing is touched and the planned edits are returned
+/// for preview.
+#[tauri::command]
+pub async fn rename_with_links(
+    app: AppHandle,
+    state: State<'_, IndexState>,
+    renders: State<'_, RenderCacheState>,
+    old_path: String,
+    new_path: String,
+    vault_root: String,
+    dry_run: Option<bool>,
+) -> Result<Vec<LinkUpdate>, String> {
+    let old = PathBuf::from(&old_path);
+    let new = PathBuf::from(&new_path);
+    if new.exists() {
+        return Err(crate::i18n::tf(
+            "error.already_exists",
+            &[("path", &new.display().to_string())],
+        ));
+    }
+    let root = PathBuf::from(vault_root);
+    let updates = state.with(&root, |index| plan_link_updates(index, &old, &new));
+    if dry_run.unwrap_or(false) {
+        return Ok(updates);
+    }
+
+    if let Some(parent) = new.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::rename(&old, &new).map_err(|e| e.to_string())?;
+    app.state::<AutosaveState>().moved(&old, &new);
+    for update in &updates {
+        store::write_atomic(Path::new(&update.path), &update.content)?;
+    }
+    state.invalidate();
+    renders.clear();
+    crate::pins::rewrite_pins(&app, &old, &new)?;
+    Ok(updates)
+}
+
+const ATTACHMENT_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "webp", "bmp", "avif", "pdf",
+];
+
+#[derive(Serialize)]
+pub struct OrphanedAttachment {
+    path: String,
+    size: u64,
+}
+
+#[derive(Serialize)]
+pub struct OrphanReport {
+    files: Vec<OrphanedAttachment>,
+    /// Bytes freed by deleting every file listed
+    total_bytes: u64,
+}
+
+/// Images and PDFs in the vault that no note links to or embeds.
+#[tauri::command]
+pub async fn find_orphaned_attachments(root: String) -> Result<OrphanReport, String> {
+    let root = PathBuf::from(root);
+    if !root.is_dir() {
+        return Err(crate::i18n::t("error.not_a_directory"));
+    }
+    let index = VaultIndex::build(&root);
+    let mut referenced: HashSet<PathBuf> = index
+        .notes
+        .iter()
+        .flat_map(|(source, note)| {
+            note.links
+                .iter()
+                .filter_map(|link| index.resolve(link, source))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    // Raw HTML embeds aren't in the index's link list; missing them here
+    // would mark files in use as safe to delete
+    static HTML_SRC: OnceLock<Regex> = OnceLock::new();
+    let src_re = HTML_SRC.get_or_init(|| Regex::new(r#"\bsrc=["']([^"']+)["']"#).unwrap());
+    for source in index.notes.keys() {
+        let Ok(content) = fs::read_to_string(source) else {
+            continue;
+        };
+        for caps in src_re.captures_iter(&content) {
+            let target = urlencoding::decode(&caps[1])
+                .map(|t| t.into_owned())
+                .unwrap_or_else(|_| caps[1].to_string());
+            referenced.extend(index.resolve(&target, source));
+        }
+    }
+
+    let files: Vec<OrphanedAttachment> = index
+        .attachments
+        .iter()
+        .filter(|path| {
+            path.extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .is_some_and(|e| ATTACHMENT_EXTENSIONS.contains(&e.as_str()))
+        })
+        .filter(|path| !referenced.contains(*path))
+        .map(|path| OrphanedAttachment {
+            path: path.to_string_lossy().to_string(),
+            size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        })
+        .collect();
+
+    Ok(OrphanReport {
+        total_bytes: files.iter().map(|f| f.size).sum(),
+        files,
+    })
+}
@@ -0,0 +1,211 @@
+use crate::ignores::IgnoreRules;
+use crate::{git, i18n, store};
+use git2::Repository;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+#[derive(Serialize, Clone)]
+pub struct DirEntry {
+    name: String,
+    path: String,
+    is_dir: bool,
+    modified_at: u64,
+    is_symlink: bool,
+    /// Where a symlink points, as stored in the link
+    target: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DirectoryPage {
+    entries: Vec<DirEntry>,
+    offset: usize,
+    total: usize,
+}
+
+const MAX_CACHED_LISTINGS: usize = 16;
+const HIDDEN_FILES_FILE: &str = "hidden_files.json";
+
+/// Folder, respect_gitignore, show_hidden
+type ListingKey = (PathBuf, bool, bool);
+
+/// Sorted listings kept between `read_directory_page` calls so later pages
+/// don't re-read and re-sort the whole folder.
+#[derive(Default)]
+pub struct DirectoryListingState {
+    listings: Mutex<HashMap<ListingKey, Arc<Vec<DirEntry>>>>,
+}
+
+impl DirectoryListingState {
+    pub fn clear(&self) {
+        self.listings.lock().unwrap().clear();
+    }
+
+    /// Drops cached listings of the folders containing `paths`.
+    pub fn invalidate(&self, paths: &[PathBuf]) {
+        let mut listings = self.listings.lock().unwrap();
+        if listings.is_empty() {
+            return;
+        }
+        listings.retain(|(dir, _, _), _| {
+            !paths
+                .iter()
+                .any(|path| path == dir || path.parent() == Some(dir))
+        });
+    }
+}
+
+/// Whether dotfiles are shown for the vault containing `path`, as saved with
+/// `set_show_hidden`.
+pub fn show_hidden_for(app: &AppHandle, path: &str) -> bool {
+    let settings: HashMap<String, bool> = store::load_json(app, HIDDEN_FILES_FILE);
+    let path = Path::new(path);
+    settings
+        .iter()
+        .filter(|(root, _)| path.starts_with(root))
+        .max_by_key(|(root, _)| root.len())
+        .is_some_and(|(_, show)| *show)
+}
+
+/// Lists a folder with directories first, then files, both alphabetically.
+pub fn list_directory(
+    path: &str,
+    respect_gitignore: bool,
+    show_hidden: bool,
+) -> Result<Vec<DirEntry>, String> {
+    let dir_path = Path::new(path);
+    if !dir_path.is_dir() {
+        return Err(i18n::t("error.not_a_directory"));
+    }
+
+    // With respect_gitignore, hide whatever the enclosing repository ignores
+    let repo = respect_gitignore
+        .then(|| Repository::discover(dir_path).ok())
+        .flatten();
+    let rules = IgnoreRules::discover(dir_path);
+
+    let mut entries: Vec<DirEntry> = fs::read_dir(dir_path)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            // Skip hidden files/folders (starting with .) unless asked for;
+            // the .git folder is never useful to browse
+            if name.starts_with('.') && (!show_hidden || name == ".git") {
+                return None;
+            }
+            if repo
+                .as_ref()
+                .is_some_and(|repo| git::is_ignored(repo, &path))
+            {
+                return None;
+            }
+            let is_dir = path.is_dir();
+            if rules.is_ignored(&path, is_dir) {
+                return None;
+            }
+
+            let modified_at = entry
+                .metadata()
+                .ok()
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+
+            let is_symlink = entry.file_type().is_ok_and(|t| t.is_symlink());
+            let target = is_symlink
+                .then(|| fs::read_link(&path).ok())
+                .flatten()
+                .map(|t| t.to_string_lossy().to_string());
+
+            Some(DirEntry {
+                name,
+                path: path.to_string_lossy().to_string(),
+                is_dir,
+                modified_at,
+                is_symlink,
+                target,
+            })
+        })
+        .collect();
+
+    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    });
+
+    Ok(entries)
+}
+
+/// One page of a folder listing plus the total entry count. The sorted
+/// listing is built on the first page (`offset` 0) and reused for later
+/// pages until the folder watcher reports a change.
+#[tauri::command]
+pub fn read_directory_page(
+    app: AppHandle,
+    state: State<'_, DirectoryListingState>,
+    path: String,
+    offset: usize,
+    limit: usize,
+    respect_gitignore: Option<bool>,
+    show_hidden: Option<bool>,
+) -> Result<DirectoryPage, String> {
+    let respect_gitignore = respect_gitignore.unwrap_or(false);
+    let show_hidden = show_hidden.unwrap_or_else(|| show_hidden_for(&app, &path));
+    let key = (PathBuf::from(&path), respect_gitignore, show_hidden);
+    let cached = if offset == 0 {
+        None
+    } else {
+        state.listings.lock().unwrap().get(&key).cloned()
+    };
+    let listing = match cached {
+        Some(listing) => listing,
+        None => {
+            let listing = Arc::new(list_directory(&path, respect_gitignore, show_hidden)?);
+            let mut listings = state.listings.lock().unwrap();
+            // Only the folders currently being paged through need to stay cached
+            if listings.len() >= MAX_CACHED_LISTINGS {
+                listings.clear();
+            }
+            listings.insert(key, listing.clone());
+            listing
+        }
+    };
+
+    Ok(DirectoryPage {
+        entries: listing.iter().skip(offset).take(limit).cloned().collect(),
+        offset,
+        total: listing.len(),
+    })
+}
+
+#[tauri::command]
+pub fn get_show_hidden(app: AppHandle, vault_root: String) -> bool {
+    show_hidden_for(&app, &vault_root)
+}
+
+/// Saves whether dotfiles and dotfolders are listed in `vault_root`.
+#[tauri::command]
+pub fn set_show_hidden(
+    app: AppHandle,
+    state: State<'_, DirectoryListingState>,
+    vault_root: String,
+    show: bool,
+) -> Result<(), String> {
+    let mut settings: HashMap<String, bool> = store::load_json(&app, HIDDEN_FILES_FILE);
+    if show {
+        settings.insert(vault_root, true);
+    } else {
+        settings.remove(&vault_root);
+    }
+    store::save_json(&app, HIDDEN_FILES_FILE, &settings)?;
+    state.clear();
+    Ok(())
+}
@@ -0,0 +1,168 @@
+// Math is swapped out for private-use placeholders before comrak runs so
+// emphasis and escaping rules can't mangle the TeX, then restored afterwards
+const OPEN: char = '\u{E000}';
+const CLOSE: char = '\u{E001}';
+/// Starts each extra line of a multi-line placeholder so none of them is blank
+const FILLER: char = '\u{E002}';
+
+pub struct Math {
+    tex: String,
+    display: bool,
+}
+
+fn placeholder(out: &mut String, math: &mut Vec<Math>, tex: &str, display: bool) {
+    out.push(OPEN);
+    out.push_str(&math.len().to_string());
+    // Keep the line count of multi-line display math, so the lines after it
+    // keep their sourcepos
+    for _ in tex.matches('\n') {
+        out.push('\n');
+        out.push(FILLER);
+    }
+    out.push(CLOSE);
+    math.push(Math {
+        tex: tex.trim().to_string(),
+        display,
+    });
+}
+
+/// Finds the closing `$` of inline math starting at `start` (just after the
+/// opening `$`): no whitespace just inside either delimiter and no digit right
+/// after the closing one. A `$` after whitespace opens something else, so
+/// "$5 and $10" stays text.
+fn inline_end(chars: &[char], start: usize) -> Option<usize> {
+    if !matches!(chars.get(start), Some(c) if !c.is_whitespace() && *c != '$') {
+        return None;
+    }
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '$' if chars[i - 1].is_whitespace() => return None,
+            '$' if !chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Replaces `$...$` and `$$...$$` outside of code with placeholders.
+pub fn protect_math(content: &str) -> (String, Vec<Math>) {
+    let mut out = String::with_capacity(content.len());
+    let mut math = Vec::new();
+    let mut in_fence = false;
+    let mut display: Option<String> = None;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if display.is_none() && (trimmed.starts_with("```") || trimmed.starts_with("~~~")) {
+            in_fence = !in_fence;
+        }
+        if in_fence {
+            out.push_str(line);
+            continue;
+        }
+
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            if let Some(tex) = display.as_mut() {
+                if chars[i] == '$' && chars.get(i + 1) == Some(&'$') {
+                    let tex = display.take().unwrap();
+                    placeholder(&mut out, &mut math, &tex, true);
+                    i += 2;
+                } else {
+                    tex.push(chars[i]);
+                    i += 1;
+                }
+                continue;
+            }
+
+            match chars[i] {
+                '\\' if chars.get(i + 1) == Some(&'$') => {
+                    out.push_str("\\$");
+                    i += 2;
+                }
+                '`' => {
+                    // Copy inline code spans verbatim
+                    let run = chars[i..].iter().take_while(|c| **c == '`').count();
+                    let fence: String = "`".repeat(run);
+                    let rest: String = chars[i + run..].iter().collect();
+                    let end = rest.find(&fence).map(|e| rest[..e].chars().count() + run);
+                    let len = run + end.unwrap_or(0);
+                    out.extend(&chars[i..i + len]);
+                    i += len;
+                }
+                '$' if chars.get(i + 1) == Some(&'$') => {
+                    display = Some(String::new());
+                    i += 2;
+                }
+                '$' => match inline_end(&chars, i + 1) {
+                    Some(end) => {
+                        let tex: String = chars[i + 1..end].iter().collect();
+                        placeholder(&mut out, &mut math, &tex, false);
+                        i = end + 1;
+                    }
+                    None => {
+                        out.push('$');
+                        i += 1;
+                    }
+                },
+                c => {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    // Unclosed display math is left as typed
+    if let Some(tex) = display {
+        out.push_str("$$");
+        out.push_str(&tex);
+    }
+    (out, math)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Puts the protected math back as KaTeX auto-render friendly markup.
+pub fn restore_math(html: &str, math: &[Math]) -> String {
+    if math.is_empty() {
+        return html.to_string();
+    }
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find(OPEN) {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + OPEN.len_utf8()..];
+        let Some(end) = after.find(CLOSE) else {
+            rest = &rest[start..];
+            break;
+        };
+        // Anything after the number is line padding, e.g. soft breaks
+        let number = after[..end].split(|c: char| !c.is_ascii_digit()).next();
+        match number
+            .and_then(|n| n.parse::<usize>().ok())
+            .and_then(|n| math.get(n))
+        {
+            Some(m) if m.display => out.push_str(&format!(
+                "<span class=\"math math-display\">\\[{}\\]</span>",
+                escape_html(&m.tex)
+            )),
+            Some(m) => out.push_str(&format!(
+                "<span class=\"math math-inline\">\\({}\\)</span>",
+                escape_html(&m.tex)
+            )),
+            None => out.push_str(&rest[start..start + OPEN.len_utf8() + end + CLOSE.len_utf8()]),
+        }
+        rest = &after[end + CLOSE.len_utf8()..];
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,129 @@
+use crate::{blocks, headings, vault};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Serialize)]
+pub struct OutlineHeading {
+    level: u8,
+    text: String,
+    /// The `id` the heading gets in the rendered note
+    anchor: String,
+    /// 1-based source line
+    line: usize,
+    children: Vec<OutlineHeading>,
+}
+
+#[derive(Serialize)]
+pub struct FileOutline {
+    path: String,
+    /// Path relative to the folder, with `/` separators
+    relative_path: String,
+    headings: Vec<OutlineHeading>,
+}
+
+/// ATX headings outside frontmatter and fenced code, in document order.
+/// Anchors are deduplicated the same way `add_heading_ids` does.
+fn flat_headings(content: &str) -> Vec<OutlineHeading> {
+    let lines: Vec<&str> = content.lines().collect();
+    let start = blocks::frontmatter_len(&lines);
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut fence: Option<&str> = None;
+    let mut out = Vec::new();
+
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        let trimmed = line.trim_start();
+        let marker = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m));
+        match (fence, marker) {
+            (None, Some(m)) => {
+                fence = Some(m);
+                continue;
+            }
+            (Some(open), Some(m)) if open == m => {
+                fence = None;
+                continue;
+            }
+            (Some(_), _) => continue,
+            _ => {}
+        }
+        let Some(level) = blocks::heading_level(line) else {
+            continue;
+        };
+        let text = line[level as usize..]
+            .trim()
+            .trim_end_matches('#')
+            .trim()
+            .to_string();
+        let base = headings::heading_slug(&text);
+        let count = seen.entry(base.clone()).or_insert(0);
+        let anchor = if *count == 0 {
+            base
+        } else {
+            format!("{}-{}", base, count)
+        };
+        *count += 1;
+        out.push(OutlineHeading {
+            level,
+            text,
+            anchor,
+            line: i + 1,
+            children: Vec::new(),
+        });
+    }
+    out
+}
+
+/// Nests each heading under the closest preceding heading of a lower level.
+fn build_tree(flat: Vec<OutlineHeading>) -> Vec<OutlineHeading> {
+    let mut roots: Vec<OutlineHeading> = Vec::new();
+    let mut stack: Vec<OutlineHeading> = Vec::new();
+    for heading in flat {
+        while stack.last().is_some_and(|top| top.level >= heading.level) {
+            let done = stack.pop().unwrap();
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(done),
+                None => roots.push(done),
+            }
+        }
+        stack.push(heading);
+    }
+    while let Some(done) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(done),
+            None => roots.push(done),
+        }
+    }
+    roots
+}
+
+pub fn outline(content: &str) -> Vec<OutlineHeading> {
+    build_tree(flat_headings(content))
+}
+
+/// Heading trees for every note below `folder`, in one call.
+#[tauri::command]
+pub async fn get_folder_outline(folder: String) -> Result<Vec<FileOutline>, String> {
+    let root = Path::new(&folder);
+    if !root.is_dir() {
+        return Err(crate::i18n::t("error.not_a_directory"));
+    }
+    Ok(vault::markdown_files(root)
+        .into_iter()
+        .filter_map(|path| {
+            let content = fs::read_to_string(&path).ok()?;
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            Some(FileOutline {
+                path: path.to_string_lossy().to_string(),
+                relative_path: relative,
+                headings: outline(&content),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,120 @@
+use crate::blocks::{self, Block, BlockKind};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct PageMetrics {
+    viewport_height: f64,
+    viewport_width: f64,
+    line_height: f64,
+    /// Average glyph width of the body font
+    char_width: f64,
+    /// Vertical gap between blocks (defaults to one line)
+    block_spacing: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct ReadingPage {
+    index: usize,
+    start_line: usize,
+    end_line: usize,
+    /// Matches the `data-sourcepos` prefix of the first block on the page
+    anchor: String,
+    /// Reading progress (0-100) once the end of this page is reached
+    progress: f64,
+}
+
+fn heading_scale(level: u8) -> f64 {
+    match level {
+        1 => 2.0,
+        2 => 1.5,
+        3 => 1.25,
+        4 => 1.1,
+        _ => 1.0,
+    }
+}
+
+fn estimate_height(block: &Block, lines: &[&str], metrics: &PageMetrics) -> f64 {
+    let chars_per_line = (metrics.viewport_width / metrics.char_width)
+        .floor()
+        .max(1.0);
+    let source = &lines[block.start_line - 1..block.end_line];
+
+    let visual_lines: f64 = source
+        .iter()
+        .map(|line| match block.kind {
+            // Code and tables scroll horizontally instead of wrapping
+            BlockKind::Code | BlockKind::Table => 1.0,
+            _ => (line.chars().count() as f64 / chars_per_line)
+                .ceil()
+                .max(1.0),
+        })
+        .sum();
+
+    let scale = match block.kind {
+        BlockKind::Heading(level) => heading_scale(level),
+        _ => 1.0,
+    };
+
+    // Images have no intrinsic size here, assume they take half a screen
+    let images = source.iter().filter(|l| l.contains("![")).count() as f64;
+
+    visual_lines * metrics.line_height * scale
+        + images * metrics.viewport_height * 0.5
+        + metrics.block_spacing.unwrap_or(metrics.line_height)
+}
+
+/// Greedily packs top-level blocks into viewport-sized pages. Blocks taller
+/// than the viewport get a page of their own.
+pub fn paginate(content: &str, metrics: &PageMetrics) -> Vec<ReadingPage> {
+    let lines: Vec<&str> = content.lines().collect();
+    let sized: Vec<(Block, f64)> = blocks::split_blocks(content)
+        .into_iter()
+        .map(|b| {
+            let height = estimate_height(&b, &lines, metrics);
+            (b, height)
+        })
+        .collect();
+    let total: f64 = sized.iter().map(|(_, h)| h).sum();
+
+    let mut pages: Vec<ReadingPage> = Vec::new();
+    let mut page_height = 0.0;
+    let mut consumed = 0.0;
+
+    for (block, height) in sized {
+        let starts_page = pages.is_empty() || page_height + height > metrics.viewport_height;
+        if starts_page {
+            pages.push(ReadingPage {
+                index: pages.len(),
+                start_line: block.start_line,
+                end_line: block.end_line,
+                anchor: format!("{}:", block.start_line),
+                progress: 0.0,
+            });
+            page_height = 0.0;
+        }
+
+        page_height += height;
+        consumed += height;
+        if let Some(page) = pages.last_mut() {
+            page.end_line = block.end_line;
+            page.progress = if total > 0.0 {
+                (consumed / total * 100.0).min(100.0)
+            } else {
+                100.0
+            };
+        }
+    }
+
+    pages
+}
+
+#[tauri::command]
+pub fn paginate_markdown(
+    content: String,
+    metrics: PageMetrics,
+) -> Result<Vec<ReadingPage>, String> {
+    if metrics.viewport_height <= 0.0 || metrics.line_height <= 0.0 || metrics.char_width <= 0.0 {
+        return Err(crate::i18n::t("error.invalid_page_metrics"));
+    }
+    Ok(paginate(&content, &metrics))
+}
@@ -0,0 +1,106 @@
+use crate::{blocks, cache};
+use pdfium_render::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Cursor;
+use tauri::{AppHandle, Manager};
+
+const DEFAULT_WIDTH: u16 = 800;
+
+#[derive(Serialize)]
+pub struct PdfPagePreview {
+    /// PNG in the artifact cache, load it through the asset protocol
+    image_path: String,
+    /// 1-based, clamped to the document
+    page: u16,
+    page_count: u16,
+}
+
+/// Page number and count of a cached render, so a hit can answer without
+/// opening the document.
+#[derive(Serialize, Deserialize)]
+struct PageMeta {
+    page: u16,
+    page_count: u16,
+}
+
+// Prefer a pdfium shipped alongside the app, then fall back to a system install
+fn load_pdfium(app: &AppHandle) -> Result<Pdfium, String> {
+    let bundled = app
+        .path()
+        .resource_dir()
+        .map_err(|e| e.to_string())
+        .and_then(|dir| {
+            Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path(&dir))
+                .map_err(|e| e.to_string())
+        });
+    let bindings = bundled
+        .or_else(|_| Pdfium::bind_to_system_library())
+        .map_err(|e| crate::i18n::tf("error.pdf_unavailable", &[("error", &e.to_string())]))?;
+    Ok(Pdfium::new(bindings))
+}
+
+/// Renders a page of a PDF attachment to an image for `![[file.pdf#page=N]]`
+/// embeds. Rendered pages are cached by file, modification time, page and width.
+#[tauri::command]
+pub async fn render_pdf_page(
+    app: AppHandle,
+    path: String,
+    page: Option<u16>,
+    width: Option<u16>,
+) -> Result<PdfPagePreview, String> {
+    let modified = fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .map_err(|e| e.to_string())?;
+    let width = width.unwrap_or(DEFAULT_WIDTH).clamp(64, 4096);
+
+    // Keyed by the requested page, so a hit needs neither pdfium nor the document
+    let requested = page.unwrap_or(1).max(1);
+    let hash = blocks::fnv1a(format!("{}|{:?}|{}|{}", path, modified, requested, width).as_bytes());
+    let key = format!("{:016x}.png", hash);
+    let meta_key = format!("{:016x}.json", hash);
+    if let (Some(cached), Some(meta)) = (
+        cache::get(&app, "pdf", &key),
+        cache::get(&app, "pdf", &meta_key),
+    ) {
+        if let Some(PageMeta { page, page_count }) = fs::read_to_string(meta)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+        {
+            return Ok(PdfPagePreview {
+                image_path: cached.to_string_lossy().to_string(),
+                page,
+                page_count,
+            });
+        }
+    }
+
+    let pdfium = load_pdfium(&app)?;
+    let document = pdfium
+        .load_pdf_from_file(&path, None)
+        .map_err(|e| e.to_string())?;
+    let page_count = document.pages().len();
+    if page_count == 0 {
+        return Err(crate::i18n::t("error.pdf_no_pages"));
+    }
+    let page = requested.min(page_count);
+
+    let pdf_page = document.pages().get(page - 1).map_err(|e| e.to_string())?;
+    let image = pdf_page
+        .render_with_config(&PdfRenderConfig::new().set_target_width(width as Pixels))
+        .map_err(|e| e.to_string())?
+        .as_image();
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+
+    let image_path = cache::put(&app, "pdf", &key, &png)?;
+    let meta = serde_json::to_vec(&PageMeta { page, page_count }).map_err(|e| e.to_string())?;
+    cache::put(&app, "pdf", &meta_key, &meta)?;
+    Ok(PdfPagePreview {
+        image_path: image_path.to_string_lossy().to_string(),
+        page,
+        page_count,
+    })
+}
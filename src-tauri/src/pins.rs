@@ -0,0 +1,116 @@
+use crate::store;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::AppHandle;
+
+const PINS_FILE: &str = "pins.json";
+
+/// Pinned paths keyed by vault root, in sidebar order.
+type PinStore = HashMap<String, Vec<String>>;
+
+#[derive(Serialize)]
+pub struct Pin {
+    path: String,
+    name: String,
+    is_dir: bool,
+    /// False once the file is gone, so the sidebar can offer to unpin it
+    exists: bool,
+}
+
+fn to_pins(paths: &[String]) -> Vec<Pin> {
+    paths
+        .iter()
+        .map(|path| {
+            let p = Path::new(path);
+            Pin {
+                path: path.clone(),
+                name: p
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.clone()),
+                is_dir: p.is_dir(),
+                exists: p.exists(),
+            }
+        })
+        .collect()
+}
+
+fn update(
+    app: &AppHandle,
+    vault_root: &str,
+    f: impl FnOnce(&mut Vec<String>),
+) -> Result<Vec<Pin>, String> {
+    let mut pins: PinStore = store::load_json(app, PINS_FILE);
+    let list = pins.entry(vault_root.to_string()).or_default();
+    f(list);
+    let result = to_pins(list);
+    if list.is_empty() {
+        pins.remove(vault_root);
+    }
+    store::save_json(app, PINS_FILE, &pins)?;
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn list_pins(app: AppHandle, vault_root: String) -> Vec<Pin> {
+    let pins: PinStore = store::load_json(&app, PINS_FILE);
+    pins.get(&vault_root)
+        .map(|p| to_pins(p))
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn pin_path(app: AppHandle, vault_root: String, path: String) -> Result<Vec<Pin>, String> {
+    update(&app, &vault_root, |list| {
+        if !list.contains(&path) {
+            list.push(path);
+        }
+    })
+}
+
+#[tauri::command]
+pub fn unpin_path(app: AppHandle, vault_root: String, path: String) -> Result<Vec<Pin>, String> {
+    update(&app, &vault_root, |list| list.retain(|p| *p != path))
+}
+
+/// Applies a new order from the sidebar. Pins missing from `paths` keep
+/// their relative order at the end; unknown paths are ignored.
+#[tauri::command]
+pub fn reorder_pins(
+    app: AppHandle,
+    vault_root: String,
+    paths: Vec<String>,
+) -> Result<Vec<Pin>, String> {
+    update(&app, &vault_root, |list| {
+        let mut ordered: Vec<String> = Vec::with_capacity(list.len());
+        for path in paths.into_iter().chain(list.iter().cloned()) {
+            if list.contains(&path) && !ordered.contains(&path) {
+                ordered.push(path);
+            }
+        }
+        *list = ordered;
+    })
+}
+
+/// Points pins at `old` (or inside it, for folders) at their new location
+/// after a rename or move, in every vault.
+pub fn rewrite_pins(app: &AppHandle, old: &Path, new: &Path) -> Result<(), String> {
+    let mut pins: PinStore = store::load_json(app, PINS_FILE);
+    let mut changed = false;
+    for path in pins.values_mut().flatten() {
+        if let Ok(rest) = Path::new(path.as_str()).strip_prefix(old) {
+            let moved = if rest.as_os_str().is_empty() {
+                new.to_path_buf()
+            } else {
+                new.join(rest)
+            };
+            *path = moved.to_string_lossy().to_string();
+            changed = true;
+        }
+    }
+    if changed {
+        store::save_json(app, PINS_FILE, &pins)?;
+    }
+    Ok(())
+}
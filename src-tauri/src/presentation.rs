@@ -0,0 +1,196 @@
+use crate::{blocks, RenderOptions};
+use serde::Serialize;
+use std::fs;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const AUDIENCE_WINDOW: &str = "presentation";
+
+#[derive(Serialize, Clone)]
+pub struct Slide {
+    pub content: String,
+    /// Speaker notes: everything after a `Note:` or `???` line in the slide
+    pub notes: String,
+}
+
+struct Presentation {
+    path: String,
+    slides: Vec<Slide>,
+    index: usize,
+    /// How slides are rendered, as for the note's preview
+    render: RenderOptions,
+}
+
+#[derive(Default)]
+pub struct PresentationState {
+    current: Mutex<Option<Presentation>>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SlideEvent {
+    path: String,
+    index: usize,
+    total: usize,
+    html: String,
+    notes: String,
+    /// Rendered upcoming slide, for the speaker view
+    next_html: Option<String>,
+}
+
+/// Splits a note into slides on horizontal rules (`---`, `***`, `___`),
+/// ignoring frontmatter and rules inside fenced code.
+pub fn split_slides(content: &str) -> Vec<Slide> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut chunks: Vec<Vec<&str>> = vec![Vec::new()];
+    let mut in_fence = false;
+
+    for line in &lines[blocks::frontmatter_len(&lines)..] {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && matches!(trimmed, "---" | "***" | "___") {
+            chunks.push(Vec::new());
+            continue;
+        }
+        chunks.last_mut().unwrap().push(line);
+    }
+
+    chunks
+        .into_iter()
+        .map(|chunk| {
+            let split = chunk
+                .iter()
+                .position(|l| matches!(l.trim(), "Note:" | "Notes:" | "???"));
+            let (body, notes) = match split {
+                Some(i) => (&chunk[..i], &chunk[i + 1..]),
+                None => (&chunk[..], &[][..]),
+            };
+            Slide {
+                content: body.join("\n").trim().to_string(),
+                notes: notes.join("\n").trim().to_string(),
+            }
+        })
+        .filter(|s| !s.content.is_empty() || !s.notes.is_empty())
+        .collect()
+}
+
+fn slide_event(app: &AppHandle, presentation: &Presentation) -> SlideEvent {
+    let slide = &presentation.slides[presentation.index];
+    let mut render = presentation.render.clone();
+    SlideEvent {
+        path: presentation.path.clone(),
+        index: presentation.index,
+        total: presentation.slides.len(),
+        html: crate::render_in_vault(app, &slide.content, &mut render),
+        notes: slide.notes.clone(),
+        next_html: presentation
+            .slides
+            .get(presentation.index + 1)
+            .map(|s| crate::render_in_vault(app, &s.content, &mut render)),
+    }
+}
+
+fn show_slide(
+    app: &AppHandle,
+    state: &PresentationState,
+    step: impl FnOnce(usize) -> usize,
+) -> Result<SlideEvent, String> {
+    let mut current = state.current.lock().unwrap();
+    let presentation = current
+        .as_mut()
+        .ok_or_else(|| crate::i18n::t("error.no_presentation"))?;
+    let last = presentation.slides.len() - 1;
+    presentation.index = step(presentation.index).min(last);
+
+    let event = slide_event(app, presentation);
+    let _ = app.emit("presentation-slide", event.clone());
+    Ok(event)
+}
+
+/// Presents the note at `path`, rendering slides with the preview's `options`
+/// so settings, wikilinks and embeds apply as they do there.
+#[tauri::command]
+pub async fn start_presentation(
+    app: AppHandle,
+    state: State<'_, PresentationState>,
+    path: String,
+    options: Option<RenderOptions>,
+) -> Result<SlideEvent, String> {
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let slides = split_slides(&content);
+    if slides.is_empty() {
+        return Err(crate::i18n::t("error.no_slides"));
+    }
+
+    let mut render = options.unwrap_or_default();
+    render.path.get_or_insert_with(|| path.clone());
+    *state.current.lock().unwrap() = Some(Presentation {
+        path,
+        slides,
+        index: 0,
+        render,
+    });
+
+    // The audience sees slides in a frameless window; the main window keeps
+    // the speaker view with notes
+    if app.get_webview_window(AUDIENCE_WINDOW).is_none() {
+        tauri::WebviewWindowBuilder::new(
+            &app,
+            AUDIENCE_WINDOW,
+            tauri::WebviewUrl::App("index.html".into()),
+        )
+        .title(crate::i18n::t("presentation.window_title"))
+        .inner_size(1280.0, 720.0)
+        .decorations(false)
+        .build()
+        .map_err(|e| e.to_string())?;
+    }
+
+    show_slide(&app, &state, |_| 0)
+}
+
+#[tauri::command]
+pub fn next_slide(
+    app: AppHandle,
+    state: State<'_, PresentationState>,
+) -> Result<SlideEvent, String> {
+    show_slide(&app, &state, |i| i + 1)
+}
+
+#[tauri::command]
+pub fn previous_slide(
+    app: AppHandle,
+    state: State<'_, PresentationState>,
+) -> Result<SlideEvent, String> {
+    show_slide(&app, &state, |i| i.saturating_sub(1))
+}
+
+#[tauri::command]
+pub fn goto_slide(
+    app: AppHandle,
+    state: State<'_, PresentationState>,
+    index: usize,
+) -> Result<SlideEvent, String> {
+    show_slide(&app, &state, |_| index)
+}
+
+#[tauri::command]
+pub fn get_presentation_state(
+    app: AppHandle,
+    state: State<'_, PresentationState>,
+) -> Option<SlideEvent> {
+    let current = state.current.lock().unwrap();
+    current
+        .as_ref()
+        .map(|presentation| slide_event(&app, presentation))
+}
+
+#[tauri::command]
+pub fn stop_presentation(app: AppHandle, state: State<'_, PresentationState>) {
+    *state.current.lock().unwrap() = None;
+    if let Some(window) = app.get_webview_window(AUDIENCE_WINDOW) {
+        let _ = window.close();
+    }
+    let _ = app.emit("presentation-ended", ());
+}
@@ -0,0 +1,257 @@
+use crate::frontmatter;
+use crate::index::IndexState;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::State;
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum Op {
+    #[serde(rename = "==")]
+    Eq,
+    #[serde(rename = "!=")]
+    Ne,
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = "<=")]
+    Le,
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = ">=")]
+    Ge,
+    #[serde(rename = "contains")]
+    Contains,
+    #[serde(rename = "exists")]
+    Exists,
+    #[serde(rename = "missing")]
+    Missing,
+}
+
+#[derive(Deserialize)]
+pub struct Condition {
+    field: String,
+    op: Op,
+    #[serde(default)]
+    value: Value,
+}
+
+#[derive(Deserialize)]
+pub struct SortKey {
+    field: String,
+    #[serde(default)]
+    descending: bool,
+}
+
+/// A dataview-style table query over note frontmatter. Besides frontmatter
+/// keys (dotted for nested maps), `file.name`, `file.path`, `file.folder`,
+/// `file.modified` and `file.created` (unix seconds) are available.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct NoteQuery {
+    /// Only notes under this folder
+    from: Option<String>,
+    /// All must match
+    filters: Vec<Condition>,
+    sort: Vec<SortKey>,
+    /// Columns to return; defaults to every frontmatter key seen
+    fields: Vec<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct QueryRow {
+    path: String,
+    /// One value per column, null when the note lacks the field
+    values: Vec<Value>,
+}
+
+#[derive(Serialize)]
+pub struct QueryResult {
+    columns: Vec<String>,
+    rows: Vec<QueryRow>,
+}
+
+struct Note {
+    path: PathBuf,
+    fields: Map<String, Value>,
+}
+
+fn seconds(time: std::io::Result<std::time::SystemTime>) -> Value {
+    time.ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| Value::from(d.as_secs()))
+        .unwrap_or(Value::Null)
+}
+
+impl Note {
+    fn get(&self, field: &str) -> Value {
+        let path = &self.path;
+        match field {
+            "file.name" => {
+                return Value::from(path.file_stem().unwrap_or_default().to_string_lossy());
+            }
+            "file.path" => return Value::from(path.to_string_lossy()),
+            "file.folder" => {
+                return Value::from(path.parent().unwrap_or(path).to_string_lossy());
+            }
+            "file.modified" => return seconds(fs::metadata(path).and_then(|m| m.modified())),
+            "file.created" => return seconds(fs::metadata(path).and_then(|m| m.created())),
+            _ => {}
+        }
+        let mut current = &self.fields;
+        let mut parts = field.split('.').peekable();
+        while let Some(part) = parts.next() {
+            // Keys match case-insensitively, as Obsidian's do
+            let Some(value) = current.get(part).or_else(|| {
+                current
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(part))
+                    .map(|(_, v)| v)
+            }) else {
+                return Value::Null;
+            };
+            match value {
+                Value::Object(map) if parts.peek().is_some() => current = map,
+                _ if parts.peek().is_some() => return Value::Null,
+                _ => return value.clone(),
+            }
+        }
+        Value::Null
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Orders numbers numerically and everything else as text, which also
+/// sorts ISO dates correctly. Nulls sort last.
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        (Value::Null, _) => Some(Ordering::Greater),
+        (_, Value::Null) => Some(Ordering::Less),
+        _ => match (as_number(a), as_number(b)) {
+            (Some(x), Some(y)) => x.partial_cmp(&y),
+            _ => Some(text(a).to_lowercase().cmp(&text(b).to_lowercase())),
+        },
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn matches(actual: &Value, op: Op, expected: &Value) -> bool {
+    // A list field matches when any of its items does
+    if let Value::Array(items) = actual {
+        return match op {
+            Op::Exists => !items.is_empty(),
+            Op::Missing => items.is_empty(),
+            Op::Ne => !items.iter().any(|item| matches(item, Op::Eq, expected)),
+            _ => items.iter().any(|item| matches(item, op, expected)),
+        };
+    }
+    let ordering = || compare(actual, expected);
+    match op {
+        Op::Exists => !actual.is_null(),
+        Op::Missing => actual.is_null(),
+        _ if actual.is_null() => matches!(op, Op::Ne),
+        Op::Eq => ordering() == Some(Ordering::Equal),
+        Op::Ne => ordering() != Some(Ordering::Equal),
+        Op::Lt => ordering() == Some(Ordering::Less),
+        Op::Le => matches!(ordering(), Some(Ordering::Less | Ordering::Equal)),
+        Op::Gt => ordering() == Some(Ordering::Greater),
+        Op::Ge => matches!(ordering(), Some(Ordering::Greater | Ordering::Equal)),
+        Op::Contains => text(actual)
+            .to_lowercase()
+            .contains(&text(expected).to_lowercase()),
+    }
+}
+
+pub fn run_query(paths: Vec<PathBuf>, query: &NoteQuery) -> QueryResult {
+    let mut notes: Vec<Note> = paths
+        .into_iter()
+        .filter(|path| match query.from.as_deref() {
+            Some(from) => path.starts_with(from),
+            None => true,
+        })
+        .filter_map(|path| {
+            let content = fs::read_to_string(&path).ok()?;
+            let fields = frontmatter::parse(&content)
+                .ok()
+                .flatten()
+                .map(|fm| fm.fields)
+                .unwrap_or_default();
+            Some(Note { path, fields })
+        })
+        .filter(|note| {
+            query
+                .filters
+                .iter()
+                .all(|c| matches(&note.get(&c.field), c.op, &c.value))
+        })
+        .collect();
+
+    notes.sort_by(|a, b| {
+        query
+            .sort
+            .iter()
+            .map(|key| {
+                let ordering =
+                    compare(&a.get(&key.field), &b.get(&key.field)).unwrap_or(Ordering::Equal);
+                if key.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| a.path.cmp(&b.path))
+    });
+    if let Some(limit) = query.limit {
+        notes.truncate(limit);
+    }
+
+    let columns = if query.fields.is_empty() {
+        let mut columns: Vec<String> = Vec::new();
+        for key in notes.iter().flat_map(|note| note.fields.keys()) {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+        columns
+    } else {
+        query.fields.clone()
+    };
+    let rows = notes
+        .iter()
+        .map(|note| QueryRow {
+            path: note.path.to_string_lossy().to_string(),
+            values: columns.iter().map(|c| note.get(c)).collect(),
+        })
+        .collect();
+    QueryResult { columns, rows }
+}
+
+/// Filters and sorts the vault's notes by frontmatter, returning a table.
+#[tauri::command]
+pub fn query_notes(
+    state: State<'_, IndexState>,
+    vault_root: String,
+    query: NoteQuery,
+) -> QueryResult {
+    let root = Path::new(&vault_root);
+    let paths = state.with(root, |index| index.notes.keys().cloned().collect());
+    run_query(paths, &query)
+}
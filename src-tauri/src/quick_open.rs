@@ -0,0 +1,119 @@
+use crate::index::IndexState;
+use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::State;
+
+const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Serialize)]
+pub struct QuickOpenResult {
+    path: String,
+    /// Path relative to the vault, with `/` separators; what was matched
+    relative_path: String,
+    score: u32,
+    /// Set when the query matched one of the note's frontmatter aliases
+    /// better than its path
+    alias: Option<String>,
+    /// Character positions in `relative_path` (or `alias`, when set) that
+    /// matched, for highlighting
+    indices: Vec<u32>,
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Ranks the vault's files against `query` with a path-aware fuzzy matcher;
+/// notes also match by their frontmatter aliases. Uses the cached vault
+/// index, so no disk walk happens per keystroke. An empty query lists the
+/// most recently modified notes.
+#[tauri::command]
+pub fn quick_open(
+    state: State<'_, IndexState>,
+    root: String,
+    query: String,
+    limit: Option<usize>,
+    include_attachments: Option<bool>,
+) -> Vec<QuickOpenResult> {
+    let root = PathBuf::from(root);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+    state.with(&root, |index| {
+        if query.trim().is_empty() {
+            let mut recent: Vec<(&PathBuf, Option<SystemTime>)> = index
+                .notes
+                .iter()
+                .map(|(path, note)| (path, note.modified))
+                .collect();
+            recent.sort_by_key(|(_, modified)| Reverse(*modified));
+            return recent
+                .into_iter()
+                .take(limit)
+                .map(|(path, _)| QuickOpenResult {
+                    path: path.to_string_lossy().to_string(),
+                    relative_path: relative(&root, path),
+                    score: 0,
+                    alias: None,
+                    indices: Vec::new(),
+                })
+                .collect();
+        }
+
+        let mut matcher = Matcher::new(Config::DEFAULT.match_paths());
+        let pattern = Pattern::parse(&query, CaseMatching::Smart, Normalization::Smart);
+        let attachments = include_attachments
+            .unwrap_or(false)
+            .then_some(&index.attachments)
+            .into_iter()
+            .flatten();
+        let mut buf = Vec::new();
+        let mut results: Vec<QuickOpenResult> = index
+            .notes
+            .keys()
+            .chain(attachments)
+            .filter_map(|path| {
+                let relative_path = relative(&root, path);
+                let aliases = index.notes.get(path).map(|n| n.aliases.as_slice());
+                let (score, alias, mut indices) = std::iter::once(None)
+                    .chain(aliases.unwrap_or_default().iter().map(Some))
+                    .filter_map(|alias| {
+                        let text = alias.map_or(relative_path.as_str(), |a| a.as_str());
+                        let mut indices = Vec::new();
+                        let score = pattern.indices(
+                            Utf32Str::new(text, &mut buf),
+                            &mut matcher,
+                            &mut indices,
+                        )?;
+                        Some((score, alias.cloned(), indices))
+                    })
+                    .max_by_key(|(score, alias, _)| (*score, alias.is_none()))?;
+                indices.sort_unstable();
+                indices.dedup();
+                Some(QuickOpenResult {
+                    path: path.to_string_lossy().to_string(),
+                    relative_path,
+                    score,
+                    alias,
+                    indices,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.relative_path.len().cmp(&b.relative_path.len()))
+        });
+        results.truncate(limit);
+        results
+    })
+}
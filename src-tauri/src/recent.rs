@@ -0,0 +1,156 @@
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+
+const RECENT_FILE: &str = "recent.json";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecentKind {
+    File,
+    Folder,
+    ClosedTab,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct RecentLists {
+    files: Vec<String>,
+    folders: Vec<String>,
+    closed_tabs: Vec<String>,
+    /// Entries kept per list
+    limit: usize,
+}
+
+impl Default for RecentLists {
+    fn default() -> Self {
+        RecentLists {
+            files: Vec::new(),
+            folders: Vec::new(),
+            closed_tabs: Vec::new(),
+            limit: 20,
+        }
+    }
+}
+
+impl RecentLists {
+    fn list(&mut self, kind: RecentKind) -> &mut Vec<String> {
+        match kind {
+            RecentKind::File => &mut self.files,
+            RecentKind::Folder => &mut self.folders,
+            RecentKind::ClosedTab => &mut self.closed_tabs,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct RecentChanged {
+    kind: RecentKind,
+    paths: Vec<String>,
+}
+
+/// Recent files, folders and closed tabs, newest first. Loaded once and
+/// written through on every change so all windows see the same lists.
+#[derive(Default)]
+pub struct RecentState {
+    lists: Mutex<Option<RecentLists>>,
+}
+
+impl RecentState {
+    fn update(
+        &self,
+        app: &AppHandle,
+        kind: RecentKind,
+        f: impl FnOnce(&mut Vec<String>, usize),
+    ) -> Result<Vec<String>, String> {
+        let mut lists = self.lists.lock().unwrap();
+        let lists = lists.get_or_insert_with(|| store::load_json(app, RECENT_FILE));
+        let limit = lists.limit;
+        let list = lists.list(kind);
+        f(list, limit);
+        let paths = list.clone();
+        store::save_json(app, RECENT_FILE, &*lists)?;
+        let _ = app.emit(
+            "recent-changed",
+            RecentChanged {
+                kind,
+                paths: paths.clone(),
+            },
+        );
+        Ok(paths)
+    }
+}
+
+#[tauri::command]
+pub fn list_recent(app: AppHandle, state: State<'_, RecentState>, kind: RecentKind) -> Vec<String> {
+    let mut lists = state.lists.lock().unwrap();
+    lists
+        .get_or_insert_with(|| store::load_json(&app, RECENT_FILE))
+        .list(kind)
+        .clone()
+}
+
+/// Moves `path` to the front of the list, dropping the oldest entries past the cap.
+#[tauri::command]
+pub fn add_recent(
+    app: AppHandle,
+    state: State<'_, RecentState>,
+    kind: RecentKind,
+    path: String,
+) -> Result<Vec<String>, String> {
+    state.update(&app, kind, |list, limit| {
+        list.retain(|p| *p != path);
+        list.insert(0, path);
+        list.truncate(limit);
+    })
+}
+
+#[tauri::command]
+pub fn remove_recent(
+    app: AppHandle,
+    state: State<'_, RecentState>,
+    kind: RecentKind,
+    path: String,
+) -> Result<Vec<String>, String> {
+    state.update(&app, kind, |list, _| list.retain(|p| *p != path))
+}
+
+/// Takes the most recently closed tab off the list, for "reopen closed tab".
+#[tauri::command]
+pub fn pop_closed_tab(
+    app: AppHandle,
+    state: State<'_, RecentState>,
+) -> Result<Option<String>, String> {
+    let mut popped = None;
+    state.update(&app, RecentKind::ClosedTab, |list, _| {
+        if !list.is_empty() {
+            popped = Some(list.remove(0));
+        }
+    })?;
+    Ok(popped)
+}
+
+#[tauri::command]
+pub fn clear_recent(
+    app: AppHandle,
+    state: State<'_, RecentState>,
+    kind: RecentKind,
+) -> Result<(), String> {
+    state.update(&app, kind, |list, _| list.clear()).map(|_| ())
+}
+
+#[tauri::command]
+pub fn set_recent_limit(
+    app: AppHandle,
+    state: State<'_, RecentState>,
+    limit: usize,
+) -> Result<(), String> {
+    let mut lists = state.lists.lock().unwrap();
+    let lists = lists.get_or_insert_with(|| store::load_json(&app, RECENT_FILE));
+    lists.limit = limit;
+    for kind in [RecentKind::File, RecentKind::Folder, RecentKind::ClosedTab] {
+        lists.list(kind).truncate(limit);
+    }
+    store::save_json(&app, RECENT_FILE, &*lists)
+}
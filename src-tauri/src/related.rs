@@ -0,0 +1,192 @@
+use crate::index::{self, IndexState, VaultIndex};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Weights of the three signals in the final score.
+const TAG_WEIGHT: f64 = 0.35;
+const LINK_WEIGHT: f64 = 0.35;
+const TEXT_WEIGHT: f64 = 0.3;
+/// Scores below this aren't worth suggesting
+const MIN_SCORE: f64 = 0.05;
+
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "all", "any", "can", "had", "her", "was",
+    "one", "our", "out", "has", "his", "how", "its", "may", "new", "now", "see", "who", "did",
+    "get", "use", "that", "this", "with", "from", "have", "they", "will", "your", "what", "when",
+    "which", "there", "their", "then", "them", "than", "been", "were", "into", "also", "more",
+    "some", "such", "only", "other", "would", "could", "should", "about", "these", "those",
+];
+
+#[derive(Serialize)]
+pub struct RelatedNote {
+    path: String,
+    /// 0..1, higher is more related
+    score: f64,
+    shared_tags: Vec<String>,
+    /// One note links directly to the other
+    linked: bool,
+    /// Notes both link to
+    shared_links: usize,
+    /// Cosine similarity of the note texts, 0..1
+    similarity: f64,
+}
+
+struct NoteSignals {
+    tags: HashSet<String>,
+    targets: HashSet<PathBuf>,
+}
+
+fn signals(index: &VaultIndex, path: &Path) -> Option<NoteSignals> {
+    let note = index.notes.get(path)?;
+    Some(NoteSignals {
+        tags: note.tags.iter().map(|t| t.to_lowercase()).collect(),
+        targets: note
+            .links
+            .iter()
+            .filter_map(|link| index.resolve(link, path))
+            .filter(|target| target != path)
+            .collect(),
+    })
+}
+
+/// Overlap of two sets, normalized so large sets don't dominate.
+fn set_cosine<T: Eq + std::hash::Hash>(a: &HashSet<T>, b: &HashSet<T>) -> (usize, f64) {
+    let shared = a.intersection(b).count();
+    if shared == 0 {
+        return (0, 0.0);
+    }
+    (shared, shared as f64 / ((a.len() * b.len()) as f64).sqrt())
+}
+
+fn term_counts(content: &str) -> HashMap<String, f64> {
+    let mut counts = HashMap::new();
+    for word in index::plain_text(content)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3 && !w.chars().all(|c| c.is_numeric()))
+    {
+        let word = word.to_lowercase();
+        if !STOPWORDS.contains(&word.as_str()) {
+            *counts.entry(word).or_insert(0.0) += 1.0;
+        }
+    }
+    counts
+}
+
+/// TF-IDF cosine similarity of `path` against every other note.
+fn text_similarity(path: &Path, notes: &[PathBuf]) -> HashMap<PathBuf, f64> {
+    let counts: Vec<(&PathBuf, HashMap<String, f64>)> = notes
+        .iter()
+        .filter_map(|p| Some((p, term_counts(&fs::read_to_string(p).ok()?))))
+        .collect();
+    let mut df: HashMap<&str, f64> = HashMap::new();
+    for (_, terms) in &counts {
+        for term in terms.keys() {
+            *df.entry(term.as_str()).or_insert(0.0) += 1.0;
+        }
+    }
+    let n = counts.len() as f64;
+    let weigh = |terms: &HashMap<String, f64>| -> HashMap<String, f64> {
+        terms
+            .iter()
+            .map(|(term, tf)| {
+                let idf = (n / df.get(term.as_str()).copied().unwrap_or(1.0)).ln() + 1.0;
+                (term.clone(), (1.0 + tf.ln()) * idf)
+            })
+            .collect()
+    };
+    let norm = |v: &HashMap<String, f64>| v.values().map(|w| w * w).sum::<f64>().sqrt();
+
+    let Some(target) = counts
+        .iter()
+        .find(|(p, _)| p.as_path() == path)
+        .map(|(_, t)| weigh(t))
+    else {
+        return HashMap::new();
+    };
+    let target_norm = norm(&target);
+    if target_norm == 0.0 {
+        return HashMap::new();
+    }
+
+    counts
+        .iter()
+        .filter(|(p, _)| p.as_path() != path)
+        .filter_map(|(p, terms)| {
+            let vector = weigh(terms);
+            let dot: f64 = vector
+                .iter()
+                .filter_map(|(term, w)| target.get(term).map(|t| t * w))
+                .sum();
+            let norm = norm(&vector);
+            (dot > 0.0 && norm > 0.0).then(|| ((*p).clone(), dot / (norm * target_norm)))
+        })
+        .collect()
+}
+
+/// Notes related to `path` for a "See also" panel, ranked by shared tags,
+/// links between or shared by the notes, and similar wording.
+#[tauri::command]
+pub fn get_related_notes(
+    state: State<'_, IndexState>,
+    path: String,
+    vault_root: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<RelatedNote>, String> {
+    let target = PathBuf::from(&path);
+    let root = match vault_root {
+        Some(root) => PathBuf::from(root),
+        None => target
+            .parent()
+            .ok_or_else(|| crate::i18n::t("error.invalid_file_path"))?
+            .to_path_buf(),
+    };
+
+    let (notes, mut related) = state.with(&root, |index| {
+        let notes: Vec<PathBuf> = index.notes.keys().cloned().collect();
+        let Some(own) = signals(index, &target) else {
+            return (notes, Vec::new());
+        };
+        let related: Vec<RelatedNote> = index
+            .notes
+            .keys()
+            .filter(|other| **other != target)
+            .filter_map(|other| {
+                let theirs = signals(index, other)?;
+                let linked = own.targets.contains(other) || theirs.targets.contains(&target);
+                let (_, tag_score) = set_cosine(&own.tags, &theirs.tags);
+                let (shared_links, shared_score) = set_cosine(&own.targets, &theirs.targets);
+                let mut shared_tags: Vec<String> =
+                    own.tags.intersection(&theirs.tags).cloned().collect();
+                shared_tags.sort();
+                Some(RelatedNote {
+                    path: other.to_string_lossy().to_string(),
+                    score: TAG_WEIGHT * tag_score
+                        + LINK_WEIGHT * if linked { 1.0 } else { shared_score },
+                    shared_tags,
+                    linked,
+                    shared_links,
+                    similarity: 0.0,
+                })
+            })
+            .collect();
+        (notes, related)
+    });
+    if related.is_empty() {
+        return Ok(related);
+    }
+
+    let similarity = text_similarity(&target, &notes);
+    for note in &mut related {
+        if let Some(s) = similarity.get(Path::new(&note.path)) {
+            note.similarity = *s;
+            note.score += TEXT_WEIGHT * s;
+        }
+    }
+    related.retain(|note| note.score >= MIN_SCORE);
+    related.sort_by(|a, b| b.score.total_cmp(&a.score));
+    related.truncate(limit.unwrap_or(10));
+    Ok(related)
+}
@@ -0,0 +1,226 @@
+use crate::blocks;
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::{AppHandle, State};
+
+const RENDER_CACHE_CONFIG_FILE: &str = "render_cache.json";
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderCacheConfig {
+    max_entries: usize,
+}
+
+impl Default for RenderCacheConfig {
+    fn default() -> Self {
+        RenderCacheConfig { max_entries: 64 }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RenderCacheStats {
+    entries: usize,
+    max_entries: usize,
+    hits: u64,
+    misses: u64,
+}
+
+/// A file's modification time and size, `None` while it doesn't exist.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+struct Entry {
+    used: u64,
+    input: String,
+    html: String,
+    /// Embedded notes and tables as they were when rendered
+    reads: Vec<(PathBuf, Stamp)>,
+}
+
+impl Entry {
+    fn is_current(&self) -> bool {
+        self.reads.iter().all(|(path, old)| stamp(path) == *old)
+    }
+}
+
+thread_local! {
+    /// Files read by the render running on this thread, while one is tracked
+    static READS: RefCell<Option<Vec<PathBuf>>> = const { RefCell::new(None) };
+}
+
+/// Records that the render in progress read `path`, so its cached HTML is
+/// dropped once that file changes.
+pub fn depends_on(path: &Path) {
+    READS.with(|reads| {
+        if let Some(reads) = reads.borrow_mut().as_mut() {
+            reads.push(path.to_path_buf());
+        }
+    });
+}
+
+/// Runs a render, returning its result and the files it read.
+pub fn tracking<R>(render: impl FnOnce() -> R) -> (R, Vec<PathBuf>) {
+    let outer = READS.with(|reads| reads.replace(Some(Vec::new())));
+    let result = render();
+    let mut reads = READS.with(|reads| reads.replace(outer)).unwrap_or_default();
+    reads.sort();
+    reads.dedup();
+    (result, reads)
+}
+
+#[derive(Default)]
+struct RenderCache {
+    config: Option<RenderCacheConfig>,
+    /// key hash -> entry
+    entries: HashMap<u64, Entry>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl RenderCache {
+    fn max_entries(&mut self, app: &AppHandle) -> usize {
+        self.config
+            .get_or_insert_with(|| store::load_json(app, RENDER_CACHE_CONFIG_FILE))
+            .max_entries
+    }
+
+    fn evict(&mut self, max_entries: usize) {
+        while self.entries.len() > max_entries {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| *key);
+            match oldest {
+                Some(key) => self.entries.remove(&key),
+                None => break,
+            };
+        }
+    }
+}
+
+/// In-memory LRU of rendered HTML, so switching back to a tab doesn't
+/// re-run the whole pipeline on unchanged content.
+#[derive(Default)]
+pub struct RenderCacheState {
+    cache: Mutex<RenderCache>,
+}
+
+impl RenderCacheState {
+    pub fn get(&self, key: &CacheKey) -> Option<String> {
+        let mut cache = self.cache.lock().unwrap();
+        cache.tick += 1;
+        let tick = cache.tick;
+        match cache
+            .entries
+            .get_mut(&key.hash)
+            .filter(|entry| entry.input == key.input && entry.is_current())
+        {
+            Some(entry) => {
+                entry.used = tick;
+                let html = entry.html.clone();
+                cache.hits += 1;
+                Some(html)
+            }
+            None => {
+                cache.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Stores `html` for `key`, along with the state of the files it `reads`.
+    pub fn put(&self, app: &AppHandle, key: CacheKey, html: String, reads: Vec<PathBuf>) {
+        let reads = reads
+            .into_iter()
+            .map(|path| {
+                let stamp = stamp(&path);
+                (path, stamp)
+            })
+            .collect();
+        let mut cache = self.cache.lock().unwrap();
+        let max_entries = cache.max_entries(app);
+        if max_entries == 0 {
+            return;
+        }
+        cache.tick += 1;
+        let tick = cache.tick;
+        cache.entries.insert(
+            key.hash,
+            Entry {
+                used: tick,
+                input: key.input,
+                html,
+                reads,
+            },
+        );
+        cache.evict(max_entries);
+    }
+
+    /// Drops every entry, e.g. when notes they embed or link to may have changed.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().entries.clear();
+    }
+}
+
+/// A rendering's input. The hash finds the entry and the input is compared
+/// on a hit, so two notes whose hashes collide never get each other's HTML.
+pub struct CacheKey {
+    hash: u64,
+    input: String,
+}
+
+/// Cache key covering the content and everything that affects its rendering.
+pub fn key(content: &str, options: &impl Serialize) -> CacheKey {
+    let options = serde_json::to_string(options).unwrap_or_default();
+    let input = format!("{}\0{}", options, content);
+    CacheKey {
+        hash: blocks::fnv1a(input.as_bytes()),
+        input,
+    }
+}
+
+#[tauri::command]
+pub fn get_render_cache_stats(
+    app: AppHandle,
+    state: State<'_, RenderCacheState>,
+) -> RenderCacheStats {
+    let mut cache = state.cache.lock().unwrap();
+    RenderCacheStats {
+        entries: cache.entries.len(),
+        max_entries: cache.max_entries(&app),
+        hits: cache.hits,
+        misses: cache.misses,
+    }
+}
+
+#[tauri::command]
+pub fn clear_render_cache(state: State<'_, RenderCacheState>) {
+    state.clear();
+}
+
+/// Sets how many rendered notes are kept; 0 disables the cache.
+#[tauri::command]
+pub fn set_render_cache_size(
+    app: AppHandle,
+    state: State<'_, RenderCacheState>,
+    max_entries: usize,
+) -> Result<(), String> {
+    let config = RenderCacheConfig { max_entries };
+    store::save_json(&app, RENDER_CACHE_CONFIG_FILE, &config)?;
+    let mut cache = state.cache.lock().unwrap();
+    cache.evict(max_entries);
+    cache.config = Some(config);
+    Ok(())
+}
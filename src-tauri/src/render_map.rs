@@ -0,0 +1,52 @@
+use crate::blocks::{self, BlockKind};
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// A top-level source block and the rendered element it produced.
+#[derive(Serialize)]
+pub struct RenderedBlock {
+    pub kind: BlockKind,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Exact `data-sourcepos` value of the outermost rendered element, for
+    /// `querySelector`. `None` when the block renders without one (e.g. embeds).
+    pub sourcepos: Option<String>,
+}
+
+/// Pairs each top-level block in `content` with the element in `html` that
+/// was rendered from it. Elements are visited in document order, so the first
+/// one starting inside a block's line range is its outermost element.
+pub fn build(content: &str, html: &str) -> Vec<RenderedBlock> {
+    static SOURCEPOS: OnceLock<Regex> = OnceLock::new();
+    let re =
+        SOURCEPOS.get_or_init(|| Regex::new(r#"data-sourcepos="((\d+):\d+-\d+:\d+)""#).unwrap());
+    let positions: Vec<(usize, &str)> = re
+        .captures_iter(html)
+        .filter_map(|caps| {
+            let line = caps[2].parse().ok()?;
+            Some((line, caps.get(1)?.as_str()))
+        })
+        .collect();
+
+    let mut next = 0;
+    blocks::split_blocks(content)
+        .into_iter()
+        .map(|block| {
+            let found = positions[next..]
+                .iter()
+                .position(|(line, _)| *line >= block.start_line)
+                .map(|i| next + i)
+                .filter(|i| positions[*i].0 <= block.end_line);
+            if let Some(i) = found {
+                next = i + 1;
+            }
+            RenderedBlock {
+                kind: block.kind,
+                start_line: block.start_line,
+                end_line: block.end_line,
+                sourcepos: found.map(|i| positions[i].1.to_string()),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,68 @@
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+const RENDER_SETTINGS_FILE: &str = "render.json";
+
+/// User preferences for the markdown renderer, persisted in the config dir.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RenderSettings {
+    /// Treat single newlines as line breaks
+    pub hardbreaks: bool,
+    /// Curly quotes, en/em dashes and ellipses
+    pub smart_punctuation: bool,
+    /// `^superscript^`
+    pub superscript: bool,
+    pub footnotes: bool,
+    /// GitHub-style `:smile:` emoji shortcodes
+    pub emoji_shortcodes: bool,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            hardbreaks: true,
+            smart_punctuation: false,
+            superscript: false,
+            footnotes: true,
+            emoji_shortcodes: true,
+        }
+    }
+}
+
+/// Settings are read from disk once and cached for every render after that.
+#[derive(Default)]
+pub struct RenderSettingsState {
+    settings: Mutex<Option<RenderSettings>>,
+}
+
+impl RenderSettingsState {
+    pub fn get(&self, app: &AppHandle) -> RenderSettings {
+        self.settings
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| store::load_json(app, RENDER_SETTINGS_FILE))
+            .clone()
+    }
+}
+
+#[tauri::command]
+pub fn get_render_settings(
+    app: AppHandle,
+    state: State<'_, RenderSettingsState>,
+) -> RenderSettings {
+    state.get(&app)
+}
+
+#[tauri::command]
+pub fn save_render_settings(
+    app: AppHandle,
+    state: State<'_, RenderSettingsState>,
+    settings: RenderSettings,
+) -> Result<(), String> {
+    store::save_json(&app, RENDER_SETTINGS_FILE, &settings)?;
+    *state.settings.lock().unwrap() = Some(settings);
+    Ok(())
+}
@@ -0,0 +1,429 @@
+use crate::{frontmatter, index, store, vault};
+use chrono::{DateTime, Local, NaiveDate};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Characters of context shown before a match in snippets.
+const CONTEXT_BEFORE: usize = 40;
+const SNIPPET_LENGTH: usize = 160;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SearchOptions {
+    case_sensitive: bool,
+    whole_word: bool,
+    /// Treat the query as a regular expression
+    regex: bool,
+    max_results: usize,
+    /// Globs relative to the search root, e.g. `projects/**`; empty means all
+    paths: Vec<String>,
+    exclude_paths: Vec<String>,
+    /// Notes must carry every one of these tags (`#project` also matches
+    /// `#project/marko`)
+    tags: Vec<String>,
+    /// A date (`2024-01-31`), an RFC 3339 timestamp, or an age such as `7d`,
+    /// `12h`, `2w`, `6m` or `1y`
+    modified_after: Option<String>,
+    modified_before: Option<String>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            case_sensitive: false,
+            whole_word: false,
+            regex: false,
+            max_results: 1000,
+            paths: Vec::new(),
+            exclude_paths: Vec::new(),
+            tags: Vec::new(),
+            modified_after: None,
+            modified_before: None,
+        }
+    }
+}
+
+impl SearchOptions {
+    fn has_filters(&self) -> bool {
+        !self.paths.is_empty()
+            || !self.exclude_paths.is_empty()
+            || !self.tags.is_empty()
+            || self.modified_after.is_some()
+            || self.modified_before.is_some()
+    }
+}
+
+/// `SearchOptions` filters, compiled once per search.
+struct SearchFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+    tags: Vec<String>,
+    after: Option<SystemTime>,
+    before: Option<SystemTime>,
+}
+
+fn glob_set(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern.trim_start_matches("./"))
+            .case_insensitive(true)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| e.to_string())?;
+        builder.add(glob);
+    }
+    builder.build().map(Some).map_err(|e| e.to_string())
+}
+
+/// Parses a filter date. Calendar dates cover the whole day, so
+/// `modified_before: 2024-01-31` includes the 31st.
+fn parse_time(value: &str, end_of_day: bool) -> Result<SystemTime, String> {
+    let value = value.trim();
+    let invalid = || crate::i18n::tf("error.invalid_date", &[("value", value)]);
+
+    if let Some(unit) = value.chars().last().filter(|c| c.is_ascii_alphabetic()) {
+        if let Ok(amount) = value[..value.len() - 1].parse::<u64>() {
+            let seconds = match unit {
+                'h' => 3600,
+                'd' => 86_400,
+                'w' => 7 * 86_400,
+                'm' => 30 * 86_400,
+                'y' => 365 * 86_400,
+                _ => return Err(invalid()),
+            };
+            return SystemTime::now()
+                .checked_sub(Duration::from_secs(amount * seconds))
+                .ok_or_else(invalid);
+        }
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.into());
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| invalid())?;
+    let date = if end_of_day {
+        date.succ_opt().ok_or_else(invalid)?
+    } else {
+        date
+    };
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|t| t.and_local_timezone(Local).earliest())
+        .map(SystemTime::from)
+        .ok_or_else(invalid)
+}
+
+impl SearchFilter {
+    fn new(options: &SearchOptions) -> Result<SearchFilter, String> {
+        Ok(SearchFilter {
+            include: glob_set(&options.paths)?,
+            exclude: glob_set(&options.exclude_paths)?,
+            tags: options
+                .tags
+                .iter()
+                .map(|t| t.trim().trim_start_matches('#').to_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect(),
+            after: options
+                .modified_after
+                .as_deref()
+                .map(|v| parse_time(v, false))
+                .transpose()?,
+            before: options
+                .modified_before
+                .as_deref()
+                .map(|v| parse_time(v, true))
+                .transpose()?,
+        })
+    }
+
+    /// Path and date checks, done before the file is read.
+    fn matches_file(&self, root: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        if self.include.as_ref().is_some_and(|g| !g.is_match(relative)) {
+            return false;
+        }
+        if self.exclude.as_ref().is_some_and(|g| g.is_match(relative)) {
+            return false;
+        }
+        if self.after.is_none() && self.before.is_none() {
+            return true;
+        }
+        let Some(modified) = fs::metadata(path).and_then(|m| m.modified()).ok() else {
+            return false;
+        };
+        let too_old = self.after.is_some_and(|after| modified < after);
+        let too_new = self.before.is_some_and(|before| modified >= before);
+        !too_old && !too_new
+    }
+
+    fn matches_content(&self, content: &str) -> bool {
+        if self.tags.is_empty() {
+            return true;
+        }
+        let frontmatter = frontmatter::parse(content).ok().flatten();
+        let tags: Vec<String> = index::note_tags(&index::strip_code(content), frontmatter.as_ref())
+            .into_iter()
+            .map(|t| t.to_lowercase())
+            .collect();
+        self.tags.iter().all(|wanted| {
+            let prefix = format!("{}/", wanted);
+            tags.iter().any(|t| t == wanted || t.starts_with(&prefix))
+        })
+    }
+}
+
+/// Starting a search supersedes the previous one, so typing in the search
+/// box never leaves stale walks running.
+#[derive(Default)]
+pub struct SearchState {
+    next_id: AtomicU64,
+    current: AtomicU64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SearchMatch {
+    /// 1-based
+    line: usize,
+    /// 0-based character offset of the match in the line
+    column: usize,
+    /// HTML-escaped context with the match wrapped in `<mark>`
+    snippet: String,
+}
+
+#[derive(Serialize, Clone)]
+struct SearchResults {
+    search_id: u64,
+    path: String,
+    matches: Vec<SearchMatch>,
+}
+
+#[derive(Serialize, Clone)]
+struct SearchDone {
+    search_id: u64,
+    files_searched: usize,
+    files_matched: usize,
+    total_matches: usize,
+    /// Stopped at `max_results`
+    truncated: bool,
+    cancelled: bool,
+}
+
+fn build_regex(query: &str, options: &SearchOptions) -> Result<Regex, String> {
+    let mut pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    if options.whole_word {
+        pattern = format!(r"\b(?:{})\b", pattern);
+    }
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+fn snippet(line: &str, start: usize, end: usize) -> String {
+    let before: Vec<(usize, char)> = line[..start].char_indices().collect();
+    let from = before
+        .len()
+        .checked_sub(CONTEXT_BEFORE)
+        .map(|i| before[i].0)
+        .unwrap_or(0);
+    let budget = SNIPPET_LENGTH.saturating_sub(line[from..end].chars().count());
+    let to = line[end..]
+        .char_indices()
+        .nth(budget)
+        .map(|(i, _)| end + i)
+        .unwrap_or(line.len());
+
+    format!(
+        "{}{}<mark>{}</mark>{}{}",
+        if from > 0 { "…" } else { "" },
+        crate::escape_attr(line[from..start].trim_start()),
+        crate::escape_attr(&line[start..end]),
+        crate::escape_attr(line[end..to].trim_end()),
+        if to < line.len() { "…" } else { "" }
+    )
+}
+
+pub fn search_content(content: &str, re: &Regex, limit: usize) -> Vec<SearchMatch> {
+    let mut matches = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        // Empty matches (e.g. from `a*`) are skipped, not the rest of the line
+        for m in re.find_iter(line).filter(|m| !m.as_str().is_empty()) {
+            if matches.len() >= limit {
+                return matches;
+            }
+            matches.push(SearchMatch {
+                line: i + 1,
+                column: line[..m.start()].chars().count(),
+                snippet: snippet(line, m.start(), m.end()),
+            });
+        }
+    }
+    matches
+}
+
+fn run_search(
+    app: &AppHandle,
+    search_id: u64,
+    root: &Path,
+    re: Option<Regex>,
+    filter: SearchFilter,
+    max_results: usize,
+) {
+    let state = app.state::<SearchState>();
+    let mut done = SearchDone {
+        search_id,
+        files_searched: 0,
+        files_matched: 0,
+        total_matches: 0,
+        truncated: false,
+        cancelled: false,
+    };
+
+    for file in vault::markdown_files(root) {
+        if state.current.load(Ordering::SeqCst) != search_id {
+            done.cancelled = true;
+            break;
+        }
+        if done.total_matches >= max_results {
+            done.truncated = true;
+            break;
+        }
+        if !filter.matches_file(root, &file) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        if !filter.matches_content(&content) {
+            continue;
+        }
+        done.files_searched += 1;
+        // Without a query every note passing the filters is a result
+        let matches = match &re {
+            Some(re) => search_content(&content, re, max_results - done.total_matches),
+            None => Vec::new(),
+        };
+        if re.is_some() && matches.is_empty() {
+            continue;
+        }
+        done.files_matched += 1;
+        done.total_matches += matches.len().max(1);
+        let _ = app.emit(
+            "search-results",
+            SearchResults {
+                search_id,
+                path: file.to_string_lossy().to_string(),
+                matches,
+            },
+        );
+    }
+
+    let _ = app.emit("search-done", done);
+}
+
+/// Searches every markdown note under `root` on a background thread,
+/// narrowed by the path, tag and date filters in `options`. An empty query
+/// lists the notes the filters select. Matches stream in as `search-results`
+/// events (one per file) followed by a `search-done` summary; returns the
+/// search id they carry.
+#[tauri::command]
+pub fn search_vault(
+    app: AppHandle,
+    state: State<'_, SearchState>,
+    root: String,
+    query: String,
+    options: Option<SearchOptions>,
+) -> Result<u64, String> {
+    let options = options.unwrap_or_default();
+    if query.is_empty() && !options.has_filters() {
+        return Err(crate::i18n::t("error.empty_search"));
+    }
+    let re = if query.is_empty() {
+        None
+    } else {
+        Some(build_regex(&query, &options)?)
+    };
+    let filter = SearchFilter::new(&options)?;
+    let root = PathBuf::from(root);
+    if !root.is_dir() {
+        return Err(crate::i18n::t("error.not_a_directory"));
+    }
+
+    let search_id = state.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+    state.current.store(search_id, Ordering::SeqCst);
+    let max_results = options.max_results;
+    std::thread::spawn(move || {
+        run_search(&app, search_id, &root, re, filter, max_results);
+    });
+    Ok(search_id)
+}
+
+#[tauri::command]
+pub fn cancel_search(state: State<'_, SearchState>) {
+    state.current.store(0, Ordering::SeqCst);
+}
+
+const SAVED_SEARCHES_FILE: &str = "saved_searches.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SavedSearch {
+    name: String,
+    query: String,
+    #[serde(default)]
+    options: SearchOptions,
+}
+
+#[tauri::command]
+pub fn list_saved_searches(app: AppHandle) -> Vec<SavedSearch> {
+    store::load_json(&app, SAVED_SEARCHES_FILE)
+}
+
+/// Saves a search under `name`, replacing any existing one with that name.
+#[tauri::command]
+pub fn save_search(
+    app: AppHandle,
+    name: String,
+    query: String,
+    options: Option<SearchOptions>,
+) -> Result<Vec<SavedSearch>, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(crate::i18n::t("error.saved_search_name"));
+    }
+    let options = options.unwrap_or_default();
+    // Reject filters that would fail every time the search is run
+    SearchFilter::new(&options)?;
+
+    let mut searches: Vec<SavedSearch> = store::load_json(&app, SAVED_SEARCHES_FILE);
+    let search = SavedSearch {
+        name: name.clone(),
+        query,
+        options,
+    };
+    match searches.iter_mut().find(|s| s.name == name) {
+        Some(existing) => *existing = search,
+        None => searches.push(search),
+    }
+    store::save_json(&app, SAVED_SEARCHES_FILE, &searches)?;
+    Ok(searches)
+}
+
+#[tauri::command]
+pub fn delete_saved_search(app: AppHandle, name: String) -> Result<Vec<SavedSearch>, String> {
+    let mut searches: Vec<SavedSearch> = store::load_json(&app, SAVED_SEARCHES_FILE);
+    searches.retain(|s| s.name != name);
+    store::save_json(&app, SAVED_SEARCHES_FILE, &searches)?;
+    Ok(searches)
+}
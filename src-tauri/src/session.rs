@@ -0,0 +1,164 @@
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+const SESSION_FILE: &str = "session.json";
+
+/// Scrolling changes the session constantly, so scroll positions are written
+/// at most this often; a held-back position is written once the interval is
+/// over, or along with any other change.
+const SCROLL_WRITE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionTab {
+    path: String,
+    #[serde(default)]
+    scroll_top: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Session {
+    tabs: Vec<SessionTab>,
+    active_tab_path: Option<String>,
+    /// The folder open in the explorer
+    folder: Option<String>,
+}
+
+/// The open workspace, written through on every change so it survives a
+/// crash as well as a normal quit.
+#[derive(Default)]
+pub struct SessionState {
+    session: Mutex<Option<Session>>,
+    last_write: Mutex<Option<Instant>>,
+    /// A throttled change is waiting for its deferred write
+    unsaved: AtomicBool,
+}
+
+impl SessionState {
+    fn update(
+        &self,
+        app: &AppHandle,
+        throttle: bool,
+        f: impl FnOnce(&mut Session),
+    ) -> Result<(), String> {
+        let mut session = self.session.lock().unwrap();
+        let session = session.get_or_insert_with(|| store::load_json(app, SESSION_FILE));
+        f(session);
+
+        let mut last_write = self.last_write.lock().unwrap();
+        if throttle {
+            if let Some(wait) = last_write
+                .and_then(|t| SCROLL_WRITE_INTERVAL.checked_sub(t.elapsed()))
+                .filter(|wait| !wait.is_zero())
+            {
+                // One deferred write covers every change until it runs
+                if !self.unsaved.swap(true, Ordering::Relaxed) {
+                    let app = app.clone();
+                    thread::spawn(move || {
+                        thread::sleep(wait);
+                        app.state::<SessionState>().flush(&app);
+                    });
+                }
+                return Ok(());
+            }
+        }
+        *last_write = Some(Instant::now());
+        self.unsaved.store(false, Ordering::Relaxed);
+        store::save_json(app, SESSION_FILE, session)
+    }
+
+    /// Writes a change that was held back by the throttle, if it hasn't been
+    /// written along with a later one.
+    pub fn flush(&self, app: &AppHandle) {
+        let session = self.session.lock().unwrap();
+        if !self.unsaved.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        if let Some(session) = session.as_ref() {
+            *self.last_write.lock().unwrap() = Some(Instant::now());
+            let _ = store::save_json(app, SESSION_FILE, session);
+        }
+    }
+}
+
+/// Replaces the open tabs, e.g. after a tab was opened, closed, moved or
+/// activated. Scroll positions already known for a path are kept when the
+/// new tab doesn't carry one.
+#[tauri::command]
+pub fn save_session_tabs(
+    app: AppHandle,
+    state: State<'_, SessionState>,
+    tabs: Vec<SessionTab>,
+    active_tab_path: Option<String>,
+) -> Result<(), String> {
+    state.update(&app, false, |session| {
+        let old = std::mem::take(&mut session.tabs);
+        session.tabs = tabs
+            .into_iter()
+            .map(|mut tab| {
+                if tab.scroll_top == 0.0 {
+                    if let Some(known) = old.iter().find(|t| t.path == tab.path) {
+                        tab.scroll_top = known.scroll_top;
+                    }
+                }
+                tab
+            })
+            .collect();
+        session.active_tab_path = active_tab_path;
+    })
+}
+
+#[tauri::command]
+pub fn save_session_scroll(
+    app: AppHandle,
+    state: State<'_, SessionState>,
+    path: String,
+    scroll_top: f64,
+) -> Result<(), String> {
+    state.update(&app, true, |session| {
+        if let Some(tab) = session.tabs.iter_mut().find(|t| t.path == path) {
+            tab.scroll_top = scroll_top;
+        }
+    })
+}
+
+#[tauri::command]
+pub fn save_session_folder(
+    app: AppHandle,
+    state: State<'_, SessionState>,
+    folder: Option<String>,
+) -> Result<(), String> {
+    state.update(&app, false, |session| session.folder = folder)
+}
+
+/// The last session, without tabs whose file is gone. `None` when there is
+/// nothing to reopen.
+#[tauri::command]
+pub fn restore_session(app: AppHandle, state: State<'_, SessionState>) -> Option<Session> {
+    let mut session = state
+        .session
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| store::load_json(&app, SESSION_FILE))
+        .clone();
+    session.tabs.retain(|tab| Path::new(&tab.path).is_file());
+    if let Some(active) = &session.active_tab_path {
+        if !session.tabs.iter().any(|t| &t.path == active) {
+            session.active_tab_path = session.tabs.first().map(|t| t.path.clone());
+        }
+    }
+    if !session
+        .folder
+        .as_deref()
+        .is_some_and(|f| Path::new(f).is_dir())
+    {
+        session.folder = None;
+    }
+    (!session.tabs.is_empty() || session.folder.is_some()).then_some(session)
+}
@@ -0,0 +1,193 @@
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const SETTINGS_FILE: &str = "settings.json";
+/// Where the theme was kept before settings.json
+const LEGACY_THEME_FILE: &str = "theme.txt";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Theme::System => "system",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EditorWidth {
+    Compact,
+    #[default]
+    Default,
+    Wide,
+    Full,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SidebarPosition {
+    #[default]
+    Left,
+    Right,
+}
+
+/// App preferences, persisted in the config dir as settings.json.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub theme: Theme,
+    pub minimap: bool,
+    /// Monaco's `wordWrap`: "on", "off", ...
+    pub word_wrap: String,
+    /// Monaco's `lineNumbers`: "on", "off", "relative"
+    pub line_numbers: String,
+    pub vim_mode: bool,
+    pub status_bar: bool,
+    pub word_count: bool,
+    /// Monaco's `renderLineHighlight`: "none", "line", "all", ...
+    pub render_line_highlight: String,
+    pub show_tabs: bool,
+    pub occurrences_highlight: bool,
+    pub auto_save: bool,
+    pub editor_width: EditorWidth,
+    pub sidebar_position: SidebarPosition,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            theme: Theme::System,
+            minimap: false,
+            word_wrap: "on".to_string(),
+            line_numbers: "on".to_string(),
+            vim_mode: false,
+            status_bar: true,
+            word_count: false,
+            render_line_highlight: "none".to_string(),
+            show_tabs: true,
+            occurrences_highlight: false,
+            auto_save: true,
+            editor_width: EditorWidth::Default,
+            sidebar_position: SidebarPosition::Left,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct SettingChanged {
+    key: String,
+    value: serde_json::Value,
+}
+
+/// Settings are read from disk once and cached after that.
+#[derive(Default)]
+pub struct SettingsState {
+    settings: Mutex<Option<Settings>>,
+}
+
+impl SettingsState {
+    pub fn get(&self, app: &AppHandle) -> Settings {
+        self.settings
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| load(app))
+            .clone()
+    }
+}
+
+/// Reads settings.json, creating it from theme.txt on first run after an
+/// upgrade.
+fn load(app: &AppHandle) -> Settings {
+    let Ok(config_dir) = app.path().app_config_dir() else {
+        return Settings::default();
+    };
+    if config_dir.join(SETTINGS_FILE).exists() {
+        return store::load_json(app, SETTINGS_FILE);
+    }
+    let legacy = config_dir.join(LEGACY_THEME_FILE);
+    let Ok(theme) = fs::read_to_string(&legacy) else {
+        return Settings::default();
+    };
+    let settings = Settings {
+        theme: serde_json::from_value(theme.trim().into()).unwrap_or_default(),
+        ..Settings::default()
+    };
+    if store::save_json(app, SETTINGS_FILE, &settings).is_ok() {
+        let _ = fs::remove_file(legacy);
+    }
+    settings
+}
+
+/// Sets one setting by its key, persists it and tells every window with
+/// "setting-changed".
+pub fn set(app: &AppHandle, key: &str, value: serde_json::Value) -> Result<(), String> {
+    let state = app.state::<SettingsState>();
+    // Held throughout so concurrent changes don't overwrite each other
+    let mut cached = state.settings.lock().unwrap();
+    let current = cached.get_or_insert_with(|| load(app));
+    let mut fields = serde_json::to_value(&*current).map_err(|e| e.to_string())?;
+    let Some(field) = fields.get_mut(key) else {
+        return Err(crate::i18n::tf("error.unknown_setting", &[("key", key)]));
+    };
+    *field = value.clone();
+    let settings: Settings = serde_json::from_value(fields).map_err(|e| {
+        crate::i18n::tf(
+            "error.invalid_setting",
+            &[("key", key), ("error", &e.to_string())],
+        )
+    })?;
+    store::save_json(app, SETTINGS_FILE, &settings)?;
+    *cached = Some(settings);
+    drop(cached);
+    let _ = app.emit(
+        "setting-changed",
+        SettingChanged {
+            key: key.to_string(),
+            value,
+        },
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_all_settings(app: AppHandle, state: State<'_, SettingsState>) -> Settings {
+    state.get(&app)
+}
+
+#[tauri::command]
+pub fn get_setting(
+    app: AppHandle,
+    state: State<'_, SettingsState>,
+    key: String,
+) -> Result<serde_json::Value, String> {
+    let fields = serde_json::to_value(state.get(&app)).map_err(|e| e.to_string())?;
+    fields
+        .get(&key)
+        .cloned()
+        .ok_or_else(|| crate::i18n::tf("error.unknown_setting", &[("key", &key)]))
+}
+
+#[tauri::command]
+pub fn set_setting(app: AppHandle, key: String, value: serde_json::Value) -> Result<(), String> {
+    set(&app, &key, value)
+}
+
+/// Kept for frontends that still save the theme on its own.
+#[tauri::command]
+pub fn save_theme(app: AppHandle, theme: String) -> Result<(), String> {
+    set(&app, "theme", theme.into())
+}
@@ -191,7 +191,10 @@ pub async fn install_app(
             Err(e) => {
                 println!("Copy failed (attempt {}): {}", retries, e);
                 if retries == 4 {
-                    return Err(format!("Failed to copy executable: {}", e));
+                    return Err(crate::i18n::tf(
+                        "error.copy_executable",
+                        &[("error", &e.to_string())],
+                    ));
                 }
                 std::thread::sleep(std::time::Duration::from_millis(500));
                 retries += 1;
@@ -0,0 +1,294 @@
+use crate::{blocks, export, store, vault, RenderOptions};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+const SITE_CSS: &str = ".site-nav { max-width: 860px; margin: 0 auto; padding: 16px 32px 0; box-sizing: border-box; font: 14px sans-serif; }
+.site-nav a { color: var(--color-accent-fg); text-decoration: none; }";
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct SiteExportOptions {
+    /// Site title for the index page; defaults to the folder name
+    title: Option<String>,
+    /// "light", "dark" or "system"; defaults to the app's saved theme
+    theme: Option<String>,
+    #[serde(flatten)]
+    render: RenderOptions,
+}
+
+#[derive(Serialize)]
+pub struct SiteExportSummary {
+    index: String,
+    pages: usize,
+    assets: usize,
+}
+
+/// Output path of a note, relative to the site root: `a/b.md` -> `a/b.html`.
+fn page_path(relative: &Path) -> PathBuf {
+    relative.with_extension("html")
+}
+
+/// A URL from the page at `from` (relative to the site root) to `to`.
+fn relative_href(from: &Path, to: &Path) -> String {
+    let depth = from.parent().map(|p| p.components().count()).unwrap_or(0);
+    let target: Vec<String> = to
+        .components()
+        .map(|c| urlencoding::encode(&c.as_os_str().to_string_lossy()).into_owned())
+        .collect();
+    format!("{}{}", "../".repeat(depth), target.join("/"))
+}
+
+struct SiteLinks<'a> {
+    folder: &'a Path,
+    out_dir: &'a Path,
+    /// Asset sources outside the folder, copied to `_assets/`
+    external: BTreeMap<PathBuf, PathBuf>,
+}
+
+impl SiteLinks<'_> {
+    fn note_href(&self, page: &Path, target: &Path) -> Option<String> {
+        let relative = target.strip_prefix(self.folder).ok()?;
+        Some(relative_href(page, &page_path(relative)))
+    }
+
+    fn asset_href(&mut self, page: &Path, asset: &Path) -> Option<String> {
+        if !asset.is_file() {
+            return None;
+        }
+        let relative = match asset.strip_prefix(self.folder) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => {
+                let name = asset.file_name()?.to_string_lossy();
+                let hash = blocks::fnv1a(asset.to_string_lossy().as_bytes());
+                let relative = Path::new("_assets").join(format!("{:08x}-{}", hash as u32, name));
+                self.external.insert(asset.to_path_buf(), relative.clone());
+                relative
+            }
+        };
+        Some(relative_href(page, &relative))
+    }
+
+    /// Points wikilinks, markdown links and assets at their exported copies
+    /// and drops the absolute paths the app's own handlers use.
+    fn rewrite(&mut self, html: &str, page: &Path, source: &Path) -> String {
+        static WIKILINK: OnceLock<Regex> = OnceLock::new();
+        static MD_LINK: OnceLock<Regex> = OnceLock::new();
+        static DATA_PATH: OnceLock<Regex> = OnceLock::new();
+        let wikilink_re = WIKILINK.get_or_init(|| {
+            Regex::new(r##"<a class="(wikilink[^"]*)" href="#" data-path="([^"]*)"([^>]*)>"##)
+                .unwrap()
+        });
+        let md_link_re = MD_LINK
+            .get_or_init(|| Regex::new(r##"href="([^"#:]+\.(?:md|markdown))(#[^"]*)?""##).unwrap());
+        let data_path_re = DATA_PATH.get_or_init(|| Regex::new(r#" data-path="[^"]*""#).unwrap());
+
+        let html = wikilink_re.replace_all(html, |caps: &Captures| {
+            let target = PathBuf::from(caps[2].replace("&amp;", "&").replace("&quot;", "\""));
+            let anchor = caps[3]
+                .split("data-anchor=\"")
+                .nth(1)
+                .and_then(|rest| rest.split('"').next())
+                .map(|a| format!("#{}", a))
+                .unwrap_or_default();
+            match self.note_href(page, &target) {
+                Some(href) => format!(
+                    "<a class=\"{}\" href=\"{}{}\">",
+                    &caps[1],
+                    crate::escape_attr(&href),
+                    anchor
+                ),
+                // Links out of the exported folder have nowhere to go
+                None => format!("<a class=\"{} unresolved\">", &caps[1]),
+            }
+        });
+        let html = md_link_re.replace_all(&html, |caps: &Captures| {
+            let decoded = urlencoding::decode(&caps[1])
+                .map(|s| s.into_owned())
+                .unwrap_or_else(|_| caps[1].to_string());
+            let target = source.parent().unwrap_or(self.folder).join(decoded);
+            match self.note_href(page, &target) {
+                Some(href) => format!(
+                    "href=\"{}{}\"",
+                    crate::escape_attr(&href),
+                    caps.get(2).map(|m| m.as_str()).unwrap_or_default()
+                ),
+                None => caps[0].to_string(),
+            }
+        });
+        let html = export::rewrite_local_assets(&html, |asset| self.asset_href(page, asset));
+        data_path_re.replace_all(&html, "").into_owned()
+    }
+}
+
+fn site_page(title: &str, body: &str, theme: &str, page: &Path, nav: bool) -> String {
+    let nav = if nav {
+        format!(
+            "<nav class=\"site-nav\"><a href=\"{}\">&larr; {}</a></nav>\n",
+            relative_href(page, Path::new("index.html")),
+            crate::escape_attr(&crate::i18n::t("export.index"))
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\"{}>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<link rel=\"stylesheet\" href=\"{}\">\n</head>\n<body>\n{}<article class=\"markdown-body\">\n{}\n</article>\n</body>\n</html>\n",
+        export::theme_attr(theme),
+        crate::escape_attr(title),
+        relative_href(page, Path::new("style.css")),
+        nav,
+        body
+    )
+}
+
+/// A nested list of every page, grouped by folder.
+fn index_body(title: &str, pages: &[(PathBuf, String)]) -> String {
+    let mut body = format!("<h1>{}</h1>\n", crate::escape_attr(title));
+    let mut current: Option<&Path> = None;
+    for (page, page_title) in pages {
+        let folder = page.parent().unwrap_or(Path::new(""));
+        if current != Some(folder) {
+            if current.is_some() {
+                body.push_str("</ul>\n");
+            }
+            if !folder.as_os_str().is_empty() {
+                body.push_str(&format!(
+                    "<h2>{}</h2>\n",
+                    crate::escape_attr(&folder.to_string_lossy())
+                ));
+            }
+            body.push_str("<ul>\n");
+            current = Some(folder);
+        }
+        body.push_str(&format!(
+            "<li><a href=\"{}\">{}</a></li>\n",
+            crate::escape_attr(&relative_href(Path::new("index.html"), page)),
+            crate::escape_attr(page_title)
+        ));
+    }
+    if current.is_some() {
+        body.push_str("</ul>\n");
+    }
+    body
+}
+
+fn write_file(path: &Path, content: impl AsRef<[u8]>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    store::write_atomic(path, content)
+}
+
+/// Publishes a folder as a static site: every note rendered to HTML with
+/// wikilinks turned into relative links, attachments copied alongside, and an
+/// index page listing everything. A root `index.md` replaces the generated index.
+#[tauri::command]
+pub async fn export_site(
+    app: AppHandle,
+    folder: String,
+    out_dir: String,
+    options: Option<SiteExportOptions>,
+) -> Result<SiteExportSummary, String> {
+    let options = options.unwrap_or_default();
+    let folder = Path::new(&folder);
+    let out_dir = Path::new(&out_dir);
+    if !folder.is_dir() {
+        return Err(crate::i18n::t("error.not_a_directory"));
+    }
+    fs::create_dir_all(out_dir).map_err(|e| e.to_string())?;
+
+    let theme = options
+        .theme
+        .clone()
+        .unwrap_or_else(|| export::saved_theme(&app));
+    let mut links = SiteLinks {
+        folder,
+        out_dir,
+        external: BTreeMap::new(),
+    };
+    let mut pages: Vec<(PathBuf, String)> = Vec::new();
+    let mut assets = 0;
+
+    for file in vault::walk_files(folder) {
+        // Don't re-export a previous export nested in the folder
+        if file.starts_with(out_dir) {
+            continue;
+        }
+        let relative = file.strip_prefix(folder).map_err(|e| e.to_string())?;
+        if !vault::is_markdown(&file) {
+            let target = out_dir.join(relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::copy(&file, &target).map_err(|e| e.to_string())?;
+            assets += 1;
+            continue;
+        }
+
+        let content = fs::read_to_string(&file).map_err(|e| e.to_string())?;
+        let mut render = options.render.clone();
+        render.vault_root = Some(folder.to_string_lossy().to_string());
+        render.path = Some(file.to_string_lossy().to_string());
+        let html = crate::render_in_vault(&app, &content, &mut render);
+
+        let page = page_path(relative);
+        let html = links.rewrite(&html, &page, &file);
+        let title = export::note_title(&content, &file);
+        let is_home = page == Path::new("index.html");
+        write_file(
+            &links.out_dir.join(&page),
+            site_page(&title, &html, &theme, &page, !is_home),
+        )?;
+        pages.push((page, title));
+    }
+
+    for (source, relative) in &links.external {
+        let target = out_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::copy(source, &target).map_err(|e| e.to_string())?;
+        assets += 1;
+    }
+
+    let index = out_dir.join("index.html");
+    if !pages
+        .iter()
+        .any(|(page, _)| page == Path::new("index.html"))
+    {
+        let title = options.title.clone().unwrap_or_else(|| {
+            folder
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        });
+        pages.sort_by(|a, b| {
+            (a.0.parent(), a.1.to_lowercase()).cmp(&(b.0.parent(), b.1.to_lowercase()))
+        });
+        write_file(
+            &index,
+            site_page(
+                &title,
+                &index_body(&title, &pages),
+                &theme,
+                Path::new("index.html"),
+                false,
+            ),
+        )?;
+    }
+    write_file(
+        &out_dir.join("style.css"),
+        format!("{}\n{}", export::page_css(), SITE_CSS),
+    )?;
+
+    Ok(SiteExportSummary {
+        index: index.to_string_lossy().to_string(),
+        pages: pages.len(),
+        assets,
+    })
+}
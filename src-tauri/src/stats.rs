@@ -0,0 +1,144 @@
+use crate::index::{IndexState, VaultIndex};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+use tauri::State;
+
+const TOP_N: usize = 10;
+
+#[derive(Serialize)]
+pub struct NoteStat {
+    path: String,
+    value: u64,
+}
+
+#[derive(Serialize)]
+pub struct GrowthPoint {
+    /// YYYY-MM
+    month: String,
+    added: usize,
+    total: usize,
+}
+
+#[derive(Serialize)]
+pub struct VaultStats {
+    notes: usize,
+    words: usize,
+    attachments: usize,
+    attachment_bytes: u64,
+    links: usize,
+    tags: usize,
+    /// By file size in bytes
+    largest: Vec<NoteStat>,
+    /// By creation (or modification) time, as unix seconds
+    oldest: Vec<NoteStat>,
+    /// By number of incoming links
+    most_linked: Vec<NoteStat>,
+    growth: Vec<GrowthPoint>,
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn top(mut stats: Vec<NoteStat>, descending: bool) -> Vec<NoteStat> {
+    if descending {
+        stats.sort_by_key(|s| Reverse(s.value));
+    } else {
+        stats.sort_by_key(|s| s.value);
+    }
+    stats.truncate(TOP_N);
+    stats
+}
+
+pub fn compute_stats(index: &VaultIndex) -> VaultStats {
+    let notes = &index.notes;
+    let tags: HashSet<&str> = notes
+        .values()
+        .flat_map(|n| n.tags.iter().map(|t| t.as_str()))
+        .collect();
+
+    let largest = notes
+        .iter()
+        .map(|(path, n)| NoteStat {
+            path: path.to_string_lossy().to_string(),
+            value: n.size,
+        })
+        .collect();
+
+    let oldest = notes
+        .iter()
+        .filter_map(|(path, n)| {
+            Some(NoteStat {
+                path: path.to_string_lossy().to_string(),
+                value: unix_secs(n.created.or(n.modified)?),
+            })
+        })
+        .collect();
+
+    let most_linked = index
+        .link_counts()
+        .into_iter()
+        .filter(|(path, _)| notes.contains_key(path))
+        .map(|(path, count)| NoteStat {
+            path: path.to_string_lossy().to_string(),
+            value: count as u64,
+        })
+        .collect();
+
+    let mut per_month: BTreeMap<String, usize> = BTreeMap::new();
+    for note in notes.values() {
+        if let Some(time) = note.created.or(note.modified) {
+            let month = DateTime::<Local>::from(time).format("%Y-%m").to_string();
+            *per_month.entry(month).or_insert(0) += 1;
+        }
+    }
+    let mut total = 0;
+    let growth = per_month
+        .into_iter()
+        .map(|(month, added)| {
+            total += added;
+            GrowthPoint {
+                month,
+                added,
+                total,
+            }
+        })
+        .collect();
+
+    VaultStats {
+        notes: notes.len(),
+        words: notes.values().map(|n| n.words).sum(),
+        attachments: index.attachments.len(),
+        attachment_bytes: index
+            .attachments
+            .iter()
+            .filter_map(|p| fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum(),
+        links: notes.values().map(|n| n.links.len()).sum(),
+        tags: tags.len(),
+        largest: top(largest, true),
+        oldest: top(oldest, false),
+        most_linked: top(most_linked, true),
+        growth,
+    }
+}
+
+#[tauri::command]
+pub async fn get_vault_stats(
+    state: State<'_, IndexState>,
+    root: String,
+) -> Result<VaultStats, String> {
+    let root_path = Path::new(&root);
+    if !root_path.is_dir() {
+        return Err(crate::i18n::t("error.not_a_directory"));
+    }
+    Ok(state.with(root_path, compute_stats))
+}
@@ -0,0 +1,63 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+/// Loads a JSON file from the app config dir, falling back to defaults when
+/// the file is missing or unreadable.
+pub fn load_json<T: DeserializeOwned + Default>(app: &AppHandle, file: &str) -> T {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join(file)).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_json<T: Serialize>(app: &AppHandle, file: &str, value: &T) -> Result<(), String> {
+    let config_dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
+    let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    write_atomic(&config_dir.join(file), content)
+}
+
+/// Writes through a temp file in the same directory, flushed to disk and
+/// renamed over the target, so a crash or full disk mid-write leaves the
+/// original untouched instead of a truncated file.
+pub fn write_atomic(path: &Path, content: impl AsRef<[u8]>) -> Result<(), String> {
+    // Write through a symlinked note rather than replacing the link
+    let resolved = fs::canonicalize(path).ok();
+    let path = resolved.as_deref().unwrap_or(path);
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let name = path
+        .file_name()
+        .ok_or_else(|| crate::i18n::t("error.invalid_file_path"))?;
+    let temp = dir.join(format!(".{}.tmp", name.to_string_lossy()));
+
+    let write = || -> std::io::Result<()> {
+        let mut file = File::create(&temp)?;
+        file.write_all(content.as_ref())?;
+        file.sync_all()?;
+        // Keep the original's permissions, e.g. an executable script
+        if let Ok(meta) = fs::metadata(path) {
+            fs::set_permissions(&temp, meta.permissions())?;
+        }
+        fs::rename(&temp, path)
+    };
+    write().map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        e.to_string()
+    })?;
+
+    // Persist the rename itself; directories can't be opened on Windows
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::index::IndexState;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use tauri::State;
+
+#[derive(Serialize)]
+pub struct TagCount {
+    /// As first written in the vault; tags match case-insensitively
+    tag: String,
+    /// Notes carrying the tag
+    notes: usize,
+    /// Total uses, counting repeats within a note
+    occurrences: usize,
+}
+
+/// Every inline `#tag` and frontmatter tag in the vault, sorted by name.
+#[tauri::command]
+pub fn list_tags(state: State<'_, IndexState>, root: String) -> Vec<TagCount> {
+    state.with(&PathBuf::from(root), |index| {
+        let mut tags: BTreeMap<String, TagCount> = BTreeMap::new();
+        for note in index.notes.values() {
+            let mut seen: HashSet<String> = HashSet::new();
+            for tag in &note.tags {
+                let key = tag.to_lowercase();
+                let entry = tags.entry(key.clone()).or_insert_with(|| TagCount {
+                    tag: tag.clone(),
+                    notes: 0,
+                    occurrences: 0,
+                });
+                entry.occurrences += 1;
+                if seen.insert(key) {
+                    entry.notes += 1;
+                }
+            }
+        }
+        tags.into_values().collect()
+    })
+}
+
+/// Notes tagged `tag` (with or without the leading `#`). Nested tags such as
+/// `#project/marko` also match `project` unless `include_nested` is false.
+#[tauri::command]
+pub fn files_for_tag(
+    state: State<'_, IndexState>,
+    root: String,
+    tag: String,
+    include_nested: Option<bool>,
+) -> Vec<String> {
+    let wanted = tag.trim().trim_start_matches('#').to_lowercase();
+    let prefix = format!("{}/", wanted);
+    let include_nested = include_nested.unwrap_or(true);
+
+    state.with(&PathBuf::from(root), |index| {
+        index
+            .notes
+            .iter()
+            .filter(|(_, note)| {
+                note.tags.iter().any(|t| {
+                    let t = t.to_lowercase();
+                    t == wanted || (include_nested && t.starts_with(&prefix))
+                })
+            })
+            .map(|(path, _)| path.to_string_lossy().to_string())
+            .collect()
+    })
+}
@@ -0,0 +1,75 @@
+use crate::store;
+use crate::watch_events::ChangeBatch;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter};
+
+/// Flips `[ ]` / `[x]` in a task list item, returning the new line and
+/// whether it is now checked, or `None` when the line isn't one.
+fn toggle_line(line: &str) -> Option<(String, bool)> {
+    static TASK: OnceLock<Regex> = OnceLock::new();
+    let re =
+        TASK.get_or_init(|| Regex::new(r"^(\s*(?:>\s*)*(?:[-*+]|\d+[.)])\s+\[)([ xX])\]").unwrap());
+    let caps = re.captures(line)?;
+    let checked = &caps[2] == " ";
+    let mark = if checked { "x" } else { " " };
+    Some((
+        format!(
+            "{}{}]{}",
+            &caps[1],
+            mark,
+            &line[caps.get(0).unwrap().end()..]
+        ),
+        checked,
+    ))
+}
+
+/// Toggles the task checkbox on `line_number` (1-based, as in
+/// `data-sourcepos`) and returns whether the task is now checked.
+#[tauri::command]
+pub fn toggle_task(app: AppHandle, path: String, line_number: usize) -> Result<bool, String> {
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let mut lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let index = line_number
+        .checked_sub(1)
+        .filter(|i| *i < lines.len())
+        .ok_or_else(|| {
+            crate::i18n::tf(
+                "error.line_out_of_range",
+                &[("line", &line_number.to_string())],
+            )
+        })?;
+
+    let (toggled, checked) = toggle_line(lines[index]).ok_or_else(|| {
+        crate::i18n::tf("error.not_a_task", &[("line", &line_number.to_string())])
+    })?;
+    lines[index] = &toggled;
+
+    store::write_atomic(Path::new(&path), lines.concat())?;
+    let _ = app.emit("file-changed", ChangeBatch::modified(&path));
+    Ok(checked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::toggle_line;
+
+    #[test]
+    fn toggles_only_the_task_box() {
+        assert_eq!(
+            toggle_line("- [x] check the [ ] box\n"),
+            Some(("- [ ] check the [ ] box\n".to_string(), false))
+        );
+        assert_eq!(
+            toggle_line("- [ ] check the [x] box"),
+            Some(("- [x] check the [x] box".to_string(), true))
+        );
+        assert_eq!(
+            toggle_line("  > 1. [X] quoted"),
+            Some(("  > 1. [ ] quoted".to_string(), false))
+        );
+        assert_eq!(toggle_line("not a [ ] task"), None);
+    }
+}
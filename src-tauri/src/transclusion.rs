@@ -0,0 +1,141 @@
+use crate::blocks;
+use crate::index::VaultIndex;
+use crate::vault;
+use regex::{Captures, Regex};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// Rendered notes are spliced in after comrak runs, so embeds are swapped for
+// placeholders first, like math
+const OPEN: char = '\u{E002}';
+const CLOSE: char = '\u{E003}';
+
+/// How many notes deep `![[note]]` embeds are followed.
+const MAX_DEPTH: usize = 3;
+
+fn resolve(target: &str, from: &Path, links: Option<&VaultIndex>) -> Option<PathBuf> {
+    let path = match links {
+        Some(index) => index.resolve(target, from)?,
+        None => {
+            let path = from.parent()?.join(target);
+            if vault::is_markdown(&path) {
+                path
+            } else {
+                PathBuf::from(format!("{}.md", path.to_string_lossy()))
+            }
+        }
+    };
+    (vault::is_markdown(&path) && path.is_file()).then_some(path)
+}
+
+/// The lines under `heading` up to the next heading of the same or a higher level.
+fn section(content: &str, heading: &str) -> Option<String> {
+    let wanted = heading.trim().to_lowercase();
+    let lines: Vec<&str> = content.lines().collect();
+    let (start, level) = lines.iter().enumerate().find_map(|(i, line)| {
+        let level = blocks::heading_level(line)?;
+        let text = line[level as usize..].trim().trim_end_matches('#').trim();
+        (text.to_lowercase() == wanted).then_some((i, level))
+    })?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| blocks::heading_level(line).is_some_and(|l| l <= level))
+        .map(|i| start + 1 + i)
+        .unwrap_or(lines.len());
+    Some(lines[start..end].join("\n"))
+}
+
+fn embed_error(key: &str) -> String {
+    format!(
+        "<p class=\"transclusion-error\">{}</p>",
+        crate::escape_attr(&crate::i18n::t(key))
+    )
+}
+
+fn embed_block(path: &Path, inner: &str) -> String {
+    let escaped = crate::escape_attr(&path.to_string_lossy());
+    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+    format!(
+        "<div class=\"transclusion\" data-path=\"{}\"><div class=\"transclusion-source\"><a class=\"wikilink\" href=\"#\" data-path=\"{}\">{}</a></div>{}</div>",
+        escaped,
+        escaped,
+        crate::escape_attr(&name),
+        inner
+    )
+}
+
+/// Replaces `![[note]]` / `![[note#Heading]]` embeds of markdown notes with
+/// placeholders and renders each target through `render`. `stack` holds the
+/// notes currently being rendered, to stop cycles.
+pub fn protect_embeds(
+    content: &str,
+    from: &Path,
+    links: Option<&VaultIndex>,
+    stack: &mut Vec<PathBuf>,
+    render: &mut dyn FnMut(&str, &Path, &mut Vec<PathBuf>) -> String,
+) -> (String, Vec<String>) {
+    static EMBED: OnceLock<Regex> = OnceLock::new();
+    let re = EMBED.get_or_init(|| Regex::new(r"!\[\[([^\[\]\n]+?)\]\]").unwrap());
+    let mut embeds = Vec::new();
+
+    let out = blocks::replace_outside_code(content, re, |caps: &Captures| {
+        let inner = &caps[1];
+        let target = inner.split('|').next().unwrap_or(inner);
+        let (note, heading) = target.split_once('#').unwrap_or((target, ""));
+        let Some(path) = resolve(note.trim(), from, links) else {
+            // Not a note: left for the image/PDF embed handling
+            return caps[0].to_string();
+        };
+
+        let html = if stack.contains(&path) {
+            embed_block(&path, &embed_error("embed.embeds_itself"))
+        } else if stack.len() > MAX_DEPTH {
+            embed_block(&path, &embed_error("embed.too_deep"))
+        } else {
+            crate::render_cache::depends_on(&path);
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            let body = if heading.is_empty() {
+                Some(content)
+            } else {
+                section(&content, heading)
+            };
+            match body {
+                Some(body) => {
+                    stack.push(path.clone());
+                    let rendered = render(&body, &path, stack);
+                    stack.pop();
+                    embed_block(&path, &rendered)
+                }
+                None => embed_block(&path, &embed_error("embed.heading_not_found")),
+            }
+        };
+
+        embeds.push(html);
+        format!("{}{}{}", OPEN, embeds.len() - 1, CLOSE)
+    });
+    (out, embeds)
+}
+
+/// Splices rendered embeds back in, unwrapping the paragraph comrak put
+/// around embeds that stood on their own line.
+pub fn restore_embeds(html: &str, embeds: &[String]) -> String {
+    if embeds.is_empty() {
+        return html.to_string();
+    }
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    let re = PLACEHOLDER.get_or_init(|| {
+        Regex::new("<p[^>]*>\u{E002}(\\d+)\u{E003}</p>|\u{E002}(\\d+)\u{E003}").unwrap()
+    });
+    re.replace_all(html, |caps: &Captures| {
+        let index = caps.get(1).or(caps.get(2)).map(|m| m.as_str());
+        let embed = index
+            .and_then(|i| i.parse::<usize>().ok())
+            .and_then(|i| embeds.get(i));
+        match embed {
+            Some(embed) => embed.clone(),
+            None => caps[0].to_string(),
+        }
+    })
+    .into_owned()
+}
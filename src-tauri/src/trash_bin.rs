@@ -0,0 +1,174 @@
+use crate::autosave::AutosaveState;
+use crate::files;
+use serde::Serialize;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// How long a permanent-delete confirmation stays valid.
+const CONFIRM_TTL: Duration = Duration::from_secs(60);
+
+/// Outstanding permanent-delete confirmations: token -> (path, issued at).
+#[derive(Default)]
+pub struct PermanentDeleteState {
+    pending: Mutex<HashMap<String, (PathBuf, Instant)>>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TrashedItem {
+    /// Platform identifier used to restore the item
+    id: String,
+    name: String,
+    original_path: String,
+    /// Seconds since the epoch
+    deleted_at: i64,
+}
+
+#[derive(Serialize, Clone)]
+struct TrashedEvent {
+    path: String,
+    /// The trash entry to pass to `restore_trashed` for an undo; `None` where
+    /// the trash can't be browsed
+    item: Option<TrashedItem>,
+}
+
+#[cfg(not(target_os = "macos"))]
+fn items() -> Result<Vec<(TrashedItem, trash::TrashItem)>, String> {
+    let mut items: Vec<_> = trash::os_limited::list()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|item| {
+            let info = TrashedItem {
+                id: item.id.to_string_lossy().to_string(),
+                name: item.name.to_string_lossy().to_string(),
+                original_path: item.original_path().to_string_lossy().to_string(),
+                deleted_at: item.time_deleted,
+            };
+            (info, item)
+        })
+        .collect();
+    items.sort_by_key(|(info, _)| std::cmp::Reverse(info.deleted_at));
+    Ok(items)
+}
+
+// Finder's trash has no public listing API
+#[cfg(target_os = "macos")]
+fn items() -> Result<Vec<(TrashedItem, trash::TrashItem)>, String> {
+    Err(crate::i18n::t("error.trash_browse_unsupported"))
+}
+
+/// Moves a file or folder to the OS trash and emits "file-trashed" with the
+/// trash entry, so the UI can offer an undo.
+pub fn trash_path(app: &AppHandle, path: &str) -> Result<(), String> {
+    trash::delete(path).map_err(|e| e.to_string())?;
+    app.state::<AutosaveState>().forget(Path::new(path));
+    let item = items().ok().and_then(|items| {
+        items
+            .into_iter()
+            .map(|(info, _)| info)
+            .find(|info| Path::new(&info.original_path) == Path::new(path))
+    });
+    let _ = app.emit(
+        "file-trashed",
+        TrashedEvent {
+            path: path.to_string(),
+            item,
+        },
+    );
+    Ok(())
+}
+
+/// Trashed items, newest first. With `root`, only items that were deleted
+/// from inside that folder are returned.
+#[tauri::command]
+pub fn list_trash(root: Option<String>, limit: Option<usize>) -> Result<Vec<TrashedItem>, String> {
+    Ok(items()?
+        .into_iter()
+        .map(|(info, _)| info)
+        .filter(|info| match &root {
+            Some(root) => Path::new(&info.original_path).starts_with(root),
+            None => true,
+        })
+        .take(limit.unwrap_or(100))
+        .collect())
+}
+
+/// Puts trashed items back where they were deleted from. Fails without
+/// restoring anything if one of the original paths is taken again.
+#[tauri::command]
+pub fn restore_trashed(ids: Vec<String>) -> Result<Vec<String>, String> {
+    let selected: Vec<(TrashedItem, trash::TrashItem)> = items()?
+        .into_iter()
+        .filter(|(info, _)| ids.contains(&info.id))
+        .collect();
+    if selected.len() < ids.len() {
+        return Err(crate::i18n::t("error.trash_item_gone"));
+    }
+    let paths = selected
+        .iter()
+        .map(|(info, _)| info.original_path.clone())
+        .collect();
+    restore(selected.into_iter().map(|(_, item)| item).collect())?;
+    Ok(paths)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn restore(items: Vec<trash::TrashItem>) -> Result<(), String> {
+    trash::os_limited::restore_all(items).map_err(|e| match e {
+        trash::Error::RestoreCollision { path, .. } => crate::i18n::tf(
+            "error.already_exists",
+            &[("path", &path.display().to_string())],
+        ),
+        e => e.to_string(),
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn restore(_items: Vec<trash::TrashItem>) -> Result<(), String> {
+    Err(crate::i18n::t("error.trash_restore_unsupported"))
+}
+
+/// Issues a single-use token that `delete_permanently` requires for `path`,
+/// so the frontend has to confirm with the user before anything is erased.
+#[tauri::command]
+pub fn request_permanent_delete(
+    state: State<'_, PermanentDeleteState>,
+    path: String,
+) -> Result<String, String> {
+    if !Path::new(&path).exists() {
+        return Err(crate::i18n::tf("error.does_not_exist", &[("path", &path)]));
+    }
+    let token = format!("{:016x}", RandomState::new().hash_one(&path));
+    let mut pending = state.pending.lock().unwrap();
+    pending.retain(|_, (_, issued)| issued.elapsed() < CONFIRM_TTL);
+    pending.insert(token.clone(), (PathBuf::from(path), Instant::now()));
+    Ok(token)
+}
+
+/// Deletes a file or folder without the trash, for locations the OS trash
+/// can't handle. `confirm_token` must come from `request_permanent_delete`
+/// for the same path within the last minute.
+#[tauri::command]
+pub fn delete_permanently(
+    app: AppHandle,
+    state: State<'_, PermanentDeleteState>,
+    path: String,
+    confirm_token: String,
+) -> Result<(), String> {
+    let confirmed = state
+        .pending
+        .lock()
+        .unwrap()
+        .remove(&confirm_token)
+        .is_some_and(|(p, issued)| p == Path::new(&path) && issued.elapsed() < CONFIRM_TTL);
+    if !confirmed {
+        return Err("invalid_confirmation".to_string());
+    }
+    files::remove_path(Path::new(&path)).map_err(|e| e.to_string())?;
+    app.state::<AutosaveState>().forget(Path::new(&path));
+    Ok(())
+}
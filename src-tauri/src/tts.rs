@@ -0,0 +1,143 @@
+use crate::index;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(target_os = "macos")]
+const AUDIO_EXTENSION: &str = "aiff";
+#[cfg(not(target_os = "macos"))]
+const AUDIO_EXTENSION: &str = "wav";
+
+fn run(command: &mut Command) -> Result<String, String> {
+    let output = command
+        .output()
+        .map_err(|e| crate::i18n::tf("error.tts_engine", &[("error", &e.to_string())]))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn synthesize(text_file: &Path, out: &Path, voice: Option<&str>) -> Result<(), String> {
+    // `say` drives AVSpeechSynthesizer and can write straight to a file
+    let mut cmd = Command::new("say");
+    if let Some(voice) = voice {
+        cmd.args(["-v", voice]);
+    }
+    cmd.arg("-o").arg(out).arg("-f").arg(text_file);
+    run(&mut cmd).map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+fn synthesize(text_file: &Path, out: &Path, voice: Option<&str>) -> Result<(), String> {
+    let select_voice = voice
+        .map(|v| format!("$s.SelectVoice('{}');", v.replace('\'', "''")))
+        .unwrap_or_default();
+    let script = format!(
+        "Add-Type -AssemblyName System.Speech; \
+         $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; {} \
+         $s.SetOutputToWaveFile('{}'); \
+         $s.Speak([IO.File]::ReadAllText('{}')); $s.Dispose()",
+        select_voice,
+        out.display().to_string().replace('\'', "''"),
+        text_file.display().to_string().replace('\'', "''")
+    );
+    run(Command::new("powershell").args(["-NoProfile", "-Command", &script])).map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+fn synthesize(text_file: &Path, out: &Path, voice: Option<&str>) -> Result<(), String> {
+    // speech-dispatcher only plays audio, so render with its default espeak-ng
+    // backend directly (falling back to classic espeak)
+    let mut last_error = String::new();
+    for engine in ["espeak-ng", "espeak"] {
+        let mut cmd = Command::new(engine);
+        if let Some(voice) = voice {
+            cmd.args(["-v", voice]);
+        }
+        cmd.arg("-w").arg(out).arg("-f").arg(text_file);
+        match run(&mut cmd) {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(crate::i18n::tf(
+        "error.espeak_unavailable",
+        &[("error", &last_error)],
+    ))
+}
+
+/// Lists the voice names the platform engine accepts for `export_audio`.
+#[tauri::command]
+pub fn list_tts_voices() -> Result<Vec<String>, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let out = run(Command::new("say").args(["-v", "?"]))?;
+        Ok(out
+            .lines()
+            .filter_map(|l| l.split("  ").next())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let out = run(Command::new("powershell").args([
+            "-NoProfile",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; \
+             (New-Object System.Speech.Synthesis.SpeechSynthesizer).GetInstalledVoices() | \
+             ForEach-Object { $_.VoiceInfo.Name }",
+        ]))?;
+        Ok(out.lines().map(|l| l.trim().to_string()).collect())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let out = run(Command::new("espeak-ng").arg("--voices"))?;
+        // Columns: Pty Language Age/Gender VoiceName File Other
+        Ok(out
+            .lines()
+            .skip(1)
+            .filter_map(|l| l.split_whitespace().nth(1))
+            .map(|v| v.to_string())
+            .collect())
+    }
+}
+
+/// Reads the note aloud into an audio file next to it (or at `out_path`) and
+/// returns the written path.
+#[tauri::command]
+pub async fn export_audio(
+    path: String,
+    voice: Option<String>,
+    out_path: Option<String>,
+) -> Result<String, String> {
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let text = index::plain_text(&content);
+    if text.trim().is_empty() {
+        return Err(crate::i18n::t("error.no_text"));
+    }
+
+    let out = out_path
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(&path).with_extension(AUDIO_EXTENSION));
+
+    // Unique per export, so concurrent exports don't share a text file
+    static EXPORTS: AtomicUsize = AtomicUsize::new(0);
+    let text_file = std::env::temp_dir().join(format!(
+        "marko_tts_{}_{}.txt",
+        std::process::id(),
+        EXPORTS.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&text_file, text).map_err(|e| e.to_string())?;
+    let result = synthesize(&text_file, &out, voice.as_deref().filter(|v| !v.is_empty()));
+    let _ = fs::remove_file(&text_file);
+    result?;
+
+    Ok(out.to_string_lossy().to_string())
+}
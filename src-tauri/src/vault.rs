@@ -0,0 +1,66 @@
+use crate::ignores::IgnoreRules;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn is_markdown(path: &Path) -> bool {
+    matches!(
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .as_deref(),
+        Some("md" | "markdown")
+    )
+}
+
+/// Recursively collects every file below `root`, skipping hidden files and
+/// folders the same way `read_directory` does, and anything `.markoignore`
+/// or the ignore settings exclude.
+pub fn walk_files(root: &Path) -> Vec<PathBuf> {
+    walk_files_with(root, &IgnoreRules::discover(root))
+}
+
+/// Symlinked folders are followed, but each real folder is visited once so
+/// link cycles can't recurse forever; dangling links are skipped.
+pub fn walk_files_with(root: &Path, rules: &IgnoreRules) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    let mut visited = HashSet::new();
+
+    while let Some(dir) = stack.pop() {
+        if !visited.insert(fs::canonicalize(&dir).unwrap_or_else(|_| dir.clone())) {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            if !path.exists() {
+                continue;
+            }
+            let is_dir = path.is_dir();
+            if rules.is_ignored(&path, is_dir) {
+                continue;
+            }
+            if is_dir {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+pub fn markdown_files(root: &Path) -> Vec<PathBuf> {
+    walk_files(root)
+        .into_iter()
+        .filter(|p| is_markdown(p))
+        .collect()
+}
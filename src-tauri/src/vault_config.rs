@@ -0,0 +1,118 @@
+use crate::attachments::AttachmentLocation;
+use crate::listing::DirectoryListingState;
+use crate::{ignores, index, store};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// The folder marking a vault, holding its config.
+const VAULT_DIR: &str = ".marko";
+const CONFIG_FILE: &str = "config.json";
+
+/// Settings of one vault, read from `.marko/config.json`. Whatever is set
+/// here wins over the app-wide setting.
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct VaultConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment_location: Option<AttachmentLocation>,
+    /// Replace the global ignore patterns; `.markoignore` still applies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_patterns: Option<Vec<String>>,
+    /// Note new files start from, relative to the vault root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_template: Option<String>,
+}
+
+pub fn is_vault(dir: &Path) -> bool {
+    dir.join(VAULT_DIR).is_dir()
+}
+
+/// The nearest folder at or above `path` that has a `.marko/` folder.
+pub fn vault_root(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|dir| is_vault(dir))
+}
+
+pub fn load(root: &Path) -> VaultConfig {
+    fs::read_to_string(root.join(VAULT_DIR).join(CONFIG_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// The vault `path` is in and its config, if it's in one.
+pub fn for_path(path: &Path) -> Option<(PathBuf, VaultConfig)> {
+    let root = vault_root(path)?;
+    Some((root.to_path_buf(), load(root)))
+}
+
+/// Whether a changed path is a vault config, so cached settings derived from
+/// it can be rebuilt.
+pub fn is_config_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == CONFIG_FILE)
+        && path
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .is_some_and(|name| name == VAULT_DIR)
+}
+
+#[tauri::command]
+pub fn get_vault_config(root: String) -> VaultConfig {
+    load(Path::new(&root))
+}
+
+/// Writes `.marko/config.json` into `root`, making it a vault if it wasn't.
+#[tauri::command]
+pub fn set_vault_config(app: AppHandle, root: String, config: VaultConfig) -> Result<(), String> {
+    if let Some(patterns) = &config.ignore_patterns {
+        ignores::validate(patterns)?;
+    }
+    let dir = Path::new(&root).join(VAULT_DIR);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    store::write_atomic(&dir.join(CONFIG_FILE), content)?;
+    // The ignore patterns may have changed
+    app.state::<index::IndexState>().invalidate();
+    app.state::<DirectoryListingState>().clear();
+    Ok(())
+}
+
+/// The vault `path` belongs to, for the frontend to pick up its config.
+#[tauri::command]
+pub fn find_vault_root(path: String) -> Option<String> {
+    vault_root(Path::new(&path)).map(|root| root.to_string_lossy().to_string())
+}
+
+/// Initial content for a new note at `path`: its vault's default template,
+/// or nothing.
+#[tauri::command]
+pub fn new_note_content(path: String) -> Result<String, String> {
+    let Some((root, config)) = for_path(Path::new(&path)) else {
+        return Ok(String::new());
+    };
+    let Some(template) = config.default_template else {
+        return Ok(String::new());
+    };
+    let title = Path::new(&path)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    // Absolute paths and `..` could otherwise read any file on disk
+    let template = root
+        .join(template)
+        .canonicalize()
+        .map_err(|e| e.to_string())?;
+    if !root
+        .canonicalize()
+        .is_ok_and(|root| template.starts_with(root))
+    {
+        return Err(crate::i18n::t("error.template_outside_vault"));
+    }
+    let content = fs::read_to_string(template).map_err(|e| e.to_string())?;
+    Ok(content.replace("{{title}}", &title).replace(
+        "{{date}}",
+        &chrono::Local::now().format("%Y-%m-%d").to_string(),
+    ))
+}
@@ -0,0 +1,192 @@
+use crate::attachments;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SizedSample};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Instant;
+use tauri::{AppHandle, State};
+
+type WavWriter = Arc<Mutex<Option<hound::WavWriter<BufWriter<File>>>>>;
+
+struct Recording {
+    note_path: String,
+    out_path: PathBuf,
+    started: Instant,
+    stop: mpsc::Sender<()>,
+    worker: JoinHandle<Result<(), String>>,
+}
+
+#[derive(Default)]
+pub struct RecordingState {
+    current: Mutex<Option<Recording>>,
+}
+
+#[derive(Serialize)]
+pub struct RecordingStatus {
+    note_path: String,
+    out_path: String,
+    elapsed_secs: u64,
+}
+
+fn write_samples<T>(data: &[T], writer: &WavWriter)
+where
+    T: Sample,
+    i16: FromSample<T>,
+{
+    if let Some(writer) = writer.lock().unwrap().as_mut() {
+        for &sample in data {
+            let _ = writer.write_sample(sample.to_sample::<i16>());
+        }
+    }
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    writer: WavWriter,
+) -> Result<cpal::Stream, String>
+where
+    T: SizedSample,
+    i16: FromSample<T>,
+{
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &_| write_samples(data, &writer),
+            |e| log::error!("Audio input error: {}", e),
+            None,
+        )
+        .map_err(|e| e.to_string())
+}
+
+// cpal streams can't move between threads, so the stream lives on its own
+// thread until a stop signal arrives
+fn record(
+    out_path: PathBuf,
+    ready: mpsc::Sender<Result<(), String>>,
+    stop: mpsc::Receiver<()>,
+) -> Result<(), String> {
+    let setup = || -> Result<(cpal::Stream, WavWriter), String> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or_else(|| crate::i18n::t("error.no_microphone"))?;
+        let config = device.default_input_config().map_err(|e| e.to_string())?;
+        let spec = hound::WavSpec {
+            channels: config.channels(),
+            sample_rate: config.sample_rate().0,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let writer: WavWriter = Arc::new(Mutex::new(Some(
+            hound::WavWriter::create(&out_path, spec).map_err(|e| e.to_string())?,
+        )));
+
+        let stream_config = config.config();
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &stream_config, writer.clone()),
+            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &stream_config, writer.clone()),
+            cpal::SampleFormat::U16 => build_stream::<u16>(&device, &stream_config, writer.clone()),
+            format => Err(crate::i18n::tf(
+                "error.unsupported_sample_format",
+                &[("format", &format.to_string())],
+            )),
+        }?;
+        stream.play().map_err(|e| e.to_string())?;
+        Ok((stream, writer))
+    };
+
+    let (stream, writer) = match setup() {
+        Ok(recording) => recording,
+        Err(e) => {
+            let _ = fs::remove_file(&out_path);
+            let _ = ready.send(Err(e.clone()));
+            return Err(e);
+        }
+    };
+    let _ = ready.send(Ok(()));
+
+    let _ = stop.recv();
+    drop(stream);
+    let writer = writer.lock().unwrap().take();
+    match writer {
+        Some(writer) => writer.finalize().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Starts recording a voice memo for a note into its attachment folder and
+/// returns the path of the audio file being written.
+#[tauri::command]
+pub fn record_audio(
+    app: AppHandle,
+    state: State<'_, RecordingState>,
+    note_path: String,
+) -> Result<String, String> {
+    let mut current = state.current.lock().unwrap();
+    if current.is_some() {
+        return Err(crate::i18n::t("error.recording_in_progress"));
+    }
+
+    let dir = attachments::attachment_dir(&app, Path::new(&note_path), None);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let out_path = dir.join(format!(
+        "Recording {}.wav",
+        chrono::Local::now().format("%Y%m%d%H%M%S")
+    ));
+
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let (stop_tx, stop_rx) = mpsc::channel();
+    let worker_path = out_path.clone();
+    let worker = std::thread::spawn(move || record(worker_path, ready_tx, stop_rx));
+    ready_rx
+        .recv()
+        .map_err(|_| crate::i18n::t("error.recording_failed"))??;
+
+    *current = Some(Recording {
+        note_path,
+        out_path: out_path.clone(),
+        started: Instant::now(),
+        stop: stop_tx,
+        worker,
+    });
+    Ok(out_path.to_string_lossy().to_string())
+}
+
+/// Stops the current recording and returns the markdown embed for the note.
+#[tauri::command]
+pub fn stop_audio_recording(state: State<'_, RecordingState>) -> Result<String, String> {
+    let recording = state
+        .current
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| crate::i18n::t("error.no_recording"))?;
+    let _ = recording.stop.send(());
+    recording
+        .worker
+        .join()
+        .map_err(|_| crate::i18n::t("error.recording_thread"))??;
+
+    Ok(attachments::embed_for(
+        Path::new(&recording.note_path),
+        &recording.out_path,
+    ))
+}
+
+#[tauri::command]
+pub fn get_recording_status(state: State<'_, RecordingState>) -> Option<RecordingStatus> {
+    state
+        .current
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|recording| RecordingStatus {
+            note_path: recording.note_path.clone(),
+            out_path: recording.out_path.to_string_lossy().to_string(),
+            elapsed_secs: recording.started.elapsed().as_secs(),
+        })
+}
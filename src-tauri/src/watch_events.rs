@@ -0,0 +1,174 @@
+use crate::store;
+use notify::event::{DataChange, ModifyKind, RenameMode};
+use notify::{EventKind, RecommendedWatcher};
+use notify_debouncer_full::{DebouncedEvent, Debouncer, FileIdMap};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+
+const WATCH_SETTINGS_FILE: &str = "watcher.json";
+
+/// A watcher whose events are delivered in batches once the disk goes quiet.
+pub type DebouncedWatcher = Debouncer<RecommendedWatcher, FileIdMap>;
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct WatchSettings {
+    /// How long to collect changes before emitting them as one batch
+    debounce_ms: u64,
+}
+
+impl Default for WatchSettings {
+    fn default() -> Self {
+        WatchSettings { debounce_ms: 300 }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Create,
+    Modify,
+    Remove,
+    Rename,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ChangeEvent {
+    pub kind: ChangeKind,
+    pub paths: Vec<String>,
+}
+
+/// Payload of "file-changed" and "folder-changed": every change seen during
+/// one debounce window, in order.
+#[derive(Serialize, Clone)]
+pub struct ChangeBatch {
+    /// The folder watch the changes came from; absent for the file watcher
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub changes: Vec<ChangeEvent>,
+}
+
+/// Payload of "file-renamed", so open tabs can follow the file.
+#[derive(Serialize, Clone)]
+pub struct RenameEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub from: String,
+    pub to: String,
+}
+
+/// Payload of "file-deleted", sent instead of "file-changed" when the
+/// watched file is gone.
+#[derive(Serialize, Clone)]
+pub struct DeletedEvent {
+    pub path: String,
+}
+
+impl ChangeBatch {
+    /// A single modification of `path`, for changes the app makes itself.
+    pub fn modified(path: &str) -> ChangeBatch {
+        ChangeBatch {
+            id: None,
+            changes: vec![ChangeEvent {
+                kind: ChangeKind::Modify,
+                paths: vec![path.to_string()],
+            }],
+        }
+    }
+}
+
+/// What a notify event means for the UI. Reads (access events) are `None`.
+pub fn change_kind(kind: &EventKind) -> Option<ChangeKind> {
+    match kind {
+        EventKind::Access(_) => None,
+        EventKind::Create(_) => Some(ChangeKind::Create),
+        EventKind::Remove(_) => Some(ChangeKind::Remove),
+        EventKind::Modify(ModifyKind::Name(_)) => Some(ChangeKind::Rename),
+        // Backends that can't tell what happened still changed something
+        EventKind::Modify(_) | EventKind::Any | EventKind::Other => Some(ChangeKind::Modify),
+    }
+}
+
+/// The batch to emit for debounced events, or `None` if nothing changed.
+pub fn batch(id: Option<String>, events: &[DebouncedEvent]) -> Option<ChangeBatch> {
+    let changes: Vec<ChangeEvent> = events
+        .iter()
+        .filter(|event| !event.paths.is_empty())
+        .filter_map(|event| {
+            Some(ChangeEvent {
+                kind: change_kind(&event.kind)?,
+                paths: event
+                    .paths
+                    .iter()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .collect(),
+            })
+        })
+        .collect();
+    (!changes.is_empty()).then_some(ChangeBatch { id, changes })
+}
+
+/// Whether `path` is only written to be renamed over the real file:
+/// `store::write_atomic`'s `.name.tmp`, or an editor's swap or backup file.
+fn is_temp_file(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    name.ends_with(".tmp") || name.ends_with(".swp") || name.ends_with('~')
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+/// Turns atomic saves, where a temp or hidden file is renamed over the real
+/// one, into a modification of the real file, and drops the temp file's own
+/// events, so saving a note isn't reported as a rename.
+pub fn fold_saves(events: &mut Vec<DebouncedEvent>) {
+    for event in events.iter_mut() {
+        if event.kind != EventKind::Modify(ModifyKind::Name(RenameMode::Both)) {
+            continue;
+        }
+        let [from, to] = event.paths.as_slice() else {
+            continue;
+        };
+        if is_temp_file(from) || (is_hidden(from) && !is_hidden(to)) {
+            event.paths = vec![to.clone()];
+            event.kind = EventKind::Modify(ModifyKind::Data(DataChange::Any));
+        }
+    }
+    events.retain(|event| event.paths.is_empty() || !event.paths.iter().all(|p| is_temp_file(p)));
+}
+
+/// Renames whose old and new path were both seen, as `(from, to)`. A file
+/// moved in or out of the watched folder only shows up as a rename in the
+/// batch.
+pub fn renames(events: &[DebouncedEvent]) -> Vec<(PathBuf, PathBuf)> {
+    events
+        .iter()
+        .filter(|event| event.kind == EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+        .filter_map(|event| match event.paths.as_slice() {
+            [from, to] => Some((from.clone(), to.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn debounce_window(app: &AppHandle) -> Duration {
+    let settings: WatchSettings = store::load_json(app, WATCH_SETTINGS_FILE);
+    // A zero window would make the debouncer spin
+    Duration::from_millis(settings.debounce_ms.clamp(10, 10_000))
+}
+
+#[tauri::command]
+pub fn get_watch_debounce(app: AppHandle) -> u64 {
+    debounce_window(&app).as_millis() as u64
+}
+
+/// Sets the debounce window in milliseconds. Watches started afterwards use
+/// it; running ones keep the window they were started with.
+#[tauri::command]
+pub fn set_watch_debounce(app: AppHandle, debounce_ms: u64) -> Result<(), String> {
+    store::save_json(&app, WATCH_SETTINGS_FILE, &WatchSettings { debounce_ms })
+}
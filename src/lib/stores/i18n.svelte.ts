@@ -0,0 +1,23 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export class I18nStore {
+	strings = $state<Record<string, string>>({});
+
+	async load() {
+		try {
+			this.strings = await invoke('get_translations');
+		} catch (e) {
+			console.error('Failed to load translations:', e);
+		}
+	}
+
+	t(key: string, args: Record<string, string> = {}) {
+		let text = this.strings[key] ?? key;
+		for (const [name, value] of Object.entries(args)) {
+			text = text.replaceAll(`{${name}}`, value);
+		}
+		return text;
+	}
+}
+
+export const i18n = new I18nStore();